@@ -37,6 +37,8 @@ cfg_target_has_atomic! {
 
     #[cfg(feature = "alloc")]
     mod lock;
+    #[cfg(feature = "alloc")]
+    mod loom;
     #[cfg(feature = "std")]
     pub mod mpsc;
     #[cfg(feature = "alloc")]
@@ -7,7 +7,8 @@
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
 use core::sync::atomic::Ordering::SeqCst;
-use core::sync::atomic::AtomicBool;
+
+use crate::loom::AtomicBool;
 
 /// A "mutex" around a value, similar to `std::sync::Mutex<T>`.
 ///
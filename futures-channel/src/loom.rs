@@ -0,0 +1,131 @@
+//! The synchronization primitives used by the channels.
+//!
+//! Building with `RUSTFLAGS="--cfg loom"` swaps them for the model-checked
+//! versions from [loom], so that the tests in `tests/loom_*.rs` can explore
+//! every interleaving of the threads using a channel.
+//!
+//! [loom]: https://docs.rs/loom
+
+#![allow(unused_imports)]
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+#[cfg(not(loom))]
+pub(crate) use alloc::sync::Arc;
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use std::{sync::Mutex, thread};
+
+#[cfg(loom)]
+pub(crate) use ::loom::sync::atomic::{fence, AtomicPtr};
+#[cfg(loom)]
+pub(crate) use ::loom::sync::{Arc, Mutex};
+#[cfg(loom)]
+pub(crate) use ::loom::thread;
+
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use futures_core::task::__internal::AtomicWaker;
+
+// loom's `AtomicWaker` registers wakers by reference with `register_by_ref`.
+#[cfg(loom)]
+#[derive(Debug)]
+pub(crate) struct AtomicWaker(::loom::future::AtomicWaker);
+
+#[cfg(loom)]
+impl AtomicWaker {
+    pub(crate) fn new() -> Self {
+        Self(::loom::future::AtomicWaker::new())
+    }
+
+    pub(crate) fn register(&self, waker: &core::task::Waker) {
+        self.0.register_by_ref(waker)
+    }
+
+    pub(crate) fn wake(&self) {
+        self.0.wake()
+    }
+}
+
+// loom treats `SeqCst` accesses as if they were `AcqRel`, so it reports
+// races that cannot happen when two threads each write one `SeqCst` atomic
+// and then read the other's, which the channels rely on. It does model
+// `SeqCst` fences, so these wrappers put one before each `SeqCst` load and
+// after each `SeqCst` write.
+#[cfg(loom)]
+mod seq_cst {
+    use ::loom::sync::atomic::{self, fence};
+    use core::sync::atomic::Ordering::{self, SeqCst};
+
+    fn fence_if_seq_cst(order: Ordering) {
+        if order == SeqCst {
+            fence(SeqCst);
+        }
+    }
+
+    #[derive(Debug)]
+    pub(crate) struct AtomicBool(atomic::AtomicBool);
+
+    impl AtomicBool {
+        pub(crate) fn new(v: bool) -> Self {
+            Self(atomic::AtomicBool::new(v))
+        }
+
+        pub(crate) fn load(&self, order: Ordering) -> bool {
+            fence_if_seq_cst(order);
+            self.0.load(order)
+        }
+
+        pub(crate) fn store(&self, v: bool, order: Ordering) {
+            self.0.store(v, order);
+            fence_if_seq_cst(order);
+        }
+
+        pub(crate) fn swap(&self, v: bool, order: Ordering) -> bool {
+            let prev = self.0.swap(v, order);
+            fence_if_seq_cst(order);
+            prev
+        }
+    }
+
+    #[derive(Debug)]
+    pub(crate) struct AtomicUsize(atomic::AtomicUsize);
+
+    impl AtomicUsize {
+        pub(crate) fn new(v: usize) -> Self {
+            Self(atomic::AtomicUsize::new(v))
+        }
+
+        pub(crate) fn load(&self, order: Ordering) -> usize {
+            fence_if_seq_cst(order);
+            self.0.load(order)
+        }
+
+        pub(crate) fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<usize, usize> {
+            let res = self.0.compare_exchange(current, new, success, failure);
+            if res.is_ok() {
+                fence_if_seq_cst(success);
+            }
+            res
+        }
+
+        pub(crate) fn fetch_sub(&self, v: usize, order: Ordering) -> usize {
+            let prev = self.0.fetch_sub(v, order);
+            fence_if_seq_cst(order);
+            prev
+        }
+
+        pub(crate) fn fetch_and(&self, v: usize, order: Ordering) -> usize {
+            let prev = self.0.fetch_and(v, order);
+            fence_if_seq_cst(order);
+            prev
+        }
+    }
+}
+
+#[cfg(loom)]
+pub(crate) use self::seq_cst::{AtomicBool, AtomicUsize};
@@ -80,13 +80,11 @@
 
 use futures_core::stream::{FusedStream, Stream};
 use futures_core::task::{Context, Poll, Waker};
-use futures_core::task::__internal::AtomicWaker;
 use std::fmt;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
 
+use crate::loom::{fence, Arc, AtomicUsize, AtomicWaker, Mutex};
 use crate::mpsc::queue::Queue;
 
 mod queue;
@@ -626,7 +624,9 @@ impl<T> BoundedSenderInner<T> {
         self.inner.parked_queue.push(t);
 
         // Check to make sure we weren't closed after we sent our task on the
-        // queue
+        // queue. The fence pairs with the one in `Receiver::close`, so that
+        // either this sees the channel closed, or the receiver sees the task.
+        fence(SeqCst);
         let state = decode_state(self.inner.state.load(SeqCst));
         self.maybe_parked = state.is_open;
     }
@@ -1005,6 +1005,7 @@ impl<T> Receiver<T> {
     pub fn close(&mut self) {
         if let Some(inner) = &mut self.inner {
             inner.set_closed();
+            fence(SeqCst);
 
             // Wake up any threads waiting as they'll see that we've closed the
             // channel and will continue on their merry way.
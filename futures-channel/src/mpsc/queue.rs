@@ -43,10 +43,11 @@
 
 pub(super) use self::PopResult::*;
 
-use std::thread;
 use std::cell::UnsafeCell;
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::atomic::Ordering;
+
+use crate::loom::{thread, AtomicPtr};
 
 /// A result of the `pop` function.
 pub(super) enum PopResult<T> {
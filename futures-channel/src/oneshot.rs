@@ -2,15 +2,14 @@
 //!
 //! This is a single-producer, single-consumer channel.
 
-use alloc::sync::Arc;
 use core::fmt;
 use core::pin::Pin;
-use core::sync::atomic::AtomicBool;
 use core::sync::atomic::Ordering::SeqCst;
 use futures_core::future::{Future, FusedFuture};
 use futures_core::task::{Context, Poll, Waker};
 
 use crate::lock::Lock;
+use crate::loom::{Arc, AtomicBool};
 
 /// A future for a value that will be provided by another asynchronous task.
 ///
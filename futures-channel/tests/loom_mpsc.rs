@@ -0,0 +1,119 @@
+//! Model checks the races between the ends of `mpsc` channels.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=2 cargo test --release --test loom_mpsc`.
+
+#![cfg(loom)]
+
+use futures::channel::mpsc;
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use loom::future::block_on;
+use loom::thread;
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let (mut tx, mut rx) = mpsc::channel(1);
+
+        let t = thread::spawn(move || {
+            block_on(tx.send(1)).unwrap();
+            block_on(tx.send(2)).unwrap();
+        });
+
+        assert_eq!(block_on(rx.next()), Some(1));
+        assert_eq!(block_on(rx.next()), Some(2));
+        assert_eq!(block_on(rx.next()), None);
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn parked_senders() {
+    loom::model(|| {
+        let (mut tx1, mut rx) = mpsc::channel(0);
+        let mut tx2 = tx1.clone();
+
+        let t1 = thread::spawn(move || block_on(tx1.send(1)).unwrap());
+        let t2 = thread::spawn(move || block_on(tx2.send(2)).unwrap());
+
+        let mut items = vec![block_on(rx.next()).unwrap(), block_on(rx.next()).unwrap()];
+        items.sort_unstable();
+        assert_eq!(items, [1, 2]);
+        assert_eq!(block_on(rx.next()), None);
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+}
+
+#[test]
+fn unbounded_senders() {
+    loom::model(|| {
+        let (tx1, mut rx) = mpsc::unbounded();
+        let tx2 = tx1.clone();
+
+        let t = thread::spawn(move || tx1.unbounded_send(1).unwrap());
+        tx2.unbounded_send(2).unwrap();
+        drop(tx2);
+
+        let mut items = vec![block_on(rx.next()).unwrap(), block_on(rx.next()).unwrap()];
+        items.sort_unstable();
+        assert_eq!(items, [1, 2]);
+        assert_eq!(block_on(rx.next()), None);
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn close_while_sending() {
+    loom::model(|| {
+        let (mut tx, mut rx) = mpsc::channel(1);
+
+        let t = thread::spawn(move || match tx.try_send(1) {
+            Ok(()) => true,
+            Err(e) => {
+                assert!(e.is_disconnected());
+                false
+            }
+        });
+
+        rx.close();
+        let received = block_on(rx.next());
+        let sent = t.join().unwrap();
+        // A message that was accepted is still delivered after closing.
+        if sent {
+            assert_eq!(received, Some(1));
+            assert_eq!(block_on(rx.next()), None);
+        } else {
+            assert_eq!(received, None);
+        }
+    });
+}
+
+#[test]
+fn drop_receiver_while_sending() {
+    loom::model(|| {
+        let (mut tx, rx) = mpsc::channel::<i32>(0);
+
+        let t = thread::spawn(move || {
+            // Either fails, or completes because the message was taken out
+            // of the channel when the receiver was dropped.
+            let _ = block_on(tx.send(1));
+            assert!(block_on(tx.send(2)).is_err());
+        });
+
+        drop(rx);
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn drop_sender_while_receiving() {
+    loom::model(|| {
+        let (tx, mut rx) = mpsc::unbounded::<i32>();
+
+        let t = thread::spawn(move || drop(tx));
+
+        assert_eq!(block_on(rx.next()), None);
+        t.join().unwrap();
+    });
+}
@@ -0,0 +1,45 @@
+//! Model checks the races between the ends of `oneshot` channels.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=2 cargo test --release --test loom_oneshot`.
+
+#![cfg(loom)]
+
+use futures::channel::oneshot;
+use loom::future::block_on;
+use loom::thread;
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let (tx, rx) = oneshot::channel();
+
+        let t = thread::spawn(move || tx.send(1).unwrap());
+
+        assert_eq!(block_on(rx), Ok(1));
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn drop_sender_while_receiving() {
+    loom::model(|| {
+        let (tx, rx) = oneshot::channel::<i32>();
+
+        let t = thread::spawn(move || drop(tx));
+
+        assert_eq!(block_on(rx), Err(oneshot::Canceled));
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn drop_receiver_while_waiting_for_cancellation() {
+    loom::model(|| {
+        let (mut tx, rx) = oneshot::channel::<i32>();
+
+        let t = thread::spawn(move || block_on(tx.cancellation()));
+
+        drop(rx);
+        t.join().unwrap();
+    });
+}
@@ -30,8 +30,28 @@
 //! [`LocalPool`] is best suited for running I/O-bound tasks that do relatively
 //! little work between I/O operations.
 //!
+//! Both [`LocalPool`] and [`block_on`] block the thread through a [`Park`]
+//! driver while they have nothing to do. A custom driver can wait for OS
+//! events instead, allowing an I/O reactor to run on the executor thread.
+//!
 //! There is also a convenience function [`block_on`] for simply running a
-//! future to completion on the current thread.
+//! future to completion on the current thread, and [`block_on_timeout`] for
+//! giving up on it after a while.
+//!
+//! # Timers
+//!
+//! With the `timer` feature enabled, [`Delay`] and [`Interval`] provide
+//! futures and streams that complete at a given `Instant`. They are driven by
+//! a shared background thread and can be awaited on any executor.
+//! [`SystemTimer`] plugs them into the time-based combinators of
+//! `FutureExt` and `StreamExt`.
+//!
+//! # Sockets
+//!
+//! With the `net` feature enabled on Linux, [`TcpListener`], [`TcpStream`],
+//! [`UdpSocket`] and [`UnixStream`] implement the `futures-io` traits on top
+//! of a shared background epoll thread. Like the timers, they can be used
+//! with [`block_on`], [`LocalPool`] or a `ThreadPool`.
 //!
 //! [`spawn_obj`]: https://docs.rs/futures/0.3/futures/task/trait.Spawn.html#tymethod.spawn_obj
 //! [`spawn_local_obj`]: https://docs.rs/futures/0.3/futures/task/trait.LocalSpawn.html#tymethod.spawn_local_obj
@@ -49,7 +69,15 @@
 #[cfg(feature = "std")]
 mod local_pool;
 #[cfg(feature = "std")]
-pub use crate::local_pool::{block_on, block_on_stream, BlockingStream, LocalPool, LocalSpawner};
+pub use crate::local_pool::{
+    block_on, block_on_stream, block_on_timeout, block_on_with_park, BlockingStream, LocalPool,
+    LocalSpawner, Timeout,
+};
+
+#[cfg(feature = "std")]
+mod park;
+#[cfg(feature = "std")]
+pub use crate::park::{Park, ThreadPark, ThreadUnpark};
 
 #[cfg(feature = "thread-pool")]
 #[cfg(feature = "std")]
@@ -63,6 +91,24 @@ mod thread_pool;
 #[cfg(feature = "std")]
 pub use crate::thread_pool::{ThreadPool, ThreadPoolBuilder};
 
+#[cfg(feature = "timer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[cfg(feature = "std")]
+mod timer;
+#[cfg(feature = "timer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[cfg(feature = "std")]
+pub use crate::timer::{Delay, Interval, MissedTickBehavior, SystemTimer};
+
+#[cfg(feature = "net")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[cfg(target_os = "linux")]
+mod net;
+#[cfg(feature = "net")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[cfg(target_os = "linux")]
+pub use crate::net::{Incoming, TcpListener, TcpStream, UdpSocket, UnixStream};
+
 #[cfg(feature = "std")]
 mod enter;
 #[cfg(feature = "std")]
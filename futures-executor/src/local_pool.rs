@@ -1,17 +1,19 @@
 use crate::enter;
+use crate::park::{Park, ThreadPark};
 use futures_core::future::Future;
 use futures_core::stream::Stream;
 use futures_core::task::{Context, Poll};
-use futures_task::{waker_ref, ArcWake};
+use futures_task::waker_ref;
 use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
 use futures_util::pin_mut;
 use futures_util::stream::FuturesUnordered;
 use futures_util::stream::StreamExt;
 use std::cell::RefCell;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::rc::{Rc, Weak};
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
 
 /// A single-threaded task pool for polling futures to completion.
 ///
@@ -24,8 +26,18 @@ use std::thread::{self, Thread};
 /// [`spawner()`](LocalPool::spawner) method. Because the executor is
 /// single-threaded, it supports a special form of task spawning for non-`Send`
 /// futures, via [`spawn_local_obj`](futures_task::LocalSpawn::spawn_local_obj).
+///
+/// While all tasks are pending, the pool blocks the thread through a [`Park`]
+/// driver; by default a [`ThreadPark`]. Use [`with_park`](LocalPool::with_park)
+/// to let another driver, such as an I/O reactor, run while the pool is idle.
 #[derive(Debug)]
-pub struct LocalPool {
+pub struct LocalPool<P = ThreadPark> {
+    tasks: Tasks,
+    park: P,
+}
+
+#[derive(Debug)]
+struct Tasks {
     pool: FuturesUnordered<LocalFutureObj<'static, ()>>,
     incoming: Rc<Incoming>,
 }
@@ -39,96 +51,105 @@ pub struct LocalSpawner {
 
 type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
 
-pub(crate) struct ThreadNotify {
-    /// The (single) executor thread.
-    thread: Thread,
-    /// A flag to ensure a wakeup (i.e. `unpark()`) is not "forgotten"
-    /// before the next `park()`, which may otherwise happen if the code
-    /// being executed as part of the future(s) being polled makes use of
-    /// park / unpark calls of its own, i.e. we cannot assume that no other
-    /// code uses park / unpark on the executing `thread`.
-    unparked: AtomicBool,
-}
-
-thread_local! {
-    static CURRENT_THREAD_NOTIFY: Arc<ThreadNotify> = Arc::new(ThreadNotify {
-        thread: thread::current(),
-        unparked: AtomicBool::new(false),
-    });
-}
-
-impl ArcWake for ThreadNotify {
-    fn wake_by_ref(arc_self: &Arc<Self>) {
-        // Make sure the wakeup is remembered until the next `park()`.
-        let unparked = arc_self.unparked.swap(true, Ordering::Relaxed);
-        if !unparked {
-            // If the thread has not been unparked yet, it must be done
-            // now. If it was actually parked, it will run again,
-            // otherwise the token made available by `unpark`
-            // may be consumed before reaching `park()`, but `unparked`
-            // ensures it is not forgotten.
-            arc_self.thread.unpark();
-        }
+// Set up and run a basic single-threaded spawner loop, invoking `f` on each
+// turn.
+fn run_executor<P: Park, T, F: FnMut(&mut Context<'_>) -> Poll<T>>(park: P, f: F) -> T {
+    match run_executor_until(park, None, f) {
+        Some(t) => t,
+        None => unreachable!(),
     }
 }
 
-// Set up and run a basic single-threaded spawner loop, invoking `f` on each
-// turn.
-fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
+// Like `run_executor`, but gives up and returns `None` once `deadline` has
+// passed, parking with a timeout in between turns.
+fn run_executor_until<P: Park, T, F: FnMut(&mut Context<'_>) -> Poll<T>>(
+    mut park: P,
+    deadline: Option<Instant>,
+    mut f: F,
+) -> Option<T> {
     let _enter = enter().expect(
         "cannot execute `LocalPool` executor from within \
          another executor",
     );
 
-    CURRENT_THREAD_NOTIFY.with(|thread_notify| {
-        let waker = waker_ref(thread_notify);
-        let mut cx = Context::from_waker(&waker);
-        loop {
-            if let Poll::Ready(t) = f(&mut cx) {
-                return t;
-            }
-            // Consume the wakeup that occurred while executing `f`, if any.
-            let unparked = thread_notify.unparked.swap(false, Ordering::Acquire);
-            if !unparked {
-                // No wakeup occurred. It may occur now, right before parking,
-                // but in that case the token made available by `unpark()`
-                // is guaranteed to still be available and `park()` is a no-op.
-                thread::park();
-                // When the thread is unparked, `unparked` will have been set
-                // and needs to be unset before the next call to `f` to avoid
-                // a redundant loop iteration.
-                thread_notify.unparked.store(false, Ordering::Release);
+    let unpark = park.unpark();
+    let waker = waker_ref(&unpark);
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(t) = f(&mut cx) {
+            return Some(t);
+        }
+        match deadline {
+            None => park.park(),
+            Some(deadline) => {
+                // Check the deadline before parking, so that a task which
+                // keeps waking itself cannot keep us past it.
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                park.park_timeout(deadline - now);
             }
         }
-    })
+    }
 }
 
-fn poll_executor<T, F: FnMut(&mut Context<'_>) -> T>(mut f: F) -> T {
+fn poll_executor<P: Park, T, F: FnMut(&mut Context<'_>) -> T>(park: &P, mut f: F) -> T {
     let _enter = enter().expect(
         "cannot execute `LocalPool` executor from within \
          another executor",
     );
 
-    CURRENT_THREAD_NOTIFY.with(|thread_notify| {
-        let waker = waker_ref(thread_notify);
-        let mut cx = Context::from_waker(&waker);
-        f(&mut cx)
-    })
+    let unpark = park.unpark();
+    let waker = waker_ref(&unpark);
+    let mut cx = Context::from_waker(&waker);
+    f(&mut cx)
 }
 
 impl LocalPool {
     /// Create a new, empty pool of tasks.
     pub fn new() -> Self {
+        Self::with_park(ThreadPark::new())
+    }
+}
+
+impl<P: Park> LocalPool<P> {
+    /// Create a new, empty pool of tasks that blocks through the given
+    /// [`Park`] driver while it has nothing to do.
+    ///
+    /// ```
+    /// use futures::executor::{LocalPool, ThreadPark};
+    ///
+    /// let mut pool = LocalPool::with_park(ThreadPark::new());
+    /// pool.run_until(async {});
+    /// ```
+    ///
+    /// Passing `&mut driver` allows the driver to be used again after the
+    /// pool is dropped.
+    pub fn with_park(park: P) -> Self {
         Self {
-            pool: FuturesUnordered::new(),
-            incoming: Default::default(),
+            tasks: Tasks {
+                pool: FuturesUnordered::new(),
+                incoming: Default::default(),
+            },
+            park,
         }
     }
 
+    /// Returns a reference to the [`Park`] driver of this pool.
+    pub fn get_park(&self) -> &P {
+        &self.park
+    }
+
+    /// Returns a mutable reference to the [`Park`] driver of this pool.
+    pub fn get_park_mut(&mut self) -> &mut P {
+        &mut self.park
+    }
+
     /// Get a clonable handle to the pool as a [`Spawn`].
     pub fn spawner(&self) -> LocalSpawner {
         LocalSpawner {
-            incoming: Rc::downgrade(&self.incoming),
+            incoming: Rc::downgrade(&self.tasks.incoming),
         }
     }
 
@@ -148,7 +169,8 @@ impl LocalPool {
     /// The function will block the calling thread until *all* tasks in the pool
     /// are complete, including any spawned while running existing tasks.
     pub fn run(&mut self) {
-        run_executor(|cx| self.poll_pool(cx))
+        let tasks = &mut self.tasks;
+        run_executor(&mut self.park, |cx| tasks.poll_pool(cx))
     }
 
     /// Runs all the tasks in the pool until the given future completes.
@@ -170,8 +192,9 @@ impl LocalPool {
     /// however, all tasks in the pool will try to make progress.
     pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
         pin_mut!(future);
+        let tasks = &mut self.tasks;
 
-        run_executor(|cx| {
+        run_executor(&mut self.park, |cx| {
             {
                 // if our main task is done, so are we
                 let result = future.as_mut().poll(cx);
@@ -180,11 +203,57 @@ impl LocalPool {
                 }
             }
 
-            let _ = self.poll_pool(cx);
+            let _ = tasks.poll_pool(cx);
             Poll::Pending
         })
     }
 
+    /// Runs all the tasks in the pool until the given future completes or
+    /// `timeout` elapses, whichever comes first.
+    ///
+    /// On timeout the unfinished future is handed back inside the [`Timeout`]
+    /// error, so that it can be inspected, or resumed by passing it to this
+    /// function again. This is why the future must be [`Unpin`]; use
+    /// `Box::pin` for futures that are not.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::future;
+    /// use std::time::Duration;
+    ///
+    /// let mut pool = LocalPool::new();
+    ///
+    /// let res = pool.run_until_timeout(future::pending::<()>(), Duration::from_millis(10));
+    /// assert!(res.is_err());
+    /// ```
+    ///
+    /// The future is always polled at least once, even if `timeout` is zero.
+    /// Like [`run_until`](LocalPool::run_until), this function does not wait
+    /// for other tasks in the pool to complete.
+    pub fn run_until_timeout<F: Future + Unpin>(
+        &mut self,
+        mut future: F,
+        timeout: Duration,
+    ) -> Result<F::Output, Timeout<F>> {
+        let deadline = Instant::now().checked_add(timeout);
+        let tasks = &mut self.tasks;
+
+        let output = run_executor_until(&mut self.park, deadline, |cx| {
+            {
+                // if our main task is done, so are we
+                let result = Pin::new(&mut future).poll(cx);
+                if let Poll::Ready(output) = result {
+                    return Poll::Ready(output);
+                }
+            }
+
+            let _ = tasks.poll_pool(cx);
+            Poll::Pending
+        });
+
+        output.ok_or(Timeout { future })
+    }
+
     /// Runs all tasks and returns after completing one future or until no more progress
     /// can be made. Returns `true` if one future was completed, `false` otherwise.
     ///
@@ -214,9 +283,10 @@ impl LocalPool {
     /// further use of one of the pool's run or poll methods.
     /// Though only one task will be completed, progress may be made on multiple tasks.
     pub fn try_run_one(&mut self) -> bool {
-        poll_executor(|ctx| {
+        let tasks = &mut self.tasks;
+        poll_executor(&self.park, |ctx| {
             loop {
-                let ret = self.poll_pool_once(ctx);
+                let ret = tasks.poll_pool_once(ctx);
 
                 // return if we have executed a future
                 if let Poll::Ready(Some(_)) = ret {
@@ -226,7 +296,7 @@ impl LocalPool {
                 // if there are no new incoming futures
                 // then there is no feature that can make progress
                 // and we can return without having completed a single future
-                if self.incoming.borrow().is_empty() {
+                if tasks.incoming.borrow().is_empty() {
                     return false;
                 }
             }
@@ -259,11 +329,14 @@ impl LocalPool {
     /// of the pool's run or poll methods. While the function is running, all tasks
     /// in the pool will try to make progress.
     pub fn run_until_stalled(&mut self) {
-        poll_executor(|ctx| {
-            let _ = self.poll_pool(ctx);
+        let tasks = &mut self.tasks;
+        poll_executor(&self.park, |ctx| {
+            let _ = tasks.poll_pool(ctx);
         });
     }
+}
 
+impl Tasks {
     // Make maximal progress on the entire pool of spawned task, returning `Ready`
     // if the pool is empty and `Pending` if no further progress can be made.
     fn poll_pool(&mut self, cx: &mut Context<'_>) -> Poll<()> {
@@ -313,10 +386,94 @@ impl Default for LocalPool {
 /// Use a [`LocalPool`](LocalPool) if you need finer-grained control over
 /// spawned tasks.
 pub fn block_on<F: Future>(f: F) -> F::Output {
+    block_on_with_park(f, ThreadPark::new())
+}
+
+/// Run a future to completion on the current thread, blocking through the
+/// given [`Park`] driver while the future is pending.
+///
+/// This allows a driver, such as an I/O reactor, to run on the same thread
+/// as the future waiting for it. Passing `&mut driver` allows the driver to
+/// be used again afterwards.
+///
+/// ```
+/// use futures::executor::{block_on_with_park, ThreadPark};
+///
+/// let mut park = ThreadPark::new();
+/// assert_eq!(block_on_with_park(async { 1 }, &mut park), 1);
+/// assert_eq!(block_on_with_park(async { 2 }, &mut park), 2);
+/// ```
+pub fn block_on_with_park<F: Future, P: Park>(f: F, park: P) -> F::Output {
     pin_mut!(f);
-    run_executor(|cx| f.as_mut().poll(cx))
+    run_executor(park, |cx| f.as_mut().poll(cx))
 }
 
+/// Run a future on the current thread until it completes or `timeout`
+/// elapses, whichever comes first.
+///
+/// On timeout the unfinished future is returned inside the [`Timeout`]
+/// error, so that it can be inspected or resumed. This is why the future must
+/// be [`Unpin`]; use `Box::pin` for futures that are not.
+///
+/// The future is always polled at least once, even if `timeout` is zero.
+///
+/// ```
+/// use futures::channel::oneshot;
+/// use futures::executor::block_on_timeout;
+/// use std::time::Duration;
+///
+/// let (tx, rx) = oneshot::channel();
+///
+/// // Nothing has been sent yet, so this gives up.
+/// let rx = block_on_timeout(rx, Duration::from_millis(10)).unwrap_err().into_inner();
+///
+/// // Resume waiting on the same receiver.
+/// tx.send(5).unwrap();
+/// assert_eq!(block_on_timeout(rx, Duration::from_millis(10)).ok(), Some(Ok(5)));
+/// ```
+pub fn block_on_timeout<F: Future + Unpin>(
+    mut f: F,
+    timeout: Duration,
+) -> Result<F::Output, Timeout<F>> {
+    let deadline = Instant::now().checked_add(timeout);
+    run_executor_until(ThreadPark::new(), deadline, |cx| Pin::new(&mut f).poll(cx))
+        .ok_or(Timeout { future: f })
+}
+
+/// Error returned by [`block_on_timeout`] and
+/// [`LocalPool::run_until_timeout`] when the future did not complete in time.
+///
+/// It owns the unfinished future.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Timeout<F> {
+    future: F,
+}
+
+impl<F> Timeout<F> {
+    /// Acquires a reference to the unfinished future.
+    pub fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Acquires a mutable reference to the unfinished future.
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.future
+    }
+
+    /// Consumes this error, returning the unfinished future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F> fmt::Display for Timeout<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "future did not complete before the timeout")
+    }
+}
+
+impl<F: fmt::Debug> std::error::Error for Timeout<F> {}
+
 /// Turn a stream into a blocking iterator.
 ///
 /// When `next` is called on the resulting `BlockingStream`, the caller
@@ -0,0 +1,18 @@
+//! Sockets driven by a background epoll thread.
+//!
+//! Every socket is registered, edge-triggered, with one process-wide epoll
+//! instance, serviced by a single `futures-reactor` thread that is started
+//! the first time a socket is created. The thread only waits for readiness
+//! and wakes tasks; the I/O itself happens on whichever executor polls the
+//! socket.
+
+mod reactor;
+
+mod tcp;
+pub use self::tcp::{Incoming, TcpListener, TcpStream};
+
+mod udp;
+pub use self::udp::UdpSocket;
+
+mod unix;
+pub use self::unix::UnixStream;
@@ -0,0 +1,244 @@
+use futures_core::task::{Context, Poll};
+use futures_util::task::AtomicWaker;
+use slab::Slab;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+
+// The low bits of `ScheduledIo::readiness` hold the readiness, the rest
+// counts the events delivered so far.
+const READABLE: usize = 0b01;
+const WRITABLE: usize = 0b10;
+const TICK_SHIFT: u32 = 2;
+
+const EVENTS_CAPACITY: usize = 1024;
+
+/// The process-wide epoll instance and the sources registered with it.
+struct Reactor {
+    epoll: RawFd,
+    sources: Mutex<Slab<Arc<ScheduledIo>>>,
+}
+
+/// Readiness of a single source, and the tasks waiting for it.
+#[derive(Debug)]
+struct ScheduledIo {
+    readiness: AtomicUsize,
+    reader: AtomicWaker,
+    writer: AtomicWaker,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Direction {
+    Read,
+    Write,
+}
+
+impl Direction {
+    fn mask(self) -> usize {
+        match self {
+            Direction::Read => READABLE,
+            Direction::Write => WRITABLE,
+        }
+    }
+}
+
+/// A file descriptor registered with the reactor.
+///
+/// It must be dropped before the file descriptor is closed.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    fd: RawFd,
+    key: usize,
+    io: Arc<ScheduledIo>,
+}
+
+impl Reactor {
+    /// The process-wide reactor, spawning its thread on first use.
+    fn global() -> &'static Self {
+        static INIT: Once = Once::new();
+        static GLOBAL: AtomicPtr<Reactor> = AtomicPtr::new(ptr::null_mut());
+
+        INIT.call_once(|| {
+            let epoll = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+            if epoll < 0 {
+                panic!("failed to create the epoll instance: {}", io::Error::last_os_error());
+            }
+            let reactor: &'static Self = Box::leak(Box::new(Self {
+                epoll,
+                sources: Mutex::new(Slab::new()),
+            }));
+            thread::Builder::new()
+                .name("futures-reactor".to_string())
+                .spawn(move || reactor.run())
+                .expect("failed to spawn the reactor thread");
+            GLOBAL.store(reactor as *const Self as *mut Self, Ordering::Release);
+        });
+
+        // Safety: `GLOBAL` is set to a leaked, never freed allocation by
+        // `INIT`, which has completed at this point.
+        unsafe { &*GLOBAL.load(Ordering::Acquire) }
+    }
+
+    // The reactor loop: wait for events and wake the tasks interested in them.
+    fn run(&self) {
+        let mut events = vec![libc::epoll_event { events: 0, u64: 0 }; EVENTS_CAPACITY];
+        let mut ready = Vec::with_capacity(EVENTS_CAPACITY);
+        loop {
+            let n = unsafe {
+                libc::epoll_wait(self.epoll, events.as_mut_slice().as_mut_ptr(), events.len() as i32, -1)
+            };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                panic!("epoll_wait failed: {}", err);
+            }
+            {
+                // An event may still arrive for a source that was removed in
+                // the meantime, and its key may have been reused. That only
+                // causes a spurious wakeup, which the I/O types tolerate.
+                let sources = self.sources.lock().unwrap();
+                for event in &events[..n as usize] {
+                    let (flags, key) = (event.events as i32, event.u64 as usize);
+                    if let Some(io) = sources.get(key) {
+                        ready.push((io.clone(), readiness(flags)));
+                    }
+                }
+            }
+
+            // Don't hold the lock while running arbitrary wake code.
+            for (io, readiness) in ready.drain(..) {
+                io.set_ready(readiness);
+            }
+        }
+    }
+}
+
+fn readiness(flags: i32) -> usize {
+    let mut readiness = 0;
+    if flags & (libc::EPOLLIN | libc::EPOLLPRI | libc::EPOLLRDHUP) != 0 {
+        readiness |= READABLE;
+    }
+    if flags & libc::EPOLLOUT != 0 {
+        readiness |= WRITABLE;
+    }
+    // Errors and hangups are reported to whoever attempts the next operation.
+    if flags & (libc::EPOLLERR | libc::EPOLLHUP) != 0 {
+        readiness |= READABLE | WRITABLE;
+    }
+    readiness
+}
+
+impl ScheduledIo {
+    fn set_ready(&self, readiness: usize) {
+        let mut current = self.readiness.load(Ordering::Acquire);
+        loop {
+            let tick = (current >> TICK_SHIFT).wrapping_add(1);
+            let new = (tick << TICK_SHIFT) | (current & (READABLE | WRITABLE)) | readiness;
+            match self.readiness.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+        if readiness & READABLE != 0 {
+            self.reader.wake();
+        }
+        if readiness & WRITABLE != 0 {
+            self.writer.wake();
+        }
+    }
+}
+
+impl Registration {
+    /// Registers `fd`, which must be in non-blocking mode, with the reactor.
+    pub(crate) fn new(fd: RawFd) -> io::Result<Self> {
+        let reactor = Reactor::global();
+        let io = Arc::new(ScheduledIo {
+            readiness: AtomicUsize::new(0),
+            reader: AtomicWaker::new(),
+            writer: AtomicWaker::new(),
+        });
+        let mut sources = reactor.sources.lock().unwrap();
+        let key = sources.insert(io.clone());
+
+        let mut event = libc::epoll_event {
+            events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET) as u32,
+            u64: key as u64,
+        };
+        if unsafe { libc::epoll_ctl(reactor.epoll, libc::EPOLL_CTL_ADD, fd, &mut event) } < 0 {
+            sources.remove(key);
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { fd, key, io })
+    }
+
+    /// Polls for `direction` readiness, returning a token to pass to
+    /// [`clear_ready`](Registration::clear_ready).
+    pub(crate) fn poll_ready(&self, direction: Direction, cx: &mut Context<'_>) -> Poll<usize> {
+        let current = self.io.readiness.load(Ordering::Acquire);
+        if current & direction.mask() != 0 {
+            return Poll::Ready(current);
+        }
+        match direction {
+            Direction::Read => self.io.reader.register(cx.waker()),
+            Direction::Write => self.io.writer.register(cx.waker()),
+        }
+        // Check again in case an event arrived before the waker was stored.
+        let current = self.io.readiness.load(Ordering::Acquire);
+        if current & direction.mask() != 0 {
+            Poll::Ready(current)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Clears `direction` readiness after an operation would have blocked,
+    /// unless an event arrived since `token` was returned by `poll_ready`.
+    pub(crate) fn clear_ready(&self, direction: Direction, token: usize) {
+        let _ = self.io.readiness.compare_exchange(
+            token,
+            token & !direction.mask(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
+    /// Runs `f` once the source is ready for `direction`, until it stops
+    /// failing with `WouldBlock`.
+    pub(crate) fn poll_io<R>(
+        &self,
+        direction: Direction,
+        cx: &mut Context<'_>,
+        mut f: impl FnMut() -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        loop {
+            let token = match self.poll_ready(direction, cx) {
+                Poll::Ready(token) => token,
+                Poll::Pending => return Poll::Pending,
+            };
+            match f() {
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.clear_ready(direction, token)
+                }
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let reactor = Reactor::global();
+        let mut sources = reactor.sources.lock().unwrap();
+        // Closing the file descriptor would deregister it as well, unless it
+        // has been duplicated.
+        unsafe {
+            libc::epoll_ctl(reactor.epoll, libc::EPOLL_CTL_DEL, self.fd, ptr::null_mut());
+        }
+        sources.remove(self.key);
+    }
+}
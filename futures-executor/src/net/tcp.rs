@@ -0,0 +1,328 @@
+use super::reactor::{Direction, Registration};
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use futures_util::future::poll_fn;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::{self, Shutdown, SocketAddr};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::pin::Pin;
+
+/// A TCP socket server, listening for connections.
+///
+/// This type is only available on Linux, when the `net` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, TcpListener, TcpStream};
+/// use futures::io::{AsyncReadExt, AsyncWriteExt};
+///
+/// # block_on(async {
+/// let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap())?;
+/// let mut client = TcpStream::connect(listener.local_addr()?).await?;
+/// let (mut server, _) = listener.accept().await?;
+///
+/// client.write_all(b"ping").await?;
+/// let mut buf = [0; 4];
+/// server.read_exact(&mut buf).await?;
+/// assert_eq!(&buf, b"ping");
+/// # Ok::<(), std::io::Error>(()) }).unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[derive(Debug)]
+pub struct TcpListener {
+    // Declared first so that it is deregistered before the socket is closed.
+    registration: Registration,
+    io: net::TcpListener,
+}
+
+impl TcpListener {
+    /// Creates a listener bound to `addr`.
+    ///
+    /// Binding with a port number of 0 requests that the OS assigns a port;
+    /// use [`local_addr`](TcpListener::local_addr) to find out which one.
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        Self::from_std(net::TcpListener::bind(addr)?)
+    }
+
+    /// Creates a listener from a standard library one, putting it in
+    /// non-blocking mode.
+    pub fn from_std(listener: net::TcpListener) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            registration: Registration::new(listener.as_raw_fd())?,
+            io: listener,
+        })
+    }
+
+    /// Returns the local address this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Attempts to accept a new incoming connection.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(TcpStream, SocketAddr)>> {
+        let (stream, addr) = ready!(self.registration.poll_io(Direction::Read, cx, || self.io.accept()))?;
+        Poll::Ready(TcpStream::from_std(stream).map(|stream| (stream, addr)))
+    }
+
+    /// Accepts a new incoming connection, returning the stream and the
+    /// address of the peer.
+    pub fn accept(&self) -> impl Future<Output = io::Result<(TcpStream, SocketAddr)>> + '_ {
+        poll_fn(move |cx| self.poll_accept(cx))
+    }
+
+    /// Returns a stream of incoming connections.
+    ///
+    /// The stream never ends; errors on individual connections are yielded
+    /// as items.
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
+    }
+}
+
+impl AsRawFd for TcpListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+/// Stream of the connections accepted by a [`TcpListener`].
+///
+/// This type is returned by [`TcpListener::incoming`].
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Incoming<'a> {
+    listener: &'a TcpListener,
+}
+
+impl Stream for Incoming<'_> {
+    type Item = io::Result<TcpStream>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let res = ready!(self.listener.poll_accept(cx));
+        Poll::Ready(Some(res.map(|(stream, _)| stream)))
+    }
+}
+
+impl FusedStream for Incoming<'_> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// A TCP connection.
+///
+/// Reading and writing go through the [`AsyncRead`] and [`AsyncWrite`]
+/// implementations, which are also provided for `&TcpStream` so that one
+/// task can read while another one writes. Only one task should read, and
+/// one write, at a time: each direction only remembers the task that polled
+/// it last.
+///
+/// This type is only available on Linux, when the `net` feature of this
+/// library is activated.
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[derive(Debug)]
+pub struct TcpStream {
+    // Declared first so that it is deregistered before the socket is closed.
+    registration: Registration,
+    io: net::TcpStream,
+}
+
+impl TcpStream {
+    /// Opens a connection to `addr`.
+    pub fn connect(addr: SocketAddr) -> impl Future<Output = io::Result<Self>> {
+        let mut connecting = Some(Self::start_connect(addr));
+        poll_fn(move |cx| {
+            if let Some(Ok(stream)) = &connecting {
+                // The socket becomes writable once the connection is either
+                // established or has failed.
+                ready!(stream.registration.poll_ready(Direction::Write, cx));
+            }
+            let stream = connecting.take().expect("`connect` polled after completion")?;
+            match stream.io.take_error()? {
+                Some(err) => Poll::Ready(Err(err)),
+                None => Poll::Ready(Ok(stream)),
+            }
+        })
+    }
+
+    fn start_connect(addr: SocketAddr) -> io::Result<Self> {
+        let family = match addr {
+            SocketAddr::V4(_) => libc::AF_INET,
+            SocketAddr::V6(_) => libc::AF_INET6,
+        };
+        let fd = unsafe {
+            libc::socket(family, libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC, 0)
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Safety: `fd` is a freshly created socket that nothing else owns.
+        let io = unsafe { net::TcpStream::from_raw_fd(fd) };
+
+        let (storage, len) = sockaddr(&addr);
+        let ret = unsafe { libc::connect(fd, &storage as *const _ as *const libc::sockaddr, len) };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::EINPROGRESS) {
+                return Err(err);
+            }
+        }
+        Ok(Self {
+            registration: Registration::new(fd)?,
+            io,
+        })
+    }
+
+    /// Creates a stream from a standard library one, putting it in
+    /// non-blocking mode.
+    pub fn from_std(stream: net::TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            registration: Registration::new(stream.as_raw_fd())?,
+            io: stream,
+        })
+    }
+
+    /// Returns the local address of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Returns the address of the peer of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.io.peer_addr()
+    }
+
+    /// Shuts down the read half, the write half, or both halves of this
+    /// connection.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.io.shutdown(how)
+    }
+
+    /// Returns whether Nagle's algorithm is disabled on this socket.
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.io.nodelay()
+    }
+
+    /// Enables or disables Nagle's algorithm on this socket.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.io.set_nodelay(nodelay)
+    }
+}
+
+impl AsRawFd for TcpStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+// Converts `addr` into the representation expected by `connect(2)`.
+fn sockaddr(addr: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    // Safety: all-zero is a valid value for these plain C structs.
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let len = match addr {
+        SocketAddr::V4(addr) => {
+            let mut sin: libc::sockaddr_in = unsafe { mem::zeroed() };
+            sin.sin_family = libc::AF_INET as libc::sa_family_t;
+            sin.sin_port = addr.port().to_be();
+            sin.sin_addr.s_addr = u32::from_ne_bytes(addr.ip().octets());
+            unsafe { (&mut storage as *mut _ as *mut libc::sockaddr_in).write(sin) };
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(addr) => {
+            let mut sin6: libc::sockaddr_in6 = unsafe { mem::zeroed() };
+            sin6.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            sin6.sin6_port = addr.port().to_be();
+            sin6.sin6_flowinfo = addr.flowinfo();
+            sin6.sin6_addr.s6_addr = addr.ip().octets();
+            sin6.sin6_scope_id = addr.scope_id();
+            unsafe { (&mut storage as *mut _ as *mut libc::sockaddr_in6).write(sin6) };
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    (storage, len as libc::socklen_t)
+}
+
+impl AsyncRead for &TcpStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let this: &TcpStream = *self;
+        this.registration.poll_io(Direction::Read, cx, || (&this.io).read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this: &TcpStream = *self;
+        this.registration.poll_io(Direction::Read, cx, || (&this.io).read_vectored(bufs))
+    }
+}
+
+impl AsyncWrite for &TcpStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this: &TcpStream = *self;
+        this.registration.poll_io(Direction::Write, cx, || (&this.io).write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this: &TcpStream = *self;
+        this.registration.poll_io(Direction::Write, cx, || (&this.io).write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.io.shutdown(Shutdown::Write))
+    }
+}
+
+impl AsyncRead for TcpStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(cx, buf)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read_vectored(cx, bufs)
+    }
+}
+
+impl AsyncWrite for TcpStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_close(cx)
+    }
+}
@@ -0,0 +1,135 @@
+use super::reactor::{Direction, Registration};
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use futures_util::future::poll_fn;
+use std::io;
+use std::net::{self, SocketAddr};
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// A UDP socket.
+///
+/// Like [`TcpStream`](crate::TcpStream), one task may send while another one
+/// receives, but each direction only remembers the task that polled it last.
+///
+/// This type is only available on Linux, when the `net` feature of this
+/// library is activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, UdpSocket};
+///
+/// # block_on(async {
+/// let a = UdpSocket::bind("127.0.0.1:0".parse().unwrap())?;
+/// let b = UdpSocket::bind("127.0.0.1:0".parse().unwrap())?;
+///
+/// a.send_to(b"hello", b.local_addr()?).await?;
+/// let mut buf = [0; 16];
+/// let (n, from) = b.recv_from(&mut buf).await?;
+/// assert_eq!(&buf[..n], b"hello");
+/// assert_eq!(from, a.local_addr()?);
+/// # Ok::<(), std::io::Error>(()) }).unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(feature = "net", target_os = "linux"))))]
+#[derive(Debug)]
+pub struct UdpSocket {
+    // Declared first so that it is deregistered before the socket is closed.
+    registration: Registration,
+    io: net::UdpSocket,
+}
+
+impl UdpSocket {
+    /// Creates a socket bound to `addr`.
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        Self::from_std(net::UdpSocket::bind(addr)?)
+    }
+
+    /// Creates a socket from a standard library one, putting it in
+    /// non-blocking mode.
+    pub fn from_std(socket: net::UdpSocket) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            registration: Registration::new(socket.as_raw_fd())?,
+            io: socket,
+        })
+    }
+
+    /// Returns the local address this socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Connects this socket to `addr`, so that [`send`](UdpSocket::send) and
+    /// [`recv`](UdpSocket::recv) can be used, and datagrams from other
+    /// addresses are filtered out.
+    pub fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+        self.io.connect(addr)
+    }
+
+    /// Attempts to send a datagram to `target`.
+    pub fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        target: SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        self.registration.poll_io(Direction::Write, cx, || self.io.send_to(buf, target))
+    }
+
+    /// Sends a datagram to `target`, returning the number of bytes sent.
+    pub fn send_to<'a>(
+        &'a self,
+        buf: &'a [u8],
+        target: SocketAddr,
+    ) -> impl Future<Output = io::Result<usize>> + 'a {
+        poll_fn(move |cx| self.poll_send_to(cx, buf, target))
+    }
+
+    /// Attempts to receive a datagram.
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        self.registration.poll_io(Direction::Read, cx, || self.io.recv_from(buf))
+    }
+
+    /// Receives a datagram, returning its length and the address it came
+    /// from.
+    ///
+    /// If `buf` is too small for the datagram, the excess bytes are
+    /// discarded.
+    pub fn recv_from<'a>(
+        &'a self,
+        buf: &'a mut [u8],
+    ) -> impl Future<Output = io::Result<(usize, SocketAddr)>> + 'a {
+        poll_fn(move |cx| self.poll_recv_from(cx, buf))
+    }
+
+    /// Attempts to send a datagram to the connected peer.
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.registration.poll_io(Direction::Write, cx, || self.io.send(buf))
+    }
+
+    /// Sends a datagram to the connected peer, returning the number of bytes
+    /// sent.
+    pub fn send<'a>(&'a self, buf: &'a [u8]) -> impl Future<Output = io::Result<usize>> + 'a {
+        poll_fn(move |cx| self.poll_send(cx, buf))
+    }
+
+    /// Attempts to receive a datagram from the connected peer.
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.registration.poll_io(Direction::Read, cx, || self.io.recv(buf))
+    }
+
+    /// Receives a datagram from the connected peer, returning its length.
+    pub fn recv<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = io::Result<usize>> + 'a {
+        poll_fn(move |cx| self.poll_recv(cx, buf))
+    }
+}
+
+impl AsRawFd for UdpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
@@ -0,0 +1,202 @@
+use futures_task::ArcWake;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// Blocks the current thread while an executor has nothing to do.
+///
+/// [`LocalPool`](crate::LocalPool) and [`block_on`](crate::block_on) call
+/// [`park`](Park::park) whenever all of their futures are pending, and the
+/// wakers they hand out unpark the executor through the handle returned by
+/// [`unpark`](Park::unpark). The default, [`ThreadPark`], simply parks the
+/// thread. A custom implementation can instead wait for OS events, and
+/// dispatch them to the tasks interested in them, so that an I/O driver runs
+/// on the same thread as the futures it serves.
+///
+/// Use a custom driver with [`LocalPool::with_park`](crate::LocalPool::with_park)
+/// or [`block_on_with_park`](crate::block_on_with_park).
+///
+/// # Examples
+///
+/// A driver that counts how often the executor went idle:
+///
+/// ```
+/// use futures::executor::{block_on_with_park, Park, ThreadPark};
+/// use futures::future;
+/// use std::time::Duration;
+///
+/// #[derive(Default)]
+/// struct Counting {
+///     inner: ThreadPark,
+///     parks: usize,
+/// }
+///
+/// impl Park for Counting {
+///     type Unpark = <ThreadPark as Park>::Unpark;
+///
+///     fn unpark(&self) -> std::sync::Arc<Self::Unpark> {
+///         self.inner.unpark()
+///     }
+///
+///     fn park(&mut self) {
+///         self.parks += 1;
+///         self.inner.park();
+///     }
+///
+///     fn park_timeout(&mut self, duration: Duration) {
+///         self.parks += 1;
+///         self.inner.park_timeout(duration);
+///     }
+/// }
+///
+/// let mut driver = Counting::default();
+/// block_on_with_park(future::ready(()), &mut driver);
+/// assert_eq!(driver.parks, 0);
+/// ```
+pub trait Park {
+    /// The handle used to unpark the executor.
+    ///
+    /// The executor builds the wakers of its tasks from this handle, so
+    /// waking a task calls [`ArcWake::wake_by_ref`] on it. Waking must cause
+    /// the next, or the currently blocked, call to [`park`](Park::park) or
+    /// [`park_timeout`](Park::park_timeout) to return, even if it happens
+    /// before the executor has started to park.
+    type Unpark: ArcWake;
+
+    /// Returns a handle that unparks this driver.
+    ///
+    /// This is called on the thread that will park, once every time an
+    /// executor starts running.
+    fn unpark(&self) -> Arc<Self::Unpark>;
+
+    /// Blocks until the executor is unparked.
+    ///
+    /// Spurious returns are allowed; the executor then polls its futures
+    /// again.
+    fn park(&mut self);
+
+    /// Blocks until the executor is unparked or `duration` has elapsed.
+    ///
+    /// Spurious returns are allowed; the executor then polls its futures
+    /// again.
+    fn park_timeout(&mut self, duration: Duration);
+}
+
+impl<P: Park + ?Sized> Park for &mut P {
+    type Unpark = P::Unpark;
+
+    fn unpark(&self) -> Arc<Self::Unpark> {
+        (**self).unpark()
+    }
+
+    fn park(&mut self) {
+        (**self).park()
+    }
+
+    fn park_timeout(&mut self, duration: Duration) {
+        (**self).park_timeout(duration)
+    }
+}
+
+impl<P: Park + ?Sized> Park for Box<P> {
+    type Unpark = P::Unpark;
+
+    fn unpark(&self) -> Arc<Self::Unpark> {
+        (**self).unpark()
+    }
+
+    fn park(&mut self) {
+        (**self).park()
+    }
+
+    fn park_timeout(&mut self, duration: Duration) {
+        (**self).park_timeout(duration)
+    }
+}
+
+/// The default [`Park`] implementation, which parks the current thread.
+///
+/// This is what [`LocalPool::new`](crate::LocalPool::new) and
+/// [`block_on`](crate::block_on) use.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadPark {
+    _priv: (),
+}
+
+impl ThreadPark {
+    /// Creates a driver parking the thread it is run on.
+    pub fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+/// The [`Unpark`](Park::Unpark) handle of a [`ThreadPark`].
+#[derive(Debug)]
+pub struct ThreadUnpark {
+    /// The (single) executor thread.
+    thread: Thread,
+    /// A flag to ensure a wakeup (i.e. `unpark()`) is not "forgotten"
+    /// before the next `park()`, which may otherwise happen if the code
+    /// being executed as part of the future(s) being polled makes use of
+    /// park / unpark calls of its own, i.e. we cannot assume that no other
+    /// code uses park / unpark on the executing `thread`.
+    unparked: AtomicBool,
+}
+
+thread_local! {
+    static CURRENT_THREAD_UNPARK: Arc<ThreadUnpark> = Arc::new(ThreadUnpark {
+        thread: thread::current(),
+        unparked: AtomicBool::new(false),
+    });
+}
+
+impl ArcWake for ThreadUnpark {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        // Make sure the wakeup is remembered until the next `park()`.
+        let unparked = arc_self.unparked.swap(true, Ordering::Relaxed);
+        if !unparked {
+            // If the thread has not been unparked yet, it must be done
+            // now. If it was actually parked, it will run again,
+            // otherwise the token made available by `unpark`
+            // may be consumed before reaching `park()`, but `unparked`
+            // ensures it is not forgotten.
+            arc_self.thread.unpark();
+        }
+    }
+}
+
+impl ThreadPark {
+    fn park_with(park: impl FnOnce()) {
+        CURRENT_THREAD_UNPARK.with(|thread_unpark| {
+            // Consume the wakeup that occurred while polling, if any.
+            let unparked = thread_unpark.unparked.swap(false, Ordering::Acquire);
+            if !unparked {
+                // No wakeup occurred. It may occur now, right before parking,
+                // but in that case the token made available by `unpark()`
+                // is guaranteed to still be available and `park()` is a no-op.
+                park();
+                // When the thread is unparked, `unparked` will have been set
+                // and needs to be unset before the next poll to avoid
+                // a redundant loop iteration.
+                thread_unpark.unparked.store(false, Ordering::Release);
+            }
+        })
+    }
+}
+
+impl Park for ThreadPark {
+    type Unpark = ThreadUnpark;
+
+    fn unpark(&self) -> Arc<ThreadUnpark> {
+        CURRENT_THREAD_UNPARK.with(Arc::clone)
+    }
+
+    fn park(&mut self) {
+        Self::park_with(thread::park)
+    }
+
+    fn park_timeout(&mut self, duration: Duration) {
+        Self::park_with(|| thread::park_timeout(duration))
+    }
+}
@@ -0,0 +1,138 @@
+use super::Shared;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// A future that completes at a given [`Instant`].
+///
+/// The timer is registered with the background timer thread when the future
+/// is first polled, and cancelled in constant time when it is dropped. Any
+/// executor can drive a `Delay`.
+///
+/// This type is only available when the `timer` feature of this library is
+/// activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, Delay};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// block_on(Delay::new(Duration::from_millis(10)));
+/// assert!(start.elapsed() >= Duration::from_millis(10));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Delay {
+    deadline: Instant,
+    key: Option<usize>,
+    state: DelayState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DelayState {
+    Idle,
+    Waiting,
+    Fired,
+}
+
+impl Delay {
+    /// Creates a future that completes `duration` from now.
+    pub fn new(duration: Duration) -> Self {
+        Self::new_at(Instant::now() + duration)
+    }
+
+    /// Creates a future that completes at `deadline`.
+    ///
+    /// A `deadline` that has already passed completes on the first poll.
+    pub fn new_at(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            key: None,
+            state: DelayState::Idle,
+        }
+    }
+
+    /// Returns the instant at which this future completes.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns `true` if this future has completed.
+    pub fn is_elapsed(&self) -> bool {
+        self.state == DelayState::Fired
+    }
+
+    /// Changes the instant at which this future completes, re-arming it if
+    /// it had already completed.
+    ///
+    /// The existing timer registration is reused, so this does not allocate.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+        if let Some(key) = self.key {
+            let shared = Shared::global();
+            let when = shared.tick_for(deadline);
+            self.state = if shared.lock().reset(key, when) {
+                DelayState::Fired
+            } else {
+                DelayState::Waiting
+            };
+        } else {
+            self.state = DelayState::Idle;
+        }
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        match this.state {
+            DelayState::Fired => return Poll::Ready(()),
+            DelayState::Waiting => {
+                let key = this.key.expect("waiting delay without a timer");
+                if Shared::global().lock().poll(key, cx.waker()) {
+                    this.state = DelayState::Fired;
+                    return Poll::Ready(());
+                }
+            }
+            DelayState::Idle => {
+                if this.deadline <= Instant::now() {
+                    this.state = DelayState::Fired;
+                    return Poll::Ready(());
+                }
+                let shared = Shared::global();
+                let when = shared.tick_for(this.deadline);
+                match shared.lock().insert(when, cx.waker()) {
+                    Some(key) => {
+                        this.key = Some(key);
+                        this.state = DelayState::Waiting;
+                    }
+                    None => {
+                        this.state = DelayState::Fired;
+                        return Poll::Ready(());
+                    }
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl FusedFuture for Delay {
+    fn is_terminated(&self) -> bool {
+        self.is_elapsed()
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            Shared::global().lock().remove(key);
+        }
+    }
+}
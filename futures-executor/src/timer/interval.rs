@@ -0,0 +1,154 @@
+use super::Delay;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// A stream that yields at a fixed period.
+///
+/// Each item is the [`Instant`] at which that tick was scheduled. What happens
+/// when the stream is not polled in time for one or more ticks is controlled
+/// by [`MissedTickBehavior`].
+///
+/// This type is only available when the `timer` feature of this library is
+/// activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on_stream, Interval};
+/// use std::time::Duration;
+///
+/// let period = Duration::from_millis(5);
+/// let ticks: Vec<_> = block_on_stream(Interval::new(period)).take(3).collect();
+///
+/// assert_eq!(ticks[1] - ticks[0], period);
+/// assert_eq!(ticks[2] - ticks[1], period);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Interval {
+    delay: Delay,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+/// What an [`Interval`] does when one or more ticks were missed because the
+/// stream was not polled in time.
+///
+/// This type is only available when the `timer` feature of this library is
+/// activated.
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Yield every missed tick as fast as possible until the stream has
+    /// caught up with the original schedule.
+    ///
+    /// This is the default.
+    Burst,
+    /// Yield one tick immediately, then restart the schedule one period
+    /// after the time the late tick was observed.
+    Delay,
+    /// Yield one tick immediately, then continue on the original schedule,
+    /// skipping the ticks that were missed.
+    Skip,
+}
+
+impl Default for MissedTickBehavior {
+    fn default() -> Self {
+        MissedTickBehavior::Burst
+    }
+}
+
+impl Interval {
+    /// Creates a stream that first yields `period` from now, and every
+    /// `period` after that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Self {
+        Self::new_at(Instant::now() + period, period)
+    }
+
+    /// Creates a stream that first yields at `start`, and every `period`
+    /// after that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new_at(start: Instant, period: Duration) -> Self {
+        assert!(period > Duration::from_secs(0), "`period` must be non-zero");
+        Self {
+            delay: Delay::new_at(start),
+            period,
+            missed_tick_behavior: MissedTickBehavior::default(),
+        }
+    }
+
+    /// Returns the period of this interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the current [`MissedTickBehavior`].
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Sets what the stream does when ticks are missed.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Restarts the schedule so that the next tick is one period from now.
+    pub fn reset(&mut self) {
+        self.delay.reset(Instant::now() + self.period);
+    }
+
+    fn next_deadline(&self, tick: Instant, now: Instant) -> Instant {
+        match self.missed_tick_behavior {
+            MissedTickBehavior::Burst => tick + self.period,
+            MissedTickBehavior::Delay => now + self.period,
+            MissedTickBehavior::Skip => {
+                let next = tick + self.period;
+                if next > now {
+                    return next;
+                }
+                let period = self.period.as_nanos();
+                let behind = (now - tick).as_nanos();
+                let periods = behind / period + 1;
+                let offset = periods * period;
+                tick + Duration::new(
+                    (offset / 1_000_000_000) as u64,
+                    (offset % 1_000_000_000) as u32,
+                )
+            }
+        }
+    }
+}
+
+impl Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Instant>> {
+        ready!(Pin::new(&mut self.delay).poll(cx));
+        let tick = self.delay.deadline();
+        let next = self.next_deadline(tick, Instant::now());
+        self.delay.reset(next);
+        Poll::Ready(Some(tick))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::max_value(), None)
+    }
+}
+
+impl FusedStream for Interval {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,204 @@
+//! Timers driven by a background thread.
+//!
+//! All [`Delay`]s and [`Interval`]s in the process share one hierarchical
+//! timing wheel, serviced by a single `futures-timer` thread that is started
+//! the first time a timer is polled. The thread only sleeps and wakes tasks;
+//! the timer futures themselves can be awaited on any executor.
+
+use futures_core::task::Waker;
+use futures_util::time::Timer;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod wheel;
+use self::wheel::Wheel;
+
+mod delay;
+pub use self::delay::Delay;
+
+mod interval;
+pub use self::interval::{Interval, MissedTickBehavior};
+
+/// A [`Timer`] backed by the system clock and the background timer thread.
+///
+/// This allows the time-based combinators of `FutureExt` and `StreamExt` to
+/// be used without a runtime.
+///
+/// This type is only available when the `timer` feature of this library is
+/// activated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::{block_on, SystemTimer};
+/// use futures::future::{self, FutureExt};
+/// use futures::time::TimedOut;
+/// use std::time::Duration;
+///
+/// let never = future::pending::<()>().timeout(SystemTimer, Duration::from_millis(10));
+/// assert_eq!(block_on(never), Err(TimedOut));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemTimer;
+
+impl Timer for SystemTimer {
+    type Sleep = Delay;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Delay {
+        Delay::new_at(deadline)
+    }
+}
+
+/// State shared between the timer futures and the driver thread.
+pub(crate) struct Shared {
+    /// The instant corresponding to tick 0 of the wheel.
+    start: Instant,
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+struct State {
+    wheel: Wheel,
+    /// The tick the driver thread will next wake up at, or `None` if it is
+    /// waiting for a timer to be added.
+    next_wake: Option<u64>,
+}
+
+pub(crate) struct Guard<'a> {
+    shared: &'a Shared,
+    state: MutexGuard<'a, State>,
+}
+
+impl Shared {
+    /// The process-wide timer, spawning its driver thread on first use.
+    pub(crate) fn global() -> &'static Self {
+        static INIT: Once = Once::new();
+        static GLOBAL: AtomicPtr<Shared> = AtomicPtr::new(ptr::null_mut());
+
+        INIT.call_once(|| {
+            let shared: &'static Self = Box::leak(Box::new(Self {
+                start: Instant::now(),
+                state: Mutex::new(State {
+                    wheel: Wheel::new(),
+                    next_wake: None,
+                }),
+                condvar: Condvar::new(),
+            }));
+            thread::Builder::new()
+                .name("futures-timer".to_string())
+                .spawn(move || shared.run())
+                .expect("failed to spawn the timer thread");
+            GLOBAL.store(shared as *const Self as *mut Self, Ordering::Release);
+        });
+
+        // Safety: `GLOBAL` is set to a leaked, never freed allocation by
+        // `INIT`, which has completed at this point.
+        unsafe { &*GLOBAL.load(Ordering::Acquire) }
+    }
+
+    pub(crate) fn lock(&self) -> Guard<'_> {
+        Guard {
+            shared: self,
+            state: self.state.lock().unwrap(),
+        }
+    }
+
+    /// Converts `deadline` into a wheel tick, rounding up so that timers
+    /// never fire early.
+    pub(crate) fn tick_for(&self, deadline: Instant) -> u64 {
+        let since_start = duration_between(self.start, deadline);
+        let ms = (since_start.as_nanos() + 999_999) / 1_000_000;
+        if ms > u128::from(u64::max_value()) {
+            u64::max_value()
+        } else {
+            ms as u64
+        }
+    }
+
+    fn now_tick(&self) -> u64 {
+        duration_between(self.start, Instant::now()).as_millis() as u64
+    }
+
+    fn instant_for(&self, tick: u64) -> Instant {
+        self.start + Duration::from_millis(tick)
+    }
+
+    // The driver loop: fire due timers, then sleep until the next slot expires
+    // or a timer earlier than that is added.
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let wakers: Vec<Waker> = state.wheel.advance(self.now_tick());
+            if !wakers.is_empty() {
+                // Don't hold the lock while running arbitrary wake code.
+                drop(state);
+                for waker in wakers {
+                    waker.wake();
+                }
+                state = self.state.lock().unwrap();
+                continue;
+            }
+
+            state.next_wake = state.wheel.next_expiration_tick();
+            state = match state.next_wake {
+                Some(tick) => {
+                    let timeout = duration_between(Instant::now(), self.instant_for(tick));
+                    self.condvar.wait_timeout(state, timeout).unwrap().0
+                }
+                None => self.condvar.wait(state).unwrap(),
+            };
+        }
+    }
+}
+
+// `Instant::saturating_duration_since` is not available on our MSRV.
+fn duration_between(earlier: Instant, later: Instant) -> Duration {
+    if later > earlier {
+        later - earlier
+    } else {
+        Duration::from_secs(0)
+    }
+}
+
+impl Guard<'_> {
+    /// Adds a timer firing at `when`, or returns `None` if it is already due.
+    pub(crate) fn insert(&mut self, when: u64, waker: &Waker) -> Option<usize> {
+        let key = self.state.wheel.insert(when, waker)?;
+        self.schedule(when);
+        Some(key)
+    }
+
+    /// Updates the waker of the timer `key`, returning `true` if it fired.
+    pub(crate) fn poll(&mut self, key: usize, waker: &Waker) -> bool {
+        self.state.wheel.poll(key, waker)
+    }
+
+    /// Moves the timer `key` to `when`, returning `true` if it is already due.
+    pub(crate) fn reset(&mut self, key: usize, when: u64) -> bool {
+        if self.state.wheel.reset(key, when) {
+            return true;
+        }
+        self.schedule(when);
+        false
+    }
+
+    pub(crate) fn remove(&mut self, key: usize) {
+        self.state.wheel.remove(key);
+    }
+
+    // Wake the driver thread if it would otherwise oversleep `when`.
+    fn schedule(&mut self, when: u64) {
+        if self.state.next_wake.map_or(true, |next| when < next) {
+            self.state.next_wake = Some(when);
+            self.shared.condvar.notify_one();
+        }
+    }
+}
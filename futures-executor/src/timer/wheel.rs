@@ -0,0 +1,259 @@
+//! A hierarchical hashed timing wheel.
+//!
+//! Time is measured in ticks (milliseconds since the timer was created). The
+//! wheel has `NUM_LEVELS` levels of `LEVEL_SLOTS` slots each. A slot on level
+//! `n` covers `LEVEL_SLOTS^n` ticks, so level 0 has millisecond resolution and
+//! the top level spans a little over two years. Timers further out than that
+//! are parked in the top level and re-inserted each time its slot comes round.
+//!
+//! Each slot is an intrusive doubly-linked list threaded through a slab of
+//! entries, so inserting and cancelling a timer are both O(1). When a slot on
+//! a level above 0 expires, its timers cascade down to a finer level; a timer
+//! therefore moves at most `NUM_LEVELS` times before it fires.
+
+use futures_core::task::Waker;
+use slab::Slab;
+
+const LEVEL_BITS: usize = 6;
+const LEVEL_SLOTS: usize = 1 << LEVEL_BITS;
+const NUM_LEVELS: usize = 6;
+
+/// The maximum number of ticks a timer can be scheduled ahead of the wheel
+/// before it has to be re-inserted into the top level.
+const MAX_DURATION: u64 = (1 << (LEVEL_BITS * NUM_LEVELS)) - 1;
+
+#[derive(Debug)]
+pub(super) struct Wheel {
+    /// The tick up to which all timers have been processed.
+    elapsed: u64,
+    levels: [Level; NUM_LEVELS],
+    entries: Slab<Entry>,
+}
+
+#[derive(Debug)]
+struct Level {
+    /// Bit `n` is set iff `slots[n]` is non-empty.
+    occupied: u64,
+    /// Head of each slot's entry list.
+    slots: [Option<usize>; LEVEL_SLOTS],
+}
+
+#[derive(Debug)]
+struct Entry {
+    when: u64,
+    waker: Option<Waker>,
+    state: State,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Pending { level: usize, slot: usize },
+    Fired,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Expiration {
+    level: usize,
+    slot: usize,
+    deadline: u64,
+}
+
+impl Wheel {
+    pub(super) fn new() -> Self {
+        Self {
+            elapsed: 0,
+            levels: [
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+                Level::new(),
+            ],
+            entries: Slab::new(),
+        }
+    }
+
+    /// Adds a timer firing at tick `when`.
+    ///
+    /// Returns `None` if `when` has already been processed, in which case the
+    /// timer is considered fired and nothing is stored.
+    pub(super) fn insert(&mut self, when: u64, waker: &Waker) -> Option<usize> {
+        if when <= self.elapsed {
+            return None;
+        }
+        let key = self.entries.insert(Entry {
+            when,
+            waker: Some(waker.clone()),
+            state: State::Fired,
+            prev: None,
+            next: None,
+        });
+        self.link(key);
+        Some(key)
+    }
+
+    /// Updates the waker of the timer `key`, returning `true` if it has
+    /// already fired.
+    pub(super) fn poll(&mut self, key: usize, waker: &Waker) -> bool {
+        let entry = &mut self.entries[key];
+        if entry.state == State::Fired {
+            return true;
+        }
+        match &entry.waker {
+            Some(w) if w.will_wake(waker) => {}
+            _ => entry.waker = Some(waker.clone()),
+        }
+        false
+    }
+
+    /// Moves the timer `key` to fire at tick `when`, re-arming it if it had
+    /// already fired. Returns `true` if `when` has already been processed.
+    pub(super) fn reset(&mut self, key: usize, when: u64) -> bool {
+        self.unlink(key);
+        self.entries[key].when = when;
+        if when <= self.elapsed {
+            return true;
+        }
+        self.link(key);
+        false
+    }
+
+    /// Cancels and forgets the timer `key`.
+    pub(super) fn remove(&mut self, key: usize) {
+        self.unlink(key);
+        self.entries.remove(key);
+    }
+
+    /// The tick at which the next slot expires, if any timer is pending.
+    pub(super) fn next_expiration_tick(&self) -> Option<u64> {
+        self.next_expiration().map(|expiration| expiration.deadline)
+    }
+
+    /// Fires every timer due at or before tick `now`, returning their wakers.
+    pub(super) fn advance(&mut self, now: u64) -> Vec<Waker> {
+        let mut wakers = Vec::new();
+        loop {
+            match self.next_expiration() {
+                Some(expiration) if expiration.deadline <= now => {
+                    self.elapsed = expiration.deadline;
+                    self.process(expiration, &mut wakers);
+                }
+                _ => break,
+            }
+        }
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+        wakers
+    }
+
+    fn process(&mut self, expiration: Expiration, wakers: &mut Vec<Waker>) {
+        let level = &mut self.levels[expiration.level];
+        let mut cur = level.slots[expiration.slot].take();
+        level.occupied &= !(1 << expiration.slot);
+
+        while let Some(key) = cur {
+            let entry = &mut self.entries[key];
+            cur = entry.next;
+            entry.state = State::Fired;
+            entry.prev = None;
+            entry.next = None;
+            if entry.when <= expiration.deadline {
+                wakers.extend(entry.waker.take());
+            } else {
+                // Cascade down to a finer-grained level.
+                self.link(key);
+            }
+        }
+    }
+
+    fn next_expiration(&self) -> Option<Expiration> {
+        for (level, lvl) in self.levels.iter().enumerate() {
+            if lvl.occupied == 0 {
+                continue;
+            }
+            let slot_range = 1u64 << (LEVEL_BITS * level);
+            let level_range = slot_range << LEVEL_BITS;
+
+            let now_slot = ((self.elapsed / slot_range) % LEVEL_SLOTS as u64) as u32;
+            let rotated = lvl.occupied.rotate_right(now_slot);
+            let slot = ((rotated.trailing_zeros() + now_slot) % LEVEL_SLOTS as u32) as usize;
+
+            let level_start = self.elapsed & !(level_range - 1);
+            let mut deadline = level_start + slot as u64 * slot_range;
+            if deadline <= self.elapsed {
+                // Only the top level wraps around: its slots act as a ring
+                // buffer for timers more than `MAX_DURATION` ticks away.
+                deadline += level_range;
+            }
+            return Some(Expiration { level, slot, deadline });
+        }
+        None
+    }
+
+    fn link(&mut self, key: usize) {
+        let when = self.entries[key].when;
+        let level = level_for(self.elapsed, when);
+        let slot = ((when >> (LEVEL_BITS * level)) % LEVEL_SLOTS as u64) as usize;
+
+        let head = self.levels[level].slots[slot];
+        if let Some(head) = head {
+            self.entries[head].prev = Some(key);
+        }
+        let entry = &mut self.entries[key];
+        entry.state = State::Pending { level, slot };
+        entry.prev = None;
+        entry.next = head;
+
+        let lvl = &mut self.levels[level];
+        lvl.slots[slot] = Some(key);
+        lvl.occupied |= 1 << slot;
+    }
+
+    fn unlink(&mut self, key: usize) {
+        let entry = &mut self.entries[key];
+        let (level, slot) = match entry.state {
+            State::Pending { level, slot } => (level, slot),
+            State::Fired => return,
+        };
+        let prev = entry.prev.take();
+        let next = entry.next.take();
+        entry.state = State::Fired;
+
+        match prev {
+            Some(prev) => self.entries[prev].next = next,
+            None => self.levels[level].slots[slot] = next,
+        }
+        if let Some(next) = next {
+            self.entries[next].prev = prev;
+        }
+        if next.is_none() && prev.is_none() {
+            self.levels[level].occupied &= !(1 << slot);
+        }
+    }
+}
+
+impl Level {
+    fn new() -> Self {
+        Self {
+            occupied: 0,
+            slots: [None; LEVEL_SLOTS],
+        }
+    }
+}
+
+/// Picks the level whose slot granularity is the coarsest that still
+/// separates `when` from `elapsed`.
+fn level_for(elapsed: u64, when: u64) -> usize {
+    const SLOT_MASK: u64 = (1 << LEVEL_BITS) - 1;
+
+    let mut masked = (elapsed ^ when) | SLOT_MASK;
+    if masked >= MAX_DURATION {
+        masked = MAX_DURATION - 1;
+    }
+    let significant = 63 - masked.leading_zeros() as usize;
+    significant / LEVEL_BITS
+}
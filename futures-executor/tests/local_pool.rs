@@ -6,7 +6,7 @@ use std::cell::{Cell, RefCell};
 use std::pin::Pin;
 use std::rc::Rc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::sync::Arc;
 use std::sync::atomic::{Ordering, AtomicBool};
 
@@ -385,3 +385,64 @@ fn park_unpark_independence() {
     futures::executor::block_on(future)
 }
 
+
+#[test]
+fn run_until_timeout_completes() {
+    let mut pool = LocalPool::new();
+    let res = pool.run_until_timeout(future::ready(3), Duration::from_secs(0));
+    assert_eq!(res.ok(), Some(3));
+}
+
+#[test]
+fn run_until_timeout_returns_unfinished_future() {
+    let (tx, rx) = oneshot::channel();
+    let mut pool = LocalPool::new();
+    let spawn = pool.spawner();
+
+    let start = Instant::now();
+    let rx = pool.run_until_timeout(rx, Duration::from_millis(20)).unwrap_err().into_inner();
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    spawn.spawn_local_obj(Box::pin(lazy(move |_| {
+        tx.send(7).unwrap();
+    })).into()).unwrap();
+    assert_eq!(pool.run_until_timeout(rx, Duration::from_secs(10)).ok(), Some(Ok(7)));
+}
+
+#[test]
+fn run_until_timeout_wakes_for_other_threads() {
+    let (tx, rx) = oneshot::channel();
+    let mut pool = LocalPool::new();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(()).unwrap();
+    });
+    assert!(pool.run_until_timeout(rx, Duration::from_secs(10)).is_ok());
+}
+
+#[test]
+fn block_on_timeout_gives_up_on_busy_future() {
+    use futures::executor::block_on_timeout;
+
+    // A future that always wakes itself must not keep the executor past the
+    // deadline.
+    let mut polls = 0;
+    let busy = poll_fn(|cx| {
+        polls += 1;
+        cx.waker().wake_by_ref();
+        Poll::<()>::Pending
+    });
+
+    assert!(block_on_timeout(busy, Duration::from_millis(10)).is_err());
+    assert!(polls > 1);
+}
+
+#[test]
+fn block_on_timeout_polls_at_least_once() {
+    use futures::executor::block_on_timeout;
+
+    assert_eq!(block_on_timeout(future::ready(1), Duration::from_secs(0)).ok(), Some(1));
+
+    let err = block_on_timeout(future::pending::<()>(), Duration::from_secs(0)).unwrap_err();
+    assert_eq!(err.to_string(), "future did not complete before the timeout");
+}
@@ -0,0 +1,185 @@
+#![cfg(target_os = "linux")]
+
+use futures::channel::oneshot;
+use futures::executor::{block_on_with_park, LocalPool, Park};
+use futures::future::{self, Future};
+use futures::task::{ArcWake, Context, Poll, Waker};
+use std::cell::RefCell;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Wakes the executor by writing to an eventfd.
+struct EventFd {
+    fd: RawFd,
+    wakes: AtomicUsize,
+}
+
+impl EventFd {
+    fn new() -> Self {
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        assert!(fd >= 0, "eventfd: {}", io::Error::last_os_error());
+        Self { fd, wakes: AtomicUsize::new(0) }
+    }
+
+    fn drain(&self) {
+        let mut buf = [0u8; 8];
+        let n = unsafe { libc::read(self.fd, buf.as_mut_ptr() as *mut _, buf.len()) };
+        assert!(n == 8 || io::Error::last_os_error().kind() == io::ErrorKind::WouldBlock);
+    }
+}
+
+impl ArcWake for EventFd {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.wakes.fetch_add(1, Ordering::SeqCst);
+        let buf = 1u64.to_ne_bytes();
+        let n = unsafe { libc::write(arc_self.fd, buf.as_ptr() as *const _, buf.len()) };
+        assert_eq!(n, 8);
+    }
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+type Registry = Rc<RefCell<Vec<(RawFd, Waker)>>>;
+
+/// A driver that sleeps in `poll(2)` on its eventfd and on every file
+/// descriptor a task is waiting for, waking those tasks once their file
+/// descriptor is readable.
+struct EventFdPark {
+    eventfd: Arc<EventFd>,
+    registry: Registry,
+    parks: usize,
+    timed_parks: usize,
+}
+
+impl EventFdPark {
+    fn new() -> Self {
+        Self {
+            eventfd: Arc::new(EventFd::new()),
+            registry: Default::default(),
+            parks: 0,
+            timed_parks: 0,
+        }
+    }
+
+    fn poll_events(&mut self, timeout_ms: libc::c_int) {
+        let mut fds = vec![libc::pollfd { fd: self.eventfd.fd, events: libc::POLLIN, revents: 0 }];
+        fds.extend(self.registry.borrow().iter().map(|&(fd, _)| libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        }));
+
+        let n = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout_ms) };
+        assert!(n >= 0, "poll: {}", io::Error::last_os_error());
+
+        if fds[0].revents != 0 {
+            self.eventfd.drain();
+        }
+        let ready: Vec<RawFd> = fds[1..].iter().filter(|p| p.revents != 0).map(|p| p.fd).collect();
+        let wakers: Vec<Waker> = {
+            let mut registry = self.registry.borrow_mut();
+            let (fired, waiting) = registry.drain(..).partition(|(fd, _)| ready.contains(fd));
+            *registry = waiting;
+            fired.into_iter().map(|(_, waker)| waker).collect::<Vec<_>>()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Park for EventFdPark {
+    type Unpark = EventFd;
+
+    fn unpark(&self) -> Arc<EventFd> {
+        self.eventfd.clone()
+    }
+
+    fn park(&mut self) {
+        self.parks += 1;
+        self.poll_events(-1);
+    }
+
+    fn park_timeout(&mut self, duration: Duration) {
+        self.parks += 1;
+        self.timed_parks += 1;
+        self.poll_events(duration.as_millis() as libc::c_int);
+    }
+}
+
+/// Completes once `fd` is readable, as reported by the driver.
+struct Readable {
+    fd: RawFd,
+    registry: Registry,
+}
+
+impl Future for Readable {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut pfd = libc::pollfd { fd: self.fd, events: libc::POLLIN, revents: 0 };
+        if unsafe { libc::poll(&mut pfd, 1, 0) } == 1 {
+            return Poll::Ready(());
+        }
+        self.registry.borrow_mut().push((self.fd, cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+#[test]
+fn wakeups_go_through_the_driver() {
+    let mut driver = EventFdPark::new();
+    let (tx, rx) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(5).unwrap();
+    });
+
+    let mut pool = LocalPool::with_park(&mut driver);
+    assert_eq!(pool.run_until(rx), Ok(5));
+    drop(pool);
+
+    assert!(driver.parks >= 1);
+    assert_eq!(driver.eventfd.wakes.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn driver_dispatches_readiness_while_idle() {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let (read_fd, write_fd) = (fds[0], fds[1]);
+
+    let mut driver = EventFdPark::new();
+    let readable = Readable { fd: read_fd, registry: driver.registry.clone() };
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(unsafe { libc::write(write_fd, b"x".as_ptr() as *const _, 1) }, 1);
+    });
+
+    block_on_with_park(readable, &mut driver);
+    writer.join().unwrap();
+    assert!(driver.parks >= 1);
+    assert!(driver.registry.borrow().is_empty());
+
+    unsafe {
+        libc::close(read_fd);
+        libc::close(write_fd);
+    }
+}
+
+#[test]
+fn timeouts_use_park_timeout() {
+    let mut pool = LocalPool::with_park(EventFdPark::new());
+    let res = pool.run_until_timeout(future::pending::<()>(), Duration::from_millis(20));
+    assert!(res.is_err());
+    assert!(pool.get_park().timed_parks >= 1);
+    assert_eq!(pool.get_park().parks, pool.get_park().timed_parks);
+}
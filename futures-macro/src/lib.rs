@@ -17,6 +17,8 @@ use proc_macro_hack::proc_macro_hack;
 
 mod join;
 mod select;
+mod stream;
+mod test;
 
 /// The `join!` macro.
 #[proc_macro_hack]
@@ -41,3 +43,27 @@ pub fn select_internal(input: TokenStream) -> TokenStream {
 pub fn select_biased_internal(input: TokenStream) -> TokenStream {
     crate::select::select_biased(input)
 }
+
+/// The `select_loop!` macro.
+#[proc_macro_hack]
+pub fn select_loop_internal(input: TokenStream) -> TokenStream {
+    crate::select::select_loop(input)
+}
+
+/// The `stream!` macro.
+#[proc_macro_hack]
+pub fn stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::stream(input)
+}
+
+/// The `try_stream!` macro.
+#[proc_macro_hack]
+pub fn try_stream_internal(input: TokenStream) -> TokenStream {
+    crate::stream::try_stream(input)
+}
+
+/// The `#[test]` attribute.
+#[proc_macro_attribute]
+pub fn test_internal(args: TokenStream, item: TokenStream) -> TokenStream {
+    crate::test::test(args, item)
+}
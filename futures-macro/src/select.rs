@@ -5,16 +5,24 @@ use proc_macro2::Span;
 use quote::{format_ident, quote};
 use syn::{parse_quote, Expr, Ident, Pat, Token};
 use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
 
 mod kw {
     syn::custom_keyword!(complete);
+    syn::custom_keyword!(on_all_complete);
 }
 
 struct Select {
     // span of `complete`, then expression after `=> ...`
     complete: Option<Expr>,
     default: Option<Expr>,
+    // `select_loop!` only
+    on_all_complete: Option<Expr>,
     normal_fut_exprs: Vec<Expr>,
+    // Whether the branch is `<pat> in <stream>` (`select_loop!` only) rather
+    // than `<pat> = <future>`.
+    normal_fut_is_stream: Vec<bool>,
+    normal_fut_guards: Vec<Option<Expr>>,
     normal_fut_handlers: Vec<(Pat, Expr)>,
 }
 
@@ -22,7 +30,8 @@ struct Select {
 enum CaseKind {
     Complete,
     Default,
-    Normal(Pat, Expr),
+    OnAllComplete,
+    Normal(Pat, Expr, bool, Option<Expr>),
 }
 
 impl Parse for Select {
@@ -30,7 +39,10 @@ impl Parse for Select {
         let mut select = Self {
             complete: None,
             default: None,
+            on_all_complete: None,
             normal_fut_exprs: vec![],
+            normal_fut_is_stream: vec![],
+            normal_fut_guards: vec![],
             normal_fut_handlers: vec![],
         };
 
@@ -49,12 +61,31 @@ impl Parse for Select {
                 }
                 input.parse::<Ident>()?;
                 CaseKind::Default
+            } else if input.peek(kw::on_all_complete) {
+                // `on_all_complete`
+                if select.on_all_complete.is_some() {
+                    return Err(input.error("multiple `on_all_complete` cases found, only one allowed"));
+                }
+                input.parse::<kw::on_all_complete>()?;
+                CaseKind::OnAllComplete
             } else {
-                // `<pat> = <expr>`
+                // `<pat> = <expr>` or `<pat> in <expr>`
                 let pat = input.parse()?;
-                input.parse::<Token![=]>()?;
+                let is_stream = input.parse::<Option<Token![in]>>()?.is_some();
+                if !is_stream {
+                    input.parse::<Token![=]>()?;
+                }
                 let expr = input.parse()?;
-                CaseKind::Normal(pat, expr)
+
+                // `, if <expr>`
+                let guard = if input.peek(Token![,]) && input.peek2(Token![if]) {
+                    input.parse::<Token![,]>()?;
+                    input.parse::<Token![if]>()?;
+                    Some(input.parse()?)
+                } else {
+                    None
+                };
+                CaseKind::Normal(pat, expr, is_stream, guard)
             };
 
             // `=> <expr>`
@@ -73,8 +104,11 @@ impl Parse for Select {
             match case_kind {
                 CaseKind::Complete => select.complete = Some(expr),
                 CaseKind::Default => select.default = Some(expr),
-                CaseKind::Normal(pat, fut_expr) => {
+                CaseKind::OnAllComplete => select.on_all_complete = Some(expr),
+                CaseKind::Normal(pat, fut_expr, is_stream, guard) => {
                     select.normal_fut_exprs.push(fut_expr);
+                    select.normal_fut_is_stream.push(is_stream);
+                    select.normal_fut_guards.push(guard);
                     select.normal_fut_handlers.push((pat, expr));
                 },
             }
@@ -134,6 +168,22 @@ pub(crate) fn select_biased(input: TokenStream) -> TokenStream {
 fn select_inner(input: TokenStream, random: bool) -> TokenStream {
     let parsed = syn::parse_macro_input!(input as Select);
 
+    if let Some(on_all_complete) = &parsed.on_all_complete {
+        return syn::Error::new(
+            on_all_complete.span(),
+            "`on_all_complete` is only supported by `select_loop!`, use `complete`",
+        ).to_compile_error().into();
+    }
+    let stream_branch = parsed.normal_fut_exprs.iter()
+        .zip(&parsed.normal_fut_is_stream)
+        .find(|(_, is_stream)| **is_stream);
+    if let Some((expr, _)) = stream_branch {
+        return syn::Error::new(
+            expr.span(),
+            "`<pat> in <stream>` branches are only supported by `select_loop!`",
+        ).to_compile_error().into();
+    }
+
     // should be def_site, but that's unstable
     let span = Span::call_site();
 
@@ -146,11 +196,25 @@ fn select_inner(input: TokenStream, random: bool) -> TokenStream {
         span,
     );
 
+    // Guards are evaluated once, in order with the future exprs, and disable
+    // their branch for the whole `select!` when false. Unguarded branches get
+    // no binding, so that they take no space in the enclosing future.
+    let guard_names: Vec<_> = parsed.normal_fut_guards.iter()
+        .enumerate()
+        .map(|(num, guard)| guard.as_ref().map(|_| format_ident!("__guard{}", num, span = span)))
+        .collect();
+
     // bind non-`Ident` future exprs w/ `let`
     let mut future_let_bindings = Vec::with_capacity(parsed.normal_fut_exprs.len());
     let bound_future_names: Vec<_> = parsed.normal_fut_exprs.into_iter()
-        .zip(variant_names.iter())
-        .map(|(expr, variant_name)| {
+        .zip(parsed.normal_fut_guards)
+        .zip(variant_names.iter().zip(guard_names.iter()))
+        .map(|((expr, guard), (variant_name, guard_name))| {
+            if let Some(guard) = guard {
+                future_let_bindings.push(quote! {
+                    let #guard_name: bool = #guard;
+                });
+            }
             match expr {
                 syn::Expr::Path(path) => {
                     // Don't bind futures that are already a path.
@@ -188,8 +252,10 @@ fn select_inner(input: TokenStream, random: bool) -> TokenStream {
 
     // For each future, make an `&mut dyn FnMut(&mut Context<'_>) -> Option<Poll<__PrivResult<...>>`
     // to use for polling that individual future. These will then be put in an array.
-    let poll_functions = bound_future_names.iter().zip(variant_names.iter())
-        .map(|(bound_future_name, variant_name)| {
+    let poll_functions = bound_future_names.iter()
+        .zip(variant_names.iter().zip(guard_names.iter()))
+        .map(|(bound_future_name, (variant_name, guard_name))| {
+            let disabled = guard_name.as_ref().map(|guard_name| quote!(!#guard_name ||));
             // Below we lazily create the Pin on the Future below.
             // This is done in order to avoid allocating memory in the generator
             // for the Pin variable.
@@ -203,7 +269,7 @@ fn select_inner(input: TokenStream, random: bool) -> TokenStream {
                     let mut #bound_future_name = unsafe {
                         __futures_crate::Pin::new_unchecked(&mut #bound_future_name)
                     };
-                    if __futures_crate::future::FusedFuture::is_terminated(&#bound_future_name) {
+                    if #disabled __futures_crate::future::FusedFuture::is_terminated(&#bound_future_name) {
                         __futures_crate::None
                     } else {
                         __futures_crate::Some(__futures_crate::future::FutureExt::poll_unpin(
@@ -218,15 +284,27 @@ fn select_inner(input: TokenStream, random: bool) -> TokenStream {
             }
         });
 
+    let all_terminated = quote! {
+        panic!("all futures in select! were completed or disabled by their guards,\
+                but no `complete =>` handler was provided")
+    };
     let none_polled = if parsed.complete.is_some() {
         quote! {
             __futures_crate::task::Poll::Ready(#enum_ident::Complete)
         }
-    } else {
+    } else if parsed.default.is_some() && guard_names.iter().any(Option::is_some) {
+        // A branch which is only disabled by its guard is not ready, so run
+        // `default` then. It's still a bug if all futures have completed.
+        let disabled = guard_names.iter().flatten().map(|guard_name| quote!(!#guard_name));
         quote! {
-            panic!("all futures in select! were completed,\
-                    but no `complete =>` handler was provided")
+            if false #( || #disabled )* {
+                __futures_crate::task::Poll::Pending
+            } else {
+                #all_terminated
+            }
         }
+    } else {
+        all_terminated
     };
 
     let branches = parsed.normal_fut_handlers.into_iter()
@@ -328,3 +406,165 @@ fn select_inner(input: TokenStream, random: bool) -> TokenStream {
         #execute_result_expr
     } })
 }
+
+/// The `select_loop!` macro.
+pub(crate) fn select_loop(input: TokenStream) -> TokenStream {
+    let parsed = syn::parse_macro_input!(input as Select);
+
+    if let Some(expr) = parsed.complete.as_ref().or(parsed.default.as_ref()) {
+        return syn::Error::new(
+            expr.span(),
+            "`select_loop!` does not support `complete` or `default`, use `on_all_complete`",
+        ).to_compile_error().into();
+    }
+
+    // should be def_site, but that's unstable
+    let span = Span::call_site();
+
+    let enum_ident = Ident::new("__PrivResult", span);
+
+    let (variant_names, enum_item) = declare_result_enum(
+        enum_ident.clone(),
+        parsed.normal_fut_exprs.len(),
+        true,
+        span,
+    );
+    let done_names: Vec<_> = (0..variant_names.len())
+        .map(|num| format_ident!("__done{}", num, span = span))
+        .collect();
+    let guard_names: Vec<_> = (0..variant_names.len())
+        .map(|num| format_ident!("__guard{}", num, span = span))
+        .collect();
+
+    // Unlike in `select!`, every future and stream is moved into the loop and
+    // pinned there, and the loop keeps track of which ones have finished, so
+    // that they need not be `Unpin` or fused.
+    // Safety: the shadowed value cannot be moved until it is dropped at the
+    // end of the `select_loop!` expression.
+    let bindings = parsed.normal_fut_exprs.iter()
+        .zip(variant_names.iter().zip(done_names.iter()))
+        .map(|(expr, (variant_name, done_name))| {
+            quote! {
+                let mut #variant_name = #expr;
+                let mut #variant_name = unsafe {
+                    __futures_crate::Pin::new_unchecked(&mut #variant_name)
+                };
+                let mut #done_name = false;
+            }
+        });
+
+    // Guards are evaluated again on every iteration, before polling.
+    let guards = parsed.normal_fut_guards.iter()
+        .zip(guard_names.iter())
+        .filter_map(|(guard, guard_name)| guard.as_ref().map(|guard| quote! {
+            let #guard_name: bool = #guard;
+        }));
+
+    let poll_functions = parsed.normal_fut_is_stream.iter()
+        .zip(parsed.normal_fut_guards.iter())
+        .zip(variant_names.iter().zip(done_names.iter().zip(guard_names.iter())))
+        .map(|((is_stream, guard), (variant_name, (done_name, guard_name)))| {
+            let disabled = guard.as_ref().map(|_| quote!(!#guard_name ||));
+            let poll = if *is_stream {
+                quote! {
+                    match __futures_crate::stream::StreamExt::poll_next_unpin(&mut #variant_name, __cx) {
+                        __futures_crate::task::Poll::Ready(__futures_crate::Some(item)) => {
+                            __futures_crate::Some(__futures_crate::task::Poll::Ready(
+                                #enum_ident::#variant_name(item),
+                            ))
+                        }
+                        // The stream drops out, without running its branch.
+                        __futures_crate::task::Poll::Ready(__futures_crate::None) => {
+                            *#done_name = true;
+                            __futures_crate::None
+                        }
+                        __futures_crate::task::Poll::Pending => {
+                            __futures_crate::Some(__futures_crate::task::Poll::Pending)
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    let __poll = __futures_crate::future::FutureExt::poll_unpin(
+                        &mut #variant_name,
+                        __cx,
+                    );
+                    if __poll.is_ready() {
+                        *#done_name = true;
+                    }
+                    __futures_crate::Some(__poll.map(#enum_ident::#variant_name))
+                }
+            };
+            quote! {
+                let #done_name = &mut #done_name;
+                let mut #variant_name = |__cx: &mut __futures_crate::task::Context<'_>| {
+                    if #disabled *#done_name {
+                        return __futures_crate::None;
+                    }
+                    #poll
+                };
+                let #variant_name: &mut dyn FnMut(
+                    &mut __futures_crate::task::Context<'_>
+                ) -> __futures_crate::Option<__futures_crate::task::Poll<_>> = &mut #variant_name;
+            }
+        });
+
+    let branches = parsed.normal_fut_handlers.into_iter()
+        .zip(variant_names.iter())
+        .map(|((pat, expr), variant_name)| {
+            quote! {
+                #enum_ident::#variant_name(#pat) => { #expr }
+            }
+        });
+
+    let on_all_complete = parsed.on_all_complete
+        .map(|expr| quote!(break #expr))
+        .unwrap_or_else(|| quote!(break));
+
+    TokenStream::from(quote! { {
+        #enum_item
+
+        #( #bindings )*
+
+        loop {
+            #( #guards )*
+
+            let __select_result = __futures_crate::future::poll_fn(
+                |__cx: &mut __futures_crate::task::Context<'_>| {
+                    let mut __any_polled = false;
+
+                    #( #poll_functions )*
+
+                    let mut __select_arr = [#( #variant_names ),*];
+                    __futures_crate::async_await::shuffle(&mut __select_arr);
+                    for poller in &mut __select_arr {
+                        let poller: &mut &mut dyn FnMut(
+                            &mut __futures_crate::task::Context<'_>
+                        ) -> __futures_crate::Option<__futures_crate::task::Poll<_>> = poller;
+                        match poller(__cx) {
+                            __futures_crate::Some(x @ __futures_crate::task::Poll::Ready(_)) =>
+                                return x,
+                            __futures_crate::Some(__futures_crate::task::Poll::Pending) => {
+                                __any_polled = true;
+                            }
+                            __futures_crate::None => {}
+                        }
+                    }
+
+                    if !__any_polled {
+                        __futures_crate::task::Poll::Ready(#enum_ident::Complete)
+                    } else {
+                        __futures_crate::task::Poll::Pending
+                    }
+                },
+            ).await;
+
+            match __select_result {
+                #( #branches )*
+                // `on_all_complete` may itself diverge.
+                #[allow(unreachable_code)]
+                #enum_ident::Complete => #on_all_complete,
+            }
+        }
+    } })
+}
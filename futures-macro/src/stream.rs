@@ -0,0 +1,173 @@
+//! The futures-rs `stream!` and `try_stream!` macro implementation.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Block, Expr, ExprYield, Ident, Item, Macro, Stmt};
+
+struct Body {
+    stmts: Vec<Stmt>,
+}
+
+impl Parse for Body {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Ok(Self { stmts: input.call(Block::parse_within)? })
+    }
+}
+
+// Replaces `yield value` with handing `value` to the stream, wrapped in `Ok`
+// for `try_stream!`.
+struct YieldRewriter {
+    yielder: Ident,
+    wrap_ok: bool,
+    // `yield`s found where they can't be rewritten.
+    error: Option<syn::Error>,
+}
+
+// Whether `ident` names one of the stream macros, whose `yield`s are their own.
+fn is_stream_macro(ident: &Ident) -> bool {
+    ident == "stream" || ident == "try_stream"
+}
+
+// Finds a `yield` in the arguments of a macro, outside of nested stream
+// macros.
+fn find_yield(tokens: TokenStream2) -> Option<Span> {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "yield" => return Some(ident.span()),
+            TokenTree::Ident(ident) if is_stream_macro(&ident) => {
+                if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+                    if punct.as_char() == '!' {
+                        tokens.next();
+                        tokens.next();
+                    }
+                }
+            }
+            TokenTree::Group(group) => {
+                if let Some(span) = find_yield(group.stream()) {
+                    return Some(span);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+impl VisitMut for YieldRewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            // `yield` in a nested closure or async block belongs to it, not
+            // to the stream.
+            Expr::Closure(_) | Expr::Async(_) => return,
+            Expr::Yield(ExprYield { expr: value, .. }) => {
+                let value = match value.take() {
+                    Some(mut value) => {
+                        self.visit_expr_mut(&mut value);
+                        quote!(#value)
+                    }
+                    None => quote!(()),
+                };
+                let yielder = &self.yielder;
+                let span = expr.span();
+                let value = if self.wrap_ok {
+                    quote_spanned!(span=> __futures_crate::Ok(#value))
+                } else {
+                    value
+                };
+                *expr = Expr::Verbatim(quote_spanned! {span=>
+                    #yielder.send(#value).await
+                });
+                return;
+            }
+            _ => {}
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    // The arguments of other macros are not parsed, so a `yield` in them
+    // would reach the compiler as is.
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if mac.path.segments.last().map_or(false, |segment| is_stream_macro(&segment.ident)) {
+            return;
+        }
+        if let Some(span) = find_yield(mac.tokens.clone()) {
+            let name = if self.wrap_ok { "try_stream" } else { "stream" };
+            let message = format!("`yield` inside another macro is not supported in `{}!`", name);
+            let error = syn::Error::new(span, message);
+            match &mut self.error {
+                Some(e) => e.combine(error),
+                None => self.error = Some(error),
+            }
+        }
+    }
+
+    // Items such as nested functions have their own scope, but macros in
+    // statement position are parsed as items too.
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        if let Item::Macro(item) = item {
+            // `macro_rules!` definitions are left alone.
+            if item.ident.is_none() {
+                self.visit_macro_mut(&mut item.mac);
+            }
+        }
+    }
+}
+
+fn rewrite(input: TokenStream, wrap_ok: bool) -> syn::Result<(Ident, TokenStream2)> {
+    let mut body: Body = syn::parse(input)?;
+
+    // should be def_site, but that's unstable
+    let yielder = Ident::new("__yield_tx", Span::call_site());
+    let mut rewriter = YieldRewriter { yielder: yielder.clone(), wrap_ok, error: None };
+    for stmt in &mut body.stmts {
+        rewriter.visit_stmt_mut(stmt);
+    }
+    if let Some(e) = rewriter.error {
+        return Err(e);
+    }
+    let stmts = body.stmts;
+    Ok((yielder, quote!(#( #stmts )*)))
+}
+
+/// The `stream!` macro.
+pub(crate) fn stream(input: TokenStream) -> TokenStream {
+    let (yielder, body) = match rewrite(input, false) {
+        Ok(rewritten) => rewritten,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    TokenStream::from(quote! { {
+        let (#yielder, __yield_rx) = __futures_crate::async_await::__stream::pair();
+        __futures_crate::async_await::__stream::AsyncStream::new(__yield_rx, async move {
+            #body
+        })
+    } })
+}
+
+/// The `try_stream!` macro.
+pub(crate) fn try_stream(input: TokenStream) -> TokenStream {
+    let (yielder, body) = match rewrite(input, true) {
+        Ok(rewritten) => rewritten,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    TokenStream::from(quote! { {
+        let (#yielder, __yield_rx) = __futures_crate::async_await::__stream::pair();
+        __futures_crate::async_await::__stream::AsyncStream::new(__yield_rx, async move {
+            let __res = async {
+                { #body };
+                #[allow(unreachable_code)]
+                let __done = __futures_crate::Ok(());
+                __done
+            }.await;
+            if let __futures_crate::Err(e) = __res {
+                #yielder.send(__futures_crate::Err(e)).await;
+            }
+        })
+    } })
+}
@@ -0,0 +1,187 @@
+//! The futures-rs `#[test]` attribute implementation.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{AttributeArgs, Error, ItemFn, Lit, Meta, NestedMeta};
+
+#[derive(Default)]
+struct Args {
+    // `Some` when running on a thread pool, with its number of workers.
+    thread_pool: Option<Option<usize>>,
+    timeout_ms: Option<u64>,
+    seed: Option<u64>,
+    allow_leaks: bool,
+}
+
+impl Args {
+    fn parse(args: AttributeArgs) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("thread_pool") => {
+                    parsed.thread_pool = Some(None);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("allow_leaks") => {
+                    parsed.allow_leaks = true;
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("thread_pool") => {
+                    let mut workers = None;
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("workers") => {
+                                let n = int(&nv.lit)?;
+                                if n == 0 {
+                                    return Err(Error::new(nv.lit.span(), "`workers` must be non-zero"));
+                                }
+                                workers = Some(n as usize);
+                            }
+                            nested => {
+                                return Err(Error::new(nested.span(), "expected `workers = <number>`"));
+                            }
+                        }
+                    }
+                    parsed.thread_pool = Some(workers);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("timeout") => {
+                    let timeout = match &nv.lit {
+                        Lit::Str(s) => parse_duration_ms(&s.value()),
+                        _ => None,
+                    };
+                    match timeout {
+                        Some(ms) => parsed.timeout_ms = Some(ms),
+                        None => {
+                            return Err(Error::new(
+                                nv.lit.span(),
+                                "expected a duration such as \"500ms\", \"5s\" or \"1m\"",
+                            ));
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("seed") => {
+                    parsed.seed = Some(int(&nv.lit)?);
+                }
+                arg => {
+                    return Err(Error::new(
+                        arg.span(),
+                        "unknown option, expected `thread_pool`, `thread_pool(workers = <number>)`, \
+                         `timeout = \"<duration>\"`, `seed = <number>` or `allow_leaks`",
+                    ));
+                }
+            }
+        }
+
+        if parsed.thread_pool.is_some() && parsed.seed.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "`seed` needs the single-threaded executor, and cannot be used with `thread_pool`",
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+fn int(lit: &Lit) -> syn::Result<u64> {
+    match lit {
+        Lit::Int(n) => n.base10_parse(),
+        _ => Err(Error::new(lit.span(), "expected an integer")),
+    }
+}
+
+// Parses "<number><unit>", with a unit of `ms`, `s` or `m`.
+fn parse_duration_ms(s: &str) -> Option<u64> {
+    let digits = s.find(|c: char| !c.is_ascii_digit())?;
+    let n: u64 = s[..digits].parse().ok()?;
+    let scale = match &s[digits..] {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        _ => return None,
+    };
+    n.checked_mul(scale)
+}
+
+/// The `#[test]` attribute.
+pub(crate) fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as AttributeArgs);
+    let item = syn::parse_macro_input!(item as ItemFn);
+
+    match expand(args, item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(args: AttributeArgs, mut item: ItemFn) -> syn::Result<TokenStream2> {
+    let args = Args::parse(args)?;
+
+    let sig = &item.sig;
+    if sig.asyncness.is_none() {
+        return Err(Error::new(sig.fn_token.span(), "the test function must be `async`"));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(sig.generics.span(), "the test function cannot be generic"));
+    }
+    if sig.inputs.len() > 1 {
+        return Err(Error::new(
+            sig.inputs.span(),
+            "the test function can only take a spawner as its argument",
+        ));
+    }
+
+    let name = item.sig.ident.clone();
+    let output = item.sig.output.clone();
+    let vis = item.vis.clone();
+    // Attributes such as `#[should_panic]` and `#[ignore]` apply to the test.
+    let attrs = std::mem::replace(&mut item.attrs, Vec::new());
+
+    item.sig.ident = format_ident!("__futures_test_{}", name);
+    let inner = &item.sig.ident;
+    let call = if item.sig.inputs.is_empty() {
+        quote!(|_| #inner())
+    } else {
+        quote!(#inner)
+    };
+
+    let timeout = match args.timeout_ms {
+        Some(ms) => quote!(::std::option::Option::Some(::std::time::Duration::from_millis(#ms))),
+        None => quote!(::std::option::Option::None),
+    };
+    let seed = match args.seed {
+        Some(seed) => quote!(::std::option::Option::Some(#seed)),
+        None => quote!(::std::option::Option::None),
+    };
+    let leak_check = !args.allow_leaks;
+    let config = quote! {
+        ::futures_test::__private::TestConfig {
+            timeout: #timeout,
+            seed: #seed,
+            leak_check: #leak_check,
+        }
+    };
+
+    let run = match args.thread_pool {
+        None => quote! {
+            ::futures_test::__private::run_local(#config, #call)
+        },
+        Some(workers) => {
+            let workers = match workers {
+                Some(n) => quote!(::std::option::Option::Some(#n)),
+                None => quote!(::std::option::Option::None),
+            };
+            quote! {
+                ::futures_test::__private::run_thread_pool(#config, #workers, #call)
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[test]
+        #( #attrs )*
+        #vis fn #name() #output {
+            #item
+            #run
+        }
+    })
+}
@@ -0,0 +1,140 @@
+//! Utilities for checking that futures are safe to cancel.
+//!
+//! A future is cancelled when it is dropped before it completes, which
+//! happens, for example, to the branches of [`select!`](futures_util::select)
+//! that lose. Futures that have done part of their work by then, such as
+//! taking a message out of a queue, can lose it.
+//! [`check_cancel_safety`] drops a future at each of the points where it can
+//! be cancelled in turn, and checks that an invariant still holds after each
+//! of them.
+
+use crate::future::FutureTestExt;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use std::any::type_name;
+use std::pin::Pin;
+
+/// Checks that the futures built by `make_future` can be dropped at any point
+/// without breaking `invariant`.
+///
+/// Each future is run on [`block_on`](futures_executor::block_on). The first
+/// one is dropped before it is polled, the next one after it has returned
+/// [`Poll::Pending`] for the 1st time, the next one after the 2nd time, and
+/// so on, until a future completes before it can be dropped. The futures are
+/// wrapped in [`InterleavePending`](crate::future::InterleavePending) to be
+/// dropped before their first poll, which is not counted as one of their
+/// `Poll::Pending`s. `invariant` is checked after each of these runs,
+/// including the last one, and must return `true` if it holds.
+///
+/// Returns the number of points at which a future was dropped, which is one
+/// more than the number of times the future returns `Poll::Pending`.
+///
+/// # Panics
+///
+/// Panics if `invariant` returns `false`, with the type name of the future
+/// and the number of the `Poll::Pending` after which it was dropped, if it
+/// was polled at all.
+///
+/// # Examples
+///
+/// ```
+/// use futures_test::cancel::check_cancel_safety;
+/// use futures_test::future::FutureTestExt;
+/// use std::cell::RefCell;
+/// use std::collections::VecDeque;
+///
+/// let queue = RefCell::new((0..10).collect::<VecDeque<u32>>());
+/// let handled = RefCell::new(Vec::new());
+///
+/// let points = check_cancel_safety(
+///     || async {
+///         // Only take a message once it can be handled without suspending.
+///         async {}.pending_once().await;
+///         let message = queue.borrow_mut().pop_front().unwrap();
+///         handled.borrow_mut().push(message);
+///     },
+///     || queue.borrow().len() + handled.borrow().len() == 10,
+/// );
+/// assert_eq!(points, 2);
+/// ```
+///
+/// ```should_panic
+/// use futures_test::cancel::check_cancel_safety;
+/// use futures_test::future::FutureTestExt;
+/// use std::cell::RefCell;
+/// use std::collections::VecDeque;
+///
+/// let queue = RefCell::new((0..10).collect::<VecDeque<u32>>());
+/// let handled = RefCell::new(Vec::new());
+///
+/// // Panics: the message is lost if the future is dropped while it waits.
+/// check_cancel_safety(
+///     || async {
+///         let message = queue.borrow_mut().pop_front().unwrap();
+///         async {}.pending_once().await;
+///         handled.borrow_mut().push(message);
+///     },
+///     || queue.borrow().len() + handled.borrow().len() == 10,
+/// );
+/// ```
+pub fn check_cancel_safety<F, Fut, I>(mut make_future: F, mut invariant: I) -> usize
+where
+    F: FnMut() -> Fut,
+    Fut: Future,
+    I: FnMut() -> bool,
+{
+    let mut cancel_at = 0;
+    loop {
+        cancel_at += 1;
+        let completed = futures_executor::block_on(CancelAfter {
+            future: Some(Box::pin(make_future().interleave_pending())),
+            remaining: cancel_at,
+        });
+        if !invariant() {
+            if completed {
+                panic!("invariant broken after running `{}` to completion", type_name::<Fut>());
+            }
+            if cancel_at == 1 {
+                panic!(
+                    "invariant broken after dropping `{}` before it was polled",
+                    type_name::<Fut>(),
+                );
+            }
+            // The first `Poll::Pending` is the one inserted by
+            // `InterleavePending` before the future is polled.
+            panic!(
+                "invariant broken after dropping `{}` at `Poll::Pending` number {}",
+                type_name::<Fut>(),
+                cancel_at - 1,
+            );
+        }
+        if completed {
+            return cancel_at - 1;
+        }
+    }
+}
+
+// Runs a future until it has returned `Poll::Pending` a given number of
+// times, then drops it. Resolves to whether the future completed first.
+struct CancelAfter<Fut> {
+    future: Option<Pin<Box<Fut>>>,
+    remaining: usize,
+}
+
+impl<Fut: Future> Future for CancelAfter<Fut> {
+    type Output = bool;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<bool> {
+        let future = self.future.as_mut().expect("polled after completion");
+        if future.as_mut().poll(cx).is_ready() {
+            self.future = None;
+            return Poll::Ready(true);
+        }
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.future = None;
+            return Poll::Ready(false);
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,181 @@
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_io::{self as io, AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use pin_project::pin_project;
+use std::{
+    cmp, fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Wrapper that fails once a given number of items or bytes have gone
+/// through it.
+///
+/// See the `fail_after` methods on:
+/// * [`StreamTestExt`](crate::stream::StreamTestExt::fail_after)
+/// * [`SinkTestExt`](crate::sink::SinkTestExt::fail_after_sink)
+/// * [`AsyncReadTestExt`](crate::io::AsyncReadTestExt::fail_after)
+/// * [`AsyncWriteTestExt`](crate::io::AsyncWriteTestExt::fail_after_write)
+#[pin_project]
+pub struct FailAfter<T, E> {
+    #[pin]
+    inner: T,
+    remaining: usize,
+    error: E,
+    // Whether a stream has produced its error, and so ended.
+    failed: bool,
+}
+
+impl<T: fmt::Debug, E> fmt::Debug for FailAfter<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailAfter")
+            .field("inner", &self.inner)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl<T, E> FailAfter<T, E> {
+    pub(crate) fn new(inner: T, remaining: usize, error: E) -> Self {
+        Self {
+            inner,
+            remaining,
+            error,
+            failed: false,
+        }
+    }
+
+    /// Returns the number of items or bytes left before the wrapper fails.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Acquires a reference to the underlying object that this adaptor is
+    /// wrapping.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Acquires a mutable reference to the underlying object that this
+    /// adaptor is wrapping.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Acquires a pinned mutable reference to the underlying object that
+    /// this adaptor is wrapping.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner
+    }
+
+    /// Consumes this adaptor returning the underlying object.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<St, F> Stream for FailAfter<St, F>
+where
+    St: Stream,
+    F: FnMut() -> St::Item,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.failed {
+            return Poll::Ready(None);
+        }
+        if *this.remaining == 0 {
+            *this.failed = true;
+            return Poll::Ready(Some((this.error)()));
+        }
+        let item = ready!(this.inner.poll_next(cx));
+        if item.is_some() {
+            *this.remaining -= 1;
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl<St, F> FusedStream for FailAfter<St, F>
+where
+    St: FusedStream,
+    F: FnMut() -> St::Item,
+{
+    fn is_terminated(&self) -> bool {
+        self.failed || (self.remaining > 0 && self.inner.is_terminated())
+    }
+}
+
+impl<Si, F, Item> Sink<Item> for FailAfter<Si, F>
+where
+    Si: Sink<Item>,
+    F: FnMut() -> Si::Error,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        let this = self.project();
+        if *this.remaining == 0 {
+            return Err((this.error)());
+        }
+        *this.remaining -= 1;
+        this.inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for FailAfter<R, io::ErrorKind> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        if *this.remaining == 0 && !buf.is_empty() {
+            return Poll::Ready(Err((*this.error).into()));
+        }
+        let limit = cmp::min(*this.remaining, buf.len());
+        let n = ready!(this.inner.poll_read(cx, &mut buf[..limit]))?;
+        *this.remaining -= n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for FailAfter<W, io::ErrorKind> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        if *this.remaining == 0 && !buf.is_empty() {
+            return Poll::Ready(Err((*this.error).into()));
+        }
+        let limit = cmp::min(*this.remaining, buf.len());
+        let n = ready!(this.inner.poll_write(cx, &buf[..limit]))?;
+        *this.remaining -= n;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
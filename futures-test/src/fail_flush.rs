@@ -0,0 +1,130 @@
+use futures_io::{self as io, AsyncWrite};
+use futures_sink::Sink;
+use pin_project::pin_project;
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Wrapper whose `poll_flush` fails on a given call.
+///
+/// See the `fail_flush` methods on:
+/// * [`SinkTestExt`](crate::sink::SinkTestExt::fail_flush)
+/// * [`AsyncWriteTestExt`](crate::io::AsyncWriteTestExt::fail_flush_write)
+#[pin_project]
+pub struct FailFlush<T, E> {
+    #[pin]
+    inner: T,
+    // The number of calls to `poll_flush` left before the failing one, or
+    // `None` once it has failed.
+    remaining: Option<usize>,
+    error: E,
+}
+
+impl<T: fmt::Debug, E> fmt::Debug for FailFlush<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailFlush")
+            .field("inner", &self.inner)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl<T, E> FailFlush<T, E> {
+    pub(crate) fn new(inner: T, n: usize, error: E) -> Self {
+        assert!(n > 0, "calls to `poll_flush` are counted from 1");
+        Self {
+            inner,
+            remaining: Some(n - 1),
+            error,
+        }
+    }
+
+    /// Acquires a reference to the underlying object that this adaptor is
+    /// wrapping.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Acquires a mutable reference to the underlying object that this
+    /// adaptor is wrapping.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Acquires a pinned mutable reference to the underlying object that
+    /// this adaptor is wrapping.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner
+    }
+
+    /// Consumes this adaptor returning the underlying object.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    // Counts a call to `poll_flush`, and returns whether it should fail.
+    fn should_fail(self: Pin<&mut Self>) -> bool {
+        let remaining = self.project().remaining;
+        match *remaining {
+            Some(0) => {
+                *remaining = None;
+                true
+            }
+            Some(ref mut n) => {
+                *n -= 1;
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+impl<Si, F, Item> Sink<Item> for FailFlush<Si, F>
+where
+    Si: Sink<Item>,
+    F: FnMut() -> Si::Error,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        self.project().inner.start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.as_mut().should_fail() {
+            return Poll::Ready(Err((self.project().error)()));
+        }
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for FailFlush<W, io::ErrorKind> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.as_mut().should_fail() {
+            return Poll::Ready(Err(self.error.into()));
+        }
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::rng::Rng;
+use futures_core::stream::{FusedStream, Stream};
+use futures_io::{self as io, AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Wrapper that fails calls at random with [`Interrupted`] or
+/// [`WouldBlock`] errors.
+///
+/// The points at which errors are injected are picked by a pseudorandom
+/// number generator seeded by the caller, so a failing test can be replayed
+/// by reusing its seed. Calls that get an error are not passed on to the
+/// wrapped object.
+///
+/// See the `inject_errors` methods on:
+/// * [`StreamTestExt`](crate::stream::StreamTestExt::inject_errors)
+/// * [`SinkTestExt`](crate::sink::SinkTestExt::inject_errors_sink)
+/// * [`AsyncReadTestExt`](crate::io::AsyncReadTestExt::inject_errors)
+/// * [`AsyncWriteTestExt`](crate::io::AsyncWriteTestExt::inject_errors_write)
+///
+/// [`Interrupted`]: std::io::ErrorKind::Interrupted
+/// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+#[pin_project]
+#[derive(Debug)]
+pub struct InjectErrors<T> {
+    #[pin]
+    inner: T,
+    rng: Rng,
+    probability: f64,
+    injected: usize,
+}
+
+impl<T> InjectErrors<T> {
+    pub(crate) fn new(inner: T, seed: u64, probability: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "the probability of injecting an error must be between 0 and 1",
+        );
+        Self {
+            inner,
+            rng: Rng::new(seed),
+            probability,
+            injected: 0,
+        }
+    }
+
+    /// Returns the number of errors injected so far.
+    pub fn injected(&self) -> usize {
+        self.injected
+    }
+
+    /// Acquires a reference to the underlying object that this adaptor is
+    /// wrapping.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Acquires a mutable reference to the underlying object that this
+    /// adaptor is wrapping.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Acquires a pinned mutable reference to the underlying object that
+    /// this adaptor is wrapping.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner
+    }
+
+    /// Consumes this adaptor returning the underlying object.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn poll_with<'a, U, E: From<io::Error>>(
+        self: Pin<&'a mut Self>,
+        cx: &mut Context<'_>,
+        f: impl FnOnce(Pin<&'a mut T>, &mut Context<'_>) -> Poll<Result<U, E>>,
+    ) -> Poll<Result<U, E>> {
+        let this = self.project();
+        if let Some(e) = next_error(this.rng, *this.probability, this.injected) {
+            return Poll::Ready(Err(e.into()));
+        }
+        f(this.inner, cx)
+    }
+}
+
+// Decides whether to inject an error into the current call, and picks its kind.
+fn next_error(rng: &mut Rng, probability: f64, injected: &mut usize) -> Option<io::Error> {
+    if !rng.gen_bool(probability) {
+        return None;
+    }
+    *injected += 1;
+    let kind = if rng.gen_bool(0.5) {
+        io::ErrorKind::Interrupted
+    } else {
+        io::ErrorKind::WouldBlock
+    };
+    Some(kind.into())
+}
+
+impl<St, T, E> Stream for InjectErrors<St>
+where
+    St: Stream<Item = Result<T, E>>,
+    E: From<io::Error>,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if let Some(e) = next_error(this.rng, *this.probability, this.injected) {
+            return Poll::Ready(Some(Err(e.into())));
+        }
+        this.inner.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.size_hint().0, None)
+    }
+}
+
+impl<St, T, E> FusedStream for InjectErrors<St>
+where
+    St: FusedStream<Item = Result<T, E>>,
+    E: From<io::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<Si, Item> Sink<Item> for InjectErrors<Si>
+where
+    Si: Sink<Item>,
+    Si::Error: From<io::Error>,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_with(cx, Si::poll_ready)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        self.project().inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_with(cx, Si::poll_flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for InjectErrors<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_with(cx, |r, cx| r.poll_read(cx, buf))
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for InjectErrors<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_with(cx, |w, cx| w.poll_write(cx, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_with(cx, W::poll_flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
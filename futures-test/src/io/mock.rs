@@ -0,0 +1,301 @@
+use futures_core::ready;
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{self as io, AsyncBufRead, AsyncRead, AsyncWrite};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::thread;
+
+/// An I/O object that plays back a script of reads and writes.
+///
+/// A `MockIo` is built with a [`MockIoBuilder`], from the steps that the code
+/// under test is expected to go through, in order:
+///
+/// - [`read`](MockIoBuilder::read) steps provide data to be read. Once all
+///   steps have been consumed, reads return end of file.
+/// - [`write`](MockIoBuilder::write) steps give the bytes that must be
+///   written next. A write of any other bytes panics, showing what was
+///   expected and what was written.
+/// - [`wait_pending`](MockIoBuilder::wait_pending) steps make the next read
+///   or write return [`Poll::Pending`] once, waking the task straight away.
+/// - [`read_error`](MockIoBuilder::read_error) steps make the next read
+///   fail.
+///
+/// Reads return `Pending` while the next step is a write, and writes return
+/// `Pending` while the next step is a read, until the other side consumes
+/// that step. This lets a reader and a writer share the mock from separate
+/// tasks, but makes sequential code that does things out of order hang
+/// rather than panic.
+///
+/// Dropping a `MockIo` before its whole script has been consumed panics,
+/// unless the thread is already panicking.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::io::{AsyncReadExt, AsyncWriteExt};
+/// use futures_test::io::MockIo;
+/// use std::io::ErrorKind;
+///
+/// let mut io = MockIo::builder()
+///     .read(b"ping")
+///     .wait_pending()
+///     .write(b"pong")
+///     .read_error(ErrorKind::ConnectionReset)
+///     .build();
+///
+/// block_on(async {
+///     let mut buf = [0; 4];
+///     io.read_exact(&mut buf).await?;
+///     assert_eq!(&buf, b"ping");
+///     io.write_all(b"pong").await?;
+///
+///     let err = io.read(&mut buf).await.unwrap_err();
+///     assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+///     Ok::<(), std::io::Error>(())
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MockIo {
+    steps: VecDeque<Step>,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+/// A builder for [`MockIo`].
+///
+/// Created with [`MockIo::builder`].
+#[derive(Debug, Default)]
+pub struct MockIoBuilder {
+    steps: VecDeque<Step>,
+}
+
+enum Step {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+    WaitPending,
+    ReadError(io::ErrorKind),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Read(data) => write!(f, "read({})", Escaped(data)),
+            Step::Write(data) => write!(f, "write({})", Escaped(data)),
+            Step::WaitPending => f.write_str("wait_pending()"),
+            Step::ReadError(kind) => write!(f, "read_error({:?})", kind),
+        }
+    }
+}
+
+// Displays bytes as a byte string literal.
+struct Escaped<'a>(&'a [u8]);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("b\"")?;
+        for &byte in self.0 {
+            for c in std::ascii::escape_default(byte) {
+                write!(f, "{}", c as char)?;
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+impl MockIoBuilder {
+    /// Adds a step providing `data` to be read.
+    ///
+    /// The data may be read in any number of calls, but all of it must be
+    /// read before the script moves on to the next step.
+    pub fn read(&mut self, data: &[u8]) -> &mut Self {
+        if !data.is_empty() {
+            self.steps.push_back(Step::Read(data.to_vec()));
+        }
+        self
+    }
+
+    /// Adds a step expecting `data` to be written.
+    ///
+    /// The data may be written in any number of calls, but all of it must be
+    /// written before the script moves on to the next step.
+    pub fn write(&mut self, data: &[u8]) -> &mut Self {
+        if !data.is_empty() {
+            self.steps.push_back(Step::Write(data.to_vec()));
+        }
+        self
+    }
+
+    /// Adds a step making the next read or write return
+    /// [`Poll::Pending`](futures_core::task::Poll::Pending) once.
+    pub fn wait_pending(&mut self) -> &mut Self {
+        self.steps.push_back(Step::WaitPending);
+        self
+    }
+
+    /// Adds a step making the next read fail with an error of the given
+    /// kind.
+    pub fn read_error(&mut self, kind: io::ErrorKind) -> &mut Self {
+        self.steps.push_back(Step::ReadError(kind));
+        self
+    }
+
+    /// Creates a [`MockIo`] playing back the steps added so far.
+    ///
+    /// The builder is left empty, so it can be reused for another script.
+    pub fn build(&mut self) -> MockIo {
+        MockIo {
+            steps: std::mem::replace(&mut self.steps, VecDeque::new()),
+            read_waker: None,
+            write_waker: None,
+        }
+    }
+}
+
+impl MockIo {
+    /// Creates a builder for a new script.
+    pub fn builder() -> MockIoBuilder {
+        MockIoBuilder::default()
+    }
+
+    /// Returns whether every step of the script has been consumed.
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // Moves on to the next step, and wakes whichever side was waiting for
+    // the script to move past the current one.
+    fn advance(&mut self) {
+        self.steps.pop_front();
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl AsyncRead for MockIo {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let data = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = data.len().min(buf.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncBufRead for MockIo {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        match this.steps.front() {
+            Some(Step::Write(_)) => {
+                this.read_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            Some(Step::WaitPending) => {
+                this.advance();
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Some(&Step::ReadError(kind)) => {
+                this.advance();
+                return Poll::Ready(Err(kind.into()));
+            }
+            Some(Step::Read(_)) | None => {}
+        }
+        match this.steps.front() {
+            Some(Step::Read(data)) => Poll::Ready(Ok(data)),
+            _ => Poll::Ready(Ok(&[])),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        if amt == 0 {
+            return;
+        }
+        match this.steps.front_mut() {
+            Some(Step::Read(data)) if amt <= data.len() => {
+                data.drain(..amt);
+                if data.is_empty() {
+                    this.advance();
+                }
+            }
+            _ => panic!("MockIo: consumed more bytes than were read"),
+        }
+    }
+}
+
+impl AsyncWrite for MockIo {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        match this.steps.front_mut() {
+            None => panic!(
+                "MockIo: unexpected write after the end of the script\n    written: {}",
+                Escaped(buf),
+            ),
+            Some(Step::Write(expected)) => {
+                let n = expected.len().min(buf.len());
+                if let Some(at) = (0..n).find(|&i| expected[i] != buf[i]) {
+                    panic!(
+                        "MockIo: unexpected write, first difference at byte {}\n    \
+                         expected: {}\n     written: {}",
+                        at,
+                        Escaped(expected),
+                        Escaped(buf),
+                    );
+                }
+                expected.drain(..n);
+                if expected.is_empty() {
+                    this.advance();
+                }
+                Poll::Ready(Ok(n))
+            }
+            Some(Step::WaitPending) => {
+                this.advance();
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Some(Step::Read(_)) | Some(Step::ReadError(_)) => {
+                this.write_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for MockIo {
+    fn drop(&mut self) {
+        if !self.steps.is_empty() && !thread::panicking() {
+            panic!(
+                "MockIo: dropped before the end of the script, {} step{} left: {:?}",
+                self.steps.len(),
+                if self.steps.len() == 1 { "" } else { "s" },
+                self.steps,
+            );
+        }
+    }
+}
@@ -1,6 +1,10 @@
 //! Additional combinators for testing async IO.
 
 mod limited;
+mod short;
+
+mod mock;
+pub use mock::{MockIo, MockIoBuilder};
 
 pub mod read;
 pub use read::AsyncReadTestExt;
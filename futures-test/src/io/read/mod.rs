@@ -1,9 +1,12 @@
 //! Additional combinators for testing async readers.
 
-use futures_io::AsyncRead;
+use futures_io::{self as io, AsyncRead};
 
 pub use super::limited::Limited;
+pub use super::short::Short;
 pub use crate::assert_unmoved::AssertUnmoved;
+pub use crate::fail_after::FailAfter;
+pub use crate::inject_errors::InjectErrors;
 pub use crate::interleave_pending::InterleavePending;
 
 /// Additional combinators for testing async readers.
@@ -121,6 +124,103 @@ pub trait AsyncReadTestExt: AsyncRead {
     {
         Limited::new(self, limit)
     }
+
+    /// Reads a random number of bytes, at least one, on each call to
+    /// `poll_read`.
+    ///
+    /// The lengths are picked by a pseudorandom number generator started from
+    /// `seed`, so the same seed gives the same reads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor};
+    /// use futures_test::io::AsyncReadTestExt;
+    ///
+    /// let mut reader = Cursor::new(&[1, 2, 3, 4, 5]).short_reads(3);
+    ///
+    /// let mut buf = [0; 5];
+    /// let n = reader.read(&mut buf).await?;
+    /// assert!(n >= 1 && n <= 5);
+    ///
+    /// let mut rest = Vec::new();
+    /// reader.read_to_end(&mut rest).await?;
+    /// assert_eq!(rest, &[1, 2, 3, 4, 5][n..]);
+    /// # Ok::<(), std::io::Error>(()) })?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn short_reads(self, seed: u64) -> Short<Self>
+    where
+        Self: Sized,
+    {
+        Short::new(self, seed)
+    }
+
+    /// Reads the first `n` bytes of the reader, then fails any further read
+    /// with an error of the given `kind`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor};
+    /// use futures_test::io::AsyncReadTestExt;
+    /// use std::io;
+    ///
+    /// let mut reader = Cursor::new(&[1, 2, 3, 4, 5]).fail_after(3, io::ErrorKind::ConnectionReset);
+    ///
+    /// let mut buf = Vec::new();
+    /// let err = reader.read_to_end(&mut buf).await.unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    /// assert_eq!(buf, [1, 2, 3]);
+    /// # });
+    /// ```
+    fn fail_after(self, n: usize, kind: io::ErrorKind) -> FailAfter<Self, io::ErrorKind>
+    where
+        Self: Sized,
+    {
+        FailAfter::new(self, n, kind)
+    }
+
+    /// Fails calls to `poll_read` with an
+    /// [`Interrupted`](io::ErrorKind::Interrupted) or
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error with the given
+    /// `probability`.
+    ///
+    /// The errors are injected at points picked by a pseudorandom number
+    /// generator started from `seed`, so the same seed gives the same errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::io::{AsyncRead, Cursor};
+    /// use futures::task::Poll;
+    /// use futures_test::io::AsyncReadTestExt;
+    /// use futures_test::task::noop_context;
+    /// use futures::pin_mut;
+    ///
+    /// let reader = Cursor::new(&[1, 2, 3]).inject_errors(1, 1.0);
+    /// pin_mut!(reader);
+    ///
+    /// let mut cx = noop_context();
+    /// let mut buf = [0; 3];
+    ///
+    /// match reader.as_mut().poll_read(&mut cx, &mut buf) {
+    ///     Poll::Ready(Err(e)) => assert!(matches!(
+    ///         e.kind(),
+    ///         std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+    ///     )),
+    ///     _ => panic!("expected an injected error"),
+    /// }
+    /// assert_eq!(reader.injected(), 1);
+    /// ```
+    fn inject_errors(self, seed: u64, probability: f64) -> InjectErrors<Self>
+    where
+        Self: Sized,
+    {
+        InjectErrors::new(self, seed, probability)
+    }
 }
 
 impl<R> AsyncReadTestExt for R where R: AsyncRead {}
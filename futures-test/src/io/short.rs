@@ -0,0 +1,127 @@
+use crate::rng::Rng;
+use futures_core::ready;
+use futures_io::{self as io, AsyncBufRead, AsyncRead, AsyncWrite};
+use pin_project::pin_project;
+use std::{
+    cmp,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// I/O wrapper that reads or writes a random number of bytes on each call.
+///
+/// Every call passes on at least one byte, when given any, so the wrapped
+/// object still makes progress. The lengths are picked by a pseudorandom
+/// number generator seeded by the caller. A length is picked again only once
+/// the wrapped object has been ready for the previous one, so the lengths
+/// do not depend on how often it returns [`Poll::Pending`].
+///
+/// See the [`short_reads`] and [`short_writes`] methods.
+///
+/// [`short_reads`]: super::AsyncReadTestExt::short_reads
+/// [`short_writes`]: super::AsyncWriteTestExt::short_writes
+#[pin_project]
+#[derive(Debug)]
+pub struct Short<Io> {
+    #[pin]
+    io: Io,
+    rng: Rng,
+    // The lengths picked for a read or write which returned `Poll::Pending`.
+    read_len: Option<usize>,
+    write_len: Option<usize>,
+}
+
+impl<Io> Short<Io> {
+    pub(crate) fn new(io: Io, seed: u64) -> Self {
+        Self {
+            io,
+            rng: Rng::new(seed),
+            read_len: None,
+            write_len: None,
+        }
+    }
+
+    /// Acquires a reference to the underlying I/O object that this adaptor is
+    /// wrapping.
+    pub fn get_ref(&self) -> &Io {
+        &self.io
+    }
+
+    /// Acquires a mutable reference to the underlying I/O object that this
+    /// adaptor is wrapping.
+    pub fn get_mut(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    /// Acquires a pinned mutable reference to the underlying I/O object that
+    /// this adaptor is wrapping.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Io> {
+        self.project().io
+    }
+
+    /// Consumes this adaptor returning the underlying I/O object.
+    pub fn into_inner(self) -> Io {
+        self.io
+    }
+}
+
+// Picks the length of a read or write of at most `max` bytes, reusing the one
+// picked for the previous call if it returned `Poll::Pending`.
+fn next_len(rng: &mut Rng, pending_len: &mut Option<usize>, max: usize) -> usize {
+    match pending_len.take() {
+        Some(len) if max > 0 => cmp::min(len, max),
+        _ => rng.gen_len(max),
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for Short<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let len = next_len(this.rng, this.write_len, buf.len());
+        let res = this.io.poll_write(cx, &buf[..len]);
+        if res.is_pending() && len > 0 {
+            *this.write_len = Some(len);
+        }
+        res
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().io.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().io.poll_close(cx)
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for Short<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let len = next_len(this.rng, this.read_len, buf.len());
+        let res = this.io.poll_read(cx, &mut buf[..len]);
+        if res.is_pending() && len > 0 {
+            *this.read_len = Some(len);
+        }
+        res
+    }
+}
+
+impl<R: AsyncBufRead> AsyncBufRead for Short<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.project();
+        let buf = ready!(this.io.poll_fill_buf(cx))?;
+        Poll::Ready(Ok(&buf[..this.rng.gen_len(buf.len())]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amount: usize) {
+        self.project().io.consume(amount)
+    }
+}
@@ -1,9 +1,13 @@
 //! Additional combinators for testing async writers.
 
-use futures_io::AsyncWrite;
+use futures_io::{self as io, AsyncWrite};
 
 pub use super::limited::Limited;
+pub use super::short::Short;
 pub use crate::assert_unmoved::AssertUnmoved;
+pub use crate::fail_after::FailAfter;
+pub use crate::fail_flush::FailFlush;
+pub use crate::inject_errors::InjectErrors;
 pub use crate::interleave_pending::InterleavePending;
 pub use crate::track_closed::TrackClosed;
 
@@ -136,6 +140,126 @@ pub trait AsyncWriteTestExt: AsyncWrite {
     {
         TrackClosed::new(self)
     }
+
+    /// Writes a random number of bytes, at least one, on each call to
+    /// `poll_write`.
+    ///
+    /// The lengths are picked by a pseudorandom number generator started from
+    /// `seed`, so the same seed gives the same writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncWriteExt, Cursor};
+    /// use futures_test::io::AsyncWriteTestExt;
+    ///
+    /// let mut writer = Cursor::new(Vec::new()).short_writes(3);
+    ///
+    /// let n = writer.write(&[1, 2, 3, 4, 5]).await?;
+    /// assert!(n >= 1 && n <= 5);
+    ///
+    /// writer.write_all(&[6, 7, 8]).await?;
+    /// assert_eq!(writer.get_ref().get_ref()[n..], [6, 7, 8]);
+    /// # Ok::<(), std::io::Error>(()) })?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn short_writes(self, seed: u64) -> Short<Self>
+    where
+        Self: Sized,
+    {
+        Short::new(self, seed)
+    }
+
+    /// Writes the first `n` bytes given to the writer, then fails any further
+    /// write with an error of the given `kind`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncWriteExt, Cursor};
+    /// use futures_test::io::AsyncWriteTestExt;
+    /// use std::io;
+    ///
+    /// let mut writer = Cursor::new(Vec::new()).fail_after_write(3, io::ErrorKind::BrokenPipe);
+    ///
+    /// let err = writer.write_all(&[1, 2, 3, 4, 5]).await.unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    /// assert_eq!(writer.get_ref().get_ref(), &[1, 2, 3]);
+    /// # });
+    /// ```
+    fn fail_after_write(self, n: usize, kind: io::ErrorKind) -> FailAfter<Self, io::ErrorKind>
+    where
+        Self: Sized,
+    {
+        FailAfter::new(self, n, kind)
+    }
+
+    /// Fails the `n`th call to `poll_flush`, counting from 1, with an error of
+    /// the given `kind`, without flushing the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncWriteExt, Cursor};
+    /// use futures_test::io::AsyncWriteTestExt;
+    /// use std::io;
+    ///
+    /// let mut writer = Cursor::new(Vec::new()).fail_flush_write(2, io::ErrorKind::Other);
+    ///
+    /// writer.flush().await?;
+    /// assert_eq!(writer.flush().await.unwrap_err().kind(), io::ErrorKind::Other);
+    /// writer.flush().await?;
+    /// # Ok::<(), std::io::Error>(()) })?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn fail_flush_write(self, n: usize, kind: io::ErrorKind) -> FailFlush<Self, io::ErrorKind>
+    where
+        Self: Sized,
+    {
+        FailFlush::new(self, n, kind)
+    }
+
+    /// Fails calls to `poll_write` and `poll_flush` with an
+    /// [`Interrupted`](io::ErrorKind::Interrupted) or
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error with the given
+    /// `probability`.
+    ///
+    /// The errors are injected at points picked by a pseudorandom number
+    /// generator started from `seed`, so the same seed gives the same errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::io::{AsyncWrite, Cursor};
+    /// use futures::task::Poll;
+    /// use futures_test::io::AsyncWriteTestExt;
+    /// use futures_test::task::noop_context;
+    /// use futures::pin_mut;
+    ///
+    /// let writer = Cursor::new(Vec::new()).inject_errors_write(1, 1.0);
+    /// pin_mut!(writer);
+    ///
+    /// let mut cx = noop_context();
+    ///
+    /// match writer.as_mut().poll_write(&mut cx, &[1, 2, 3]) {
+    ///     Poll::Ready(Err(e)) => assert!(matches!(
+    ///         e.kind(),
+    ///         std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+    ///     )),
+    ///     _ => panic!("expected an injected error"),
+    /// }
+    /// assert_eq!(writer.injected(), 1);
+    /// assert!(writer.get_ref().get_ref().is_empty());
+    /// ```
+    fn inject_errors_write(self, seed: u64, probability: f64) -> InjectErrors<Self>
+    where
+        Self: Sized,
+    {
+        InjectErrors::new(self, seed, probability)
+    }
 }
 
 impl<W> AsyncWriteTestExt for W where W: AsyncWrite {}
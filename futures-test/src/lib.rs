@@ -23,6 +23,8 @@ pub mod __private {
     pub mod assert {
         pub use crate::assert::*;
     }
+
+    pub use crate::runner::{run_local, run_thread_pool, TestConfig};
 }
 
 #[macro_use]
@@ -35,6 +37,9 @@ pub mod task;
 #[cfg(feature = "std")]
 pub mod future;
 
+#[cfg(feature = "std")]
+pub mod cancel;
+
 #[cfg(feature = "std")]
 pub mod stream;
 
@@ -44,6 +49,66 @@ pub mod sink;
 #[cfg(feature = "std")]
 pub mod io;
 
+#[cfg(feature = "std")]
+pub mod time;
+
+#[cfg(feature = "std")]
+mod runner;
+
+/// Runs an `async fn` as a test.
+///
+/// By default, the test runs on a [`LocalPool`](futures_executor::LocalPool)
+/// on the test's own thread. The function can take one argument, which is
+/// given a [`TrackingSpawner`](crate::task::TrackingSpawner) to spawn tasks
+/// on the executor running the test. Once the test completes, the executor
+/// runs its other tasks until they are all stuck, and the test fails if any
+/// of them is still running.
+///
+/// The attribute accepts the following options:
+///
+/// * `thread_pool`, or `thread_pool(workers = N)`: run the test on a
+///   [`ThreadPool`](futures_executor::ThreadPool), with `N` worker threads or
+///   the default number. The test future, and so the spawner argument, must
+///   then be `Send + 'static`. Spawned tasks get whatever is left of the
+///   timeout, or one second, to finish after the test.
+/// * `timeout = "5s"`: fail the test if it has not completed after the given
+///   duration, in milliseconds (`ms`), seconds (`s`) or minutes (`m`).
+/// * `seed = N`: make the choices of [`select!`](futures_util::select) and
+///   similar macros on the test's thread reproducible. Not available with
+///   `thread_pool`.
+/// * `allow_leaks`: do not fail if spawned tasks are still running at the
+///   end of the test.
+///
+/// Other attributes on the function, such as `#[should_panic]`, apply to the
+/// test.
+///
+/// # Examples
+///
+/// ```
+/// # type TrackingSpawner<Sp> = futures_test::task::TrackingSpawner<Sp>;
+/// # type LocalSpawner = futures::executor::LocalSpawner;
+/// #[futures_test::test]
+/// async fn spawned_task_replies(spawner: TrackingSpawner<LocalSpawner>) {
+///     use futures::channel::oneshot;
+///     use futures::task::LocalSpawnExt;
+///
+///     let (tx, rx) = oneshot::channel();
+///     spawner.spawn_local(async { tx.send(42).unwrap() }).unwrap();
+///     assert_eq!(rx.await, Ok(42));
+/// }
+///
+/// #[futures_test::test(thread_pool(workers = 2), timeout = "5s")]
+/// async fn runs_on_a_thread_pool() {
+///     assert_eq!(futures::future::ready(1).await, 1);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub use futures_macro::test_internal as test;
+
 mod assert_unmoved;
+mod fail_after;
+mod fail_flush;
+mod inject_errors;
 mod interleave_pending;
+mod rng;
 mod track_closed;
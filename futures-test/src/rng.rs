@@ -0,0 +1,43 @@
+// A small seeded pseudorandom number generator, so that adaptors injecting
+// faults at random points do so reproducibly.
+//
+// Based on [xorshift*].
+//
+// [xorshift*]: https://en.wikipedia.org/wiki/Xorshift#xorshift*
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero.
+        Self(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Returns a value from `1..=n`, or 0 if `n` is 0.
+    pub(crate) fn gen_len(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            1 + (self.next() % n as u64) as usize
+        }
+    }
+
+    // Returns `true` with the given probability.
+    pub(crate) fn gen_bool(&mut self, probability: f64) -> bool {
+        // The top 53 bits, as a float in `0.0..1.0`.
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
@@ -0,0 +1,104 @@
+// Runtime support for `#[futures_test::test]`.
+
+use crate::task::TrackingSpawner;
+use futures_core::future::Future;
+use futures_executor::{block_on_timeout, LocalPool, LocalSpawner, ThreadPool};
+use futures_util::future::FutureExt;
+use futures_util::task::SpawnExt;
+use std::time::{Duration, Instant};
+
+// How long a test on a thread pool waits for its spawned tasks to finish
+// when it has no timeout.
+const LEAK_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// The options given to `#[futures_test::test]`.
+#[derive(Debug)]
+pub struct TestConfig {
+    /// How long the test may run.
+    pub timeout: Option<Duration>,
+    /// The seed for `select!` on the test's thread.
+    pub seed: Option<u64>,
+    /// Whether to fail when spawned tasks outlive the test.
+    pub leak_check: bool,
+}
+
+/// Runs `test` on a `LocalPool`.
+pub fn run_local<F, Fut>(config: TestConfig, test: F) -> Fut::Output
+where
+    F: FnOnce(TrackingSpawner<LocalSpawner>) -> Fut,
+    Fut: Future,
+{
+    if let Some(seed) = config.seed {
+        futures_util::seed_shuffle(seed);
+    }
+
+    let mut pool = LocalPool::new();
+    let spawner = TrackingSpawner::new(pool.spawner());
+    let output = {
+        let test = Box::pin(test(spawner.clone()));
+        match config.timeout {
+            Some(timeout) => match pool.run_until_timeout(test, timeout) {
+                Ok(output) => output,
+                Err(_) => panic!("test timed out after {:?}", timeout),
+            },
+            None => pool.run_until(test),
+        }
+    };
+
+    if config.leak_check {
+        pool.run_until_stalled();
+        check_leaks(spawner.running());
+    }
+    output
+}
+
+/// Runs `test` on a `ThreadPool` with `workers` threads.
+pub fn run_thread_pool<F, Fut>(config: TestConfig, workers: Option<usize>, test: F) -> Fut::Output
+where
+    F: FnOnce(TrackingSpawner<ThreadPool>) -> Fut,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send,
+{
+    let mut builder = ThreadPool::builder();
+    builder.name_prefix("futures-test-");
+    if let Some(workers) = workers {
+        builder.pool_size(workers);
+    }
+    let pool = builder.create().expect("failed to create the test thread pool");
+    let spawner = TrackingSpawner::new(pool);
+
+    // Panics in the test are resumed here by the handle.
+    let (test, handle) = test(spawner.clone()).remote_handle();
+    spawner.get_ref().spawn(test).expect("failed to spawn the test");
+
+    let start = Instant::now();
+    let output = match config.timeout {
+        Some(timeout) => match block_on_timeout(handle, timeout) {
+            Ok(output) => output,
+            Err(_) => panic!("test timed out after {:?}", timeout),
+        },
+        None => futures_executor::block_on(handle),
+    };
+
+    if config.leak_check {
+        // Other threads may still be finishing their tasks, so give them
+        // whatever is left of the timeout.
+        let grace = match config.timeout {
+            Some(timeout) => timeout.checked_sub(start.elapsed()).unwrap_or_default(),
+            None => LEAK_GRACE_PERIOD,
+        };
+        let _ = block_on_timeout(Box::pin(spawner.idle()), grace);
+        check_leaks(spawner.running());
+    }
+    output
+}
+
+fn check_leaks(running: usize) {
+    if running > 0 {
+        panic!(
+            "{} spawned task{} still running at the end of the test",
+            running,
+            if running == 1 { " was" } else { "s were" },
+        );
+    }
+}
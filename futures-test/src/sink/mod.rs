@@ -1,8 +1,12 @@
 //! Additional combinators for testing sinks.
 
 use futures_sink::Sink;
+use std::io;
 
 pub use crate::assert_unmoved::AssertUnmoved;
+pub use crate::fail_after::FailAfter;
+pub use crate::fail_flush::FailFlush;
+pub use crate::inject_errors::InjectErrors;
 pub use crate::interleave_pending::InterleavePending;
 pub use crate::track_closed::TrackClosed;
 
@@ -77,6 +81,102 @@ pub trait SinkTestExt<Item>: Sink<Item> {
     {
         TrackClosed::new(self)
     }
+
+    /// Accepts the first `n` items sent to the sink, then fails to send any
+    /// further item with the error returned by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::sink::SinkExt;
+    /// use futures_test::sink::SinkTestExt;
+    ///
+    /// let mut sink = futures::sink::drain()
+    ///     .sink_map_err(|e| match e {})
+    ///     .fail_after_sink(2, || "disconnected");
+    ///
+    /// sink.send(1).await?;
+    /// sink.send(2).await?;
+    /// assert_eq!(sink.send(3).await, Err("disconnected"));
+    /// # Ok::<(), &str>(()) }).unwrap();
+    /// ```
+    fn fail_after_sink<F>(self, n: usize, f: F) -> FailAfter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Error,
+    {
+        FailAfter::new(self, n, f)
+    }
+
+    /// Fails the `n`th call to `poll_flush`, counting from 1, with the error
+    /// returned by `f`, without flushing the sink.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::sink::SinkExt;
+    /// use futures_test::sink::SinkTestExt;
+    ///
+    /// let mut sink = futures::sink::drain()
+    ///     .sink_map_err(|e| match e {})
+    ///     .fail_flush(2, || "flush failed");
+    ///
+    /// sink.send(1).await?;
+    /// assert_eq!(sink.send(2).await, Err("flush failed"));
+    /// sink.send(3).await?;
+    /// # Ok::<(), &str>(()) }).unwrap();
+    /// ```
+    fn fail_flush<F>(self, n: usize, f: F) -> FailFlush<Self, F>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Error,
+    {
+        FailFlush::new(self, n, f)
+    }
+
+    /// Fails calls to `poll_ready` and `poll_flush` with an
+    /// [`Interrupted`](io::ErrorKind::Interrupted) or
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error, converted into the
+    /// error type of the sink, with the given `probability`.
+    ///
+    /// The errors are injected at points picked by a pseudorandom number
+    /// generator started from `seed`, so the same seed gives the same errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::sink::{self, Sink, SinkExt};
+    /// use futures::task::Poll;
+    /// use futures_test::sink::SinkTestExt;
+    /// use futures_test::task::noop_context;
+    /// use futures::pin_mut;
+    /// use std::io;
+    ///
+    /// let sink = sink::drain::<i32>()
+    ///     .sink_map_err(|e| -> io::Error { match e {} })
+    ///     .inject_errors_sink(1, 1.0);
+    /// pin_mut!(sink);
+    ///
+    /// let mut cx = noop_context();
+    ///
+    /// match sink.as_mut().poll_ready(&mut cx) {
+    ///     Poll::Ready(Err(e)) => assert!(matches!(
+    ///         e.kind(),
+    ///         io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+    ///     )),
+    ///     _ => panic!("expected an injected error"),
+    /// }
+    /// assert_eq!(sink.injected(), 1);
+    /// ```
+    fn inject_errors_sink(self, seed: u64, probability: f64) -> InjectErrors<Self>
+    where
+        Self: Sized,
+        Self::Error: From<io::Error>,
+    {
+        InjectErrors::new(self, seed, probability)
+    }
 }
 
 impl<Item, W> SinkTestExt<Item> for W where W: Sink<Item> {}
@@ -1,8 +1,11 @@
 //! Additional combinators for testing streams.
 
 use futures_core::stream::Stream;
+use std::io;
 
 pub use crate::assert_unmoved::AssertUnmoved;
+pub use crate::fail_after::FailAfter;
+pub use crate::inject_errors::InjectErrors;
 pub use crate::interleave_pending::InterleavePending;
 
 /// Additional combinators for testing streams.
@@ -52,6 +55,61 @@ pub trait StreamTestExt: Stream {
     {
         InterleavePending::new(self)
     }
+
+    /// Produces the first `n` items of the stream, then the item returned by
+    /// `f` in place of the next one, and then ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::stream::StreamTestExt;
+    ///
+    /// let stream = stream::iter(vec![Ok(1), Ok(2), Ok(3)]).fail_after(2, || Err("reset"));
+    ///
+    /// assert_eq!(stream.collect::<Vec<_>>().await, vec![Ok(1), Ok(2), Err("reset")]);
+    /// # });
+    /// ```
+    fn fail_after<F>(self, n: usize, f: F) -> FailAfter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Item,
+    {
+        FailAfter::new(self, n, f)
+    }
+
+    /// Produces an [`Interrupted`](io::ErrorKind::Interrupted) or
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error, converted into the
+    /// error type of the stream, instead of polling it with the given
+    /// `probability`.
+    ///
+    /// The errors are injected at points picked by a pseudorandom number
+    /// generator started from `seed`, so the same seed gives the same errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::stream::StreamTestExt;
+    /// use std::io;
+    ///
+    /// let items = stream::iter((0..100).map(Ok::<_, io::Error>)).inject_errors(7, 0.2);
+    /// let results: Vec<_> = items.collect().await;
+    ///
+    /// let values: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).copied().collect();
+    /// assert_eq!(values, (0..100).collect::<Vec<_>>());
+    /// assert!(results.len() > 100);
+    /// # });
+    /// ```
+    fn inject_errors<T, E>(self, seed: u64, probability: f64) -> InjectErrors<Self>
+    where
+        Self: Stream<Item = Result<T, E>> + Sized,
+        E: From<io::Error>,
+    {
+        InjectErrors::new(self, seed, probability)
+    }
 }
 
 impl<St> StreamTestExt for St where St: Stream {}
@@ -27,6 +27,8 @@
 //!   [`wake`](futures_core::task::Waker) is called.
 //! - [`new_count_waker`](crate::task::new_count_waker) creates a waker that increments a counter whenever
 //!   [`wake`](futures_core::task::Waker) is called.
+//! - [`WakeChecker`](crate::task::WakeChecker) polls futures with a waker that tracks its
+//!   clones, drops and wakeups, and panics when a future stalls without storing it.
 //!
 //! Test spawners:
 //! - [`NoopSpawner`](crate::task::NoopSpawner) ignores calls to
@@ -34,6 +36,8 @@
 //! - [`PanicSpawner`](crate::task::PanicSpawner) panics if [`spawn`](futures_util::task::SpawnExt::spawn) is
 //!   called.
 //! - [`RecordSpawner`](crate::task::RecordSpawner) records the spawned futures.
+//! - [`TrackingSpawner`](crate::task::TrackingSpawner) wraps another spawner
+//!   and counts the tasks spawned on it that are still running.
 //!
 //! For convenience there additionally exist various functions that directly
 //! return waker/spawner references: [`noop_waker_ref`](crate::task::noop_waker_ref),
@@ -56,5 +60,11 @@ pub use self::panic_waker::{panic_waker, panic_waker_ref};
 mod record_spawner;
 pub use self::record_spawner::RecordSpawner;
 
+mod tracking_spawner;
+pub use self::tracking_spawner::TrackingSpawner;
+
+mod wake_checker;
+pub use self::wake_checker::WakeChecker;
+
 mod wake_counter;
 pub use self::wake_counter::{AwokenCount, new_count_waker};
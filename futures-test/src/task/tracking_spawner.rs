@@ -0,0 +1,134 @@
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use futures_task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+use futures_util::future::poll_fn;
+use futures_util::task::AtomicWaker;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A wrapper around a spawner that keeps count of the tasks spawned through
+/// it that have not finished yet.
+///
+/// This is what [`#[futures_test::test]`](crate::test) hands to tests that
+/// take a spawner, so that it can check that no task outlives the test.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::LocalPool;
+/// use futures::task::LocalSpawnExt;
+/// use futures_test::task::TrackingSpawner;
+///
+/// let mut pool = LocalPool::new();
+/// let spawner = TrackingSpawner::new(pool.spawner());
+///
+/// spawner.spawn_local(async {}).unwrap();
+/// assert_eq!(spawner.running(), 1);
+/// pool.run();
+/// assert_eq!(spawner.running(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackingSpawner<Sp> {
+    spawner: Sp,
+    tracker: Arc<Tracker>,
+}
+
+#[derive(Debug, Default)]
+struct Tracker {
+    running: AtomicUsize,
+    idle: AtomicWaker,
+}
+
+impl<Sp> TrackingSpawner<Sp> {
+    /// Wraps `spawner`.
+    pub fn new(spawner: Sp) -> Self {
+        Self { spawner, tracker: Arc::default() }
+    }
+
+    /// Returns the number of tasks spawned through this spawner, or one of
+    /// its clones, that have neither completed nor been dropped.
+    pub fn running(&self) -> usize {
+        self.tracker.running.load(Ordering::SeqCst)
+    }
+
+    /// Waits until no task spawned through this spawner is running.
+    pub fn idle(&self) -> impl Future<Output = ()> + '_ {
+        poll_fn(move |cx| {
+            if self.running() == 0 {
+                return Poll::Ready(());
+            }
+            self.tracker.idle.register(cx.waker());
+            if self.running() == 0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Returns a reference to the wrapped spawner.
+    pub fn get_ref(&self) -> &Sp {
+        &self.spawner
+    }
+
+    fn track<F>(&self, future: F) -> Tracked<F> {
+        self.tracker.running.fetch_add(1, Ordering::SeqCst);
+        Tracked { future, tracker: Some(self.tracker.clone()) }
+    }
+}
+
+impl<Sp: Spawn> Spawn for TrackingSpawner<Sp> {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawner.spawn_obj(FutureObj::new(Box::new(self.track(future))))
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.spawner.status()
+    }
+}
+
+impl<Sp: LocalSpawn> LocalSpawn for TrackingSpawner<Sp> {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawner.spawn_local_obj(LocalFutureObj::new(Box::new(self.track(future))))
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        self.spawner.status_local()
+    }
+}
+
+// A spawned task, which stops counting as running when it completes or is
+// dropped.
+struct Tracked<F> {
+    future: F,
+    tracker: Option<Arc<Tracker>>,
+}
+
+impl<F> Tracked<F> {
+    fn finish(&mut self) {
+        if let Some(tracker) = self.tracker.take() {
+            if tracker.running.fetch_sub(1, Ordering::SeqCst) == 1 {
+                tracker.idle.wake();
+            }
+        }
+    }
+}
+
+impl<F: Future<Output = ()> + Unpin> Future for Tracked<F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let res = Pin::new(&mut self.future).poll(cx);
+        if res.is_ready() {
+            self.finish();
+        }
+        res
+    }
+}
+
+impl<F> Drop for Tracked<F> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
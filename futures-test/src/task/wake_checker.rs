@@ -0,0 +1,193 @@
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::any::type_name;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, Thread};
+
+/// Polls futures with a waker that keeps track of how it is used, to catch
+/// futures that lose their wakeups.
+///
+/// A future that returns [`Poll::Pending`] has to make sure that the waker
+/// of the task will be woken once it can make progress, usually by cloning
+/// the waker and storing it somewhere. One that does not will never be
+/// polled again, and so stalls forever. [`poll`](WakeChecker::poll) and
+/// [`block_on`](WakeChecker::block_on) panic with the type name of the
+/// future when it returns `Poll::Pending` while no clone of the waker is
+/// alive and the waker was not woken during the poll.
+///
+/// The checker also counts the clones and drops of its waker, so that tests
+/// can check that wakers are not leaked.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::oneshot;
+/// use futures_test::task::WakeChecker;
+///
+/// let checker = WakeChecker::new();
+/// let (tx, rx) = oneshot::channel();
+/// let mut rx = Box::pin(rx);
+///
+/// assert!(checker.poll(rx.as_mut()).is_pending());
+/// assert_eq!(checker.live_wakers(), 1);
+///
+/// tx.send(5).unwrap();
+/// assert_eq!(checker.wakes(), 1);
+/// assert_eq!(checker.block_on(rx), Ok(5));
+/// assert_eq!(checker.live_wakers(), 0);
+/// ```
+///
+/// ```should_panic
+/// use futures::future::poll_fn;
+/// use futures::task::Poll;
+/// use futures_test::task::WakeChecker;
+///
+/// // Panics: the future returns `Poll::Pending` without keeping the waker.
+/// WakeChecker::new().block_on(poll_fn(|_| Poll::<()>::Pending));
+/// ```
+#[derive(Debug)]
+pub struct WakeChecker {
+    inner: Arc<Inner>,
+    waker: Waker,
+}
+
+#[derive(Debug)]
+struct Inner {
+    clones: AtomicUsize,
+    drops: AtomicUsize,
+    wakes: AtomicUsize,
+    notified: AtomicBool,
+    // The thread blocked in `block_on`, if any.
+    thread: Mutex<Option<Thread>>,
+}
+
+impl Inner {
+    fn wake(&self) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+        self.notified.store(true, Ordering::SeqCst);
+        if let Some(thread) = &*self.thread.lock().unwrap() {
+            thread.unpark();
+        }
+    }
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake_by_ref, drop_waker);
+
+fn raw_waker(inner: Arc<Inner>) -> RawWaker {
+    RawWaker::new(Arc::into_raw(inner) as *const (), &VTABLE)
+}
+
+unsafe fn clone_waker(data: *const ()) -> RawWaker {
+    let inner = ManuallyDrop::new(Arc::from_raw(data as *const Inner));
+    inner.clones.fetch_add(1, Ordering::SeqCst);
+    raw_waker(Arc::clone(&inner))
+}
+
+unsafe fn wake(data: *const ()) {
+    wake_by_ref(data);
+    drop_waker(data);
+}
+
+unsafe fn wake_by_ref(data: *const ()) {
+    let inner = ManuallyDrop::new(Arc::from_raw(data as *const Inner));
+    inner.wake();
+}
+
+unsafe fn drop_waker(data: *const ()) {
+    let inner = Arc::from_raw(data as *const Inner);
+    inner.drops.fetch_add(1, Ordering::SeqCst);
+}
+
+impl WakeChecker {
+    /// Creates a new checker.
+    pub fn new() -> Self {
+        let inner = Arc::new(Inner {
+            clones: AtomicUsize::new(0),
+            drops: AtomicUsize::new(0),
+            wakes: AtomicUsize::new(0),
+            notified: AtomicBool::new(false),
+            thread: Mutex::new(None),
+        });
+        let waker = unsafe { Waker::from_raw(raw_waker(inner.clone())) };
+        Self { inner, waker }
+    }
+
+    /// Returns a [`Context`] whose waker is tracked by this checker.
+    ///
+    /// Polling a future with this context directly does not check it for
+    /// lost wakeups, but its uses of the waker are still counted.
+    pub fn context(&self) -> Context<'_> {
+        Context::from_waker(&self.waker)
+    }
+
+    /// Polls `future` once, and panics if it returns `Poll::Pending` without
+    /// arranging to be woken.
+    pub fn poll<F>(&self, future: Pin<&mut F>) -> Poll<F::Output>
+    where
+        F: Future + ?Sized,
+    {
+        let wakes = self.wakes();
+        let res = future.poll(&mut self.context());
+        if res.is_pending() && self.wakes() == wakes && self.live_wakers() == 0 {
+            panic!(
+                "`{}` returned `Poll::Pending` without storing or waking the waker, \
+                 so it will never be polled again",
+                type_name::<F>(),
+            );
+        }
+        res
+    }
+
+    /// Runs `future` to completion on the current thread, checking each of
+    /// its polls as [`poll`](WakeChecker::poll) does.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        futures_util::pin_mut!(future);
+        *self.inner.thread.lock().unwrap() = Some(thread::current());
+        let output = loop {
+            self.inner.notified.store(false, Ordering::SeqCst);
+            if let Poll::Ready(output) = self.poll(future.as_mut()) {
+                break output;
+            }
+            while !self.inner.notified.load(Ordering::SeqCst) {
+                thread::park();
+            }
+        };
+        *self.inner.thread.lock().unwrap() = None;
+        output
+    }
+
+    /// Returns the number of times the waker was cloned.
+    pub fn clones(&self) -> usize {
+        self.inner.clones.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of clones of the waker that were dropped, including
+    /// those consumed by [`Waker::wake`].
+    pub fn drops(&self) -> usize {
+        self.inner.drops.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of times the waker, or one of its clones, was woken.
+    pub fn wakes(&self) -> usize {
+        self.inner.wakes.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of clones of the waker that are still alive.
+    ///
+    /// Once every future polled with this checker has been dropped, a
+    /// non-zero count means that a waker was leaked.
+    pub fn live_wakers(&self) -> usize {
+        // Read the drops first, so that they cannot outnumber the clones.
+        let drops = self.drops();
+        self.clones() - drops
+    }
+}
+
+impl Default for WakeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,325 @@
+use super::Sleep;
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll, Waker};
+use futures_executor::LocalPool;
+use futures_util::future::poll_fn;
+use futures_util::pin_mut;
+use futures_util::task::{waker_ref, ArcWake, AtomicWaker};
+use futures_util::time::Timer;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A manually advanced clock.
+///
+/// The clock starts at the real [`Instant`] at which it was created and only
+/// moves forward when it is explicitly [advanced](Clock::advance), or, in
+/// auto-advance mode, when the executor driving it becomes idle.
+///
+/// Cloning a `Clock` returns another handle to the same virtual time.
+///
+/// `Clock` implements [`Timer`](futures_util::time::Timer), so it can drive
+/// the time-based combinators of `FutureExt` and `StreamExt`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::task::Poll;
+/// use futures::future::FutureExt;
+/// use futures_test::task::noop_context;
+/// use futures_test::time::Clock;
+/// use std::time::Duration;
+///
+/// let clock = Clock::new();
+/// let mut sleep = clock.sleep(Duration::from_millis(10));
+///
+/// let mut cx = noop_context();
+/// assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+///
+/// clock.advance(Duration::from_millis(9));
+/// assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+///
+/// clock.advance(Duration::from_millis(1));
+/// assert_eq!(sleep.poll_unpin(&mut cx), Poll::Ready(()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Clock {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    now: Instant,
+    auto_advance: bool,
+    next_id: u64,
+    // Timers are ordered by deadline, ties broken by registration order.
+    timers: BTreeMap<(Instant, u64), Waker>,
+}
+
+impl Clock {
+    /// Create a new clock starting at the current instant, with auto-advance
+    /// disabled.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                now: Instant::now(),
+                auto_advance: false,
+                next_id: 0,
+                timers: BTreeMap::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap()
+    }
+
+    /// Returns the current virtual instant.
+    pub fn now(&self) -> Instant {
+        self.lock().now
+    }
+
+    /// Returns a future that resolves once the clock has advanced by
+    /// `duration`.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleep_until(self.now() + duration)
+    }
+
+    /// Returns a future that resolves once the clock has reached `deadline`.
+    ///
+    /// If `deadline` is not in the future, the returned future resolves on its
+    /// first poll.
+    pub fn sleep_until(&self, deadline: Instant) -> Sleep {
+        Sleep::new(self.clone(), deadline)
+    }
+
+    /// Moves the clock forward by `duration`, waking every task whose timer
+    /// is now due.
+    pub fn advance(&self, duration: Duration) {
+        let target = self.now() + duration;
+        self.advance_to(target);
+    }
+
+    /// Moves the clock forward to `instant`, waking every task whose timer is
+    /// now due.
+    ///
+    /// The clock never goes backwards: an `instant` before [`now`](Clock::now)
+    /// only fires the timers that are already due.
+    pub fn advance_to(&self, instant: Instant) {
+        let wakers = {
+            let mut inner = self.lock();
+            if instant > inner.now {
+                inner.now = instant;
+            }
+            inner.take_due()
+        };
+        // Wake outside of the lock so that woken tasks may poll inline.
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Returns the deadline of the earliest pending timer, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.lock().timers.keys().next().map(|&(deadline, _)| deadline)
+    }
+
+    /// Returns the number of timers that have been polled and are still
+    /// waiting for the clock to reach their deadline.
+    pub fn pending_timers(&self) -> usize {
+        self.lock().timers.len()
+    }
+
+    /// Enables or disables auto-advance mode.
+    ///
+    /// When enabled, [`run_until`](Clock::run_until) and
+    /// [`run_until_stalled`](Clock::run_until_stalled) jump the clock to the
+    /// next pending deadline each time the executor has no more work to do.
+    pub fn set_auto_advance(&self, enabled: bool) {
+        self.lock().auto_advance = enabled;
+    }
+
+    /// Returns whether auto-advance mode is enabled.
+    pub fn is_auto_advancing(&self) -> bool {
+        self.lock().auto_advance
+    }
+
+    // Jump to the earliest pending deadline, returning `false` if there is no
+    // timer to fire.
+    fn advance_to_next_deadline(&self) -> bool {
+        match self.next_deadline() {
+            Some(deadline) => {
+                self.advance_to(deadline);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs all tasks in `pool` until no more progress can be made.
+    ///
+    /// If auto-advance is enabled, the clock is then moved to the next pending
+    /// deadline and the pool is run again, until no timer is left pending. A
+    /// task that waits on a timer is therefore run to completion without any
+    /// real time passing.
+    ///
+    /// ```
+    /// use futures::executor::LocalPool;
+    /// use futures::task::LocalSpawnExt;
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let sleep = clock.sleep(Duration::from_secs(3600));
+    /// pool.spawner().spawn_local(sleep).unwrap();
+    ///
+    /// let start = clock.now();
+    /// clock.run_until_stalled(&mut pool);
+    /// assert_eq!(clock.now() - start, Duration::from_secs(3600));
+    /// ```
+    pub fn run_until_stalled(&self, pool: &mut LocalPool) {
+        loop {
+            pool.run_until_stalled();
+            if !self.is_auto_advancing() || !self.advance_to_next_deadline() {
+                return;
+            }
+        }
+    }
+
+    /// Runs all tasks in `pool` until `future` completes.
+    ///
+    /// While auto-advance is enabled, the clock is moved to the next pending
+    /// deadline whenever neither `future` nor any task in `pool` can make
+    /// progress. Otherwise, or if no timer is pending, this blocks the current
+    /// thread until `future` is woken, as
+    /// [`LocalPool::run_until`](futures_executor::LocalPool::run_until) does.
+    pub fn run_until<F: Future>(&self, pool: &mut LocalPool, future: F) -> F::Output {
+        pin_mut!(future);
+
+        let notify = Arc::new(Notify {
+            woken: AtomicBool::new(true),
+            waker: AtomicWaker::new(),
+        });
+        let waker = waker_ref(&notify);
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            if notify.woken.swap(false, Ordering::SeqCst) {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+
+            pool.run_until_stalled();
+            if notify.woken.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            if self.is_auto_advancing() && self.advance_to_next_deadline() {
+                continue;
+            }
+
+            // Nothing can make progress in virtual time, so wait for a real
+            // wakeup while still driving the pool.
+            pool.run_until(poll_fn(|cx| {
+                notify.waker.register(cx.waker());
+                if notify.woken.load(Ordering::SeqCst) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            }));
+        }
+    }
+
+    /// Runs `future` to completion on a new [`LocalPool`], advancing the clock
+    /// as described in [`run_until`](Clock::run_until).
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.run_until(&mut LocalPool::new(), future)
+    }
+
+    pub(super) fn poll_timer(
+        &self,
+        deadline: Instant,
+        id: &mut Option<u64>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        let mut inner = self.lock();
+        if inner.now >= deadline {
+            if let Some(id) = id.take() {
+                inner.timers.remove(&(deadline, id));
+            }
+            return Poll::Ready(());
+        }
+
+        match *id {
+            Some(id) => {
+                let waker = inner
+                    .timers
+                    .entry((deadline, id))
+                    .or_insert_with(|| cx.waker().clone());
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+            }
+            None => {
+                let new_id = inner.next_id;
+                inner.next_id += 1;
+                inner.timers.insert((deadline, new_id), cx.waker().clone());
+                *id = Some(new_id);
+            }
+        }
+        Poll::Pending
+    }
+
+    pub(super) fn cancel_timer(&self, deadline: Instant, id: u64) {
+        self.lock().timers.remove(&(deadline, id));
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timer for Clock {
+    type Sleep = Sleep;
+
+    fn now(&self) -> Instant {
+        Clock::now(self)
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Sleep {
+        Clock::sleep_until(self, deadline)
+    }
+}
+
+impl Inner {
+    fn take_due(&mut self) -> Vec<Waker> {
+        let mut due = Vec::new();
+        while let Some(&key) = self.timers.keys().next() {
+            if key.0 > self.now {
+                break;
+            }
+            due.push(self.timers.remove(&key).unwrap());
+        }
+        due
+    }
+}
+
+struct Notify {
+    woken: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl ArcWake for Notify {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+        arc_self.waker.wake();
+    }
+}
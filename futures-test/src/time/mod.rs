@@ -0,0 +1,43 @@
+//! Virtual time for testing timer-driven code.
+//!
+//! This module provides a manually advanced [`Clock`] and the [`Sleep`]
+//! future that is resolved against it. Time only moves forward when the test
+//! says so, which makes timeout and retry logic fast and deterministic to
+//! test:
+//!
+//! - [`Clock::advance`] moves the clock forward and wakes every task whose
+//!   [`Sleep`] is now due.
+//! - In auto-advance mode (see [`Clock::set_auto_advance`]) the clock jumps
+//!   straight to the next deadline whenever the executor driven by
+//!   [`Clock::run_until`] or [`Clock::run_until_stalled`] has nothing else
+//!   left to do.
+//!
+//! # Examples
+//!
+//! ```
+//! use futures::future::{self, Either};
+//! use futures_test::time::Clock;
+//! use std::time::Duration;
+//!
+//! let clock = Clock::new();
+//! clock.set_auto_advance(true);
+//!
+//! let start = clock.now();
+//! let winner = clock.block_on(async {
+//!     let slow = clock.sleep(Duration::from_secs(60));
+//!     let fast = clock.sleep(Duration::from_secs(5));
+//!     match future::select(slow, fast).await {
+//!         Either::Left(_) => "slow",
+//!         Either::Right(_) => "fast",
+//!     }
+//! });
+//!
+//! assert_eq!(winner, "fast");
+//! assert_eq!(clock.now() - start, Duration::from_secs(5));
+//! ```
+
+mod clock;
+pub use self::clock::Clock;
+
+mod sleep;
+pub use self::sleep::Sleep;
@@ -0,0 +1,78 @@
+use super::Clock;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::pin::Pin;
+use std::time::Instant;
+
+/// Future that resolves once a [`Clock`] reaches a deadline.
+///
+/// This is created by the [`Clock::sleep`] and [`Clock::sleep_until`]
+/// methods.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    clock: Clock,
+    deadline: Instant,
+    id: Option<u64>,
+    done: bool,
+}
+
+impl Sleep {
+    pub(super) fn new(clock: Clock, deadline: Instant) -> Self {
+        Self {
+            clock,
+            deadline,
+            id: None,
+            done: false,
+        }
+    }
+
+    /// Returns the instant at which this future resolves.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns `true` if the clock has reached the deadline.
+    pub fn is_elapsed(&self) -> bool {
+        self.clock.now() >= self.deadline
+    }
+
+    /// Changes the deadline of this future, re-arming it if it had already
+    /// resolved.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.cancel();
+        self.deadline = deadline;
+        self.done = false;
+    }
+
+    fn cancel(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.clock.cancel_timer(self.deadline, id);
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let ret = this.clock.poll_timer(this.deadline, &mut this.id, cx);
+        if ret.is_ready() {
+            this.done = true;
+        }
+        ret
+    }
+}
+
+impl FusedFuture for Sleep {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,7 @@
+fn main() {
+    // Declare the `loom` cfg, which is set when model checking with
+    // `RUSTFLAGS="--cfg loom"`. Cargo versions without check-cfg support
+    // ignore this.
+    println!("cargo:rustc-check-cfg=cfg(loom)");
+    println!("cargo:rerun-if-changed=build.rs");
+}
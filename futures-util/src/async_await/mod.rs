@@ -30,6 +30,15 @@ mod select_mod;
 #[cfg(feature = "async-await-macro")]
 pub use self::select_mod::*;
 
+// Primary export is a macro
+#[cfg(feature = "alloc")]
+#[cfg(feature = "async-await-macro")]
+mod stream_mod;
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/64762
+#[cfg(feature = "alloc")]
+#[cfg(feature = "async-await-macro")]
+pub use self::stream_mod::*;
+
 #[cfg(feature = "std")]
 #[cfg(feature = "async-await-macro")]
 mod random;
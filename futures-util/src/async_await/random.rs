@@ -16,6 +16,32 @@ pub fn shuffle<T>(slice: &mut [T]) {
     }
 }
 
+// Makes the choices of `shuffle` on the current thread reproducible, for
+// testing.
+#[doc(hidden)]
+pub fn seed_shuffle(seed: u64) {
+    // xorshift gets stuck at zero.
+    let seed = if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed };
+    RNG.with(|rng| rng.set(Wrapping(seed)));
+}
+
+thread_local! {
+    static RNG: Cell<Wrapping<u64>> = Cell::new(Wrapping(prng_seed()));
+}
+
+fn prng_seed() -> u64 {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    // Any non-zero seed will do
+    let mut seed = 0;
+    while seed == 0 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+        seed = hasher.finish();
+    }
+    seed
+}
+
 /// Return a value from `0..n`.
 fn gen_index(n: usize) -> usize {
     (random() % n as u64) as usize
@@ -25,23 +51,6 @@ fn gen_index(n: usize) -> usize {
 ///
 /// [xorshift*]: https://en.wikipedia.org/wiki/Xorshift#xorshift*
 fn random() -> u64 {
-    thread_local! {
-        static RNG: Cell<Wrapping<u64>> = Cell::new(Wrapping(prng_seed()));
-    }
-
-    fn prng_seed() -> u64 {
-        static COUNTER: AtomicUsize = AtomicUsize::new(0);
-
-        // Any non-zero seed will do
-        let mut seed = 0;
-        while seed == 0 {
-            let mut hasher = DefaultHasher::new();
-            hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
-            seed = hasher.finish();
-        }
-        seed
-    }
-
     RNG.with(|rng| {
         let mut x = rng.get();
         debug_assert_ne!(x.0, 0);
@@ -121,9 +121,10 @@ macro_rules! document_select_macro {
         ///
         /// `select` also accepts a `complete` branch and a `default` branch.
         /// `complete` will run if all futures and streams have already been
-        /// exhausted. `default` will run if no futures or streams are
-        /// immediately ready. `complete` takes priority over `default` in
-        /// the case where all futures have completed.
+        /// exhausted, or are disabled by a guard (see below). `default` will
+        /// run if no futures or streams are immediately ready. `complete`
+        /// takes priority over `default` in the case where all futures have
+        /// completed.
         /// A motivating use-case for passing `Future`s by name as well as for
         /// `complete` blocks is to call `select!` in a loop, which is
         /// demonstrated in the following example:
@@ -148,6 +149,35 @@ macro_rules! document_select_macro {
         /// # });
         /// ```
         ///
+        /// A branch can be given a precondition with an `if` guard after its
+        /// future, as in `pattern = future, if condition => body`. The
+        /// condition is evaluated once, before any future is polled, and the
+        /// future of a branch whose guard is `false` is not polled at all, so
+        /// it does not need to be replaced with a terminated one to turn the
+        /// branch off. Without `complete`, `default` runs when some branches
+        /// are disabled and the others are exhausted or not ready, but if
+        /// every branch is exhausted, this still panics. For `complete`, a
+        /// disabled branch counts as exhausted:
+        ///
+        /// ```
+        /// # futures::executor::block_on(async {
+        /// use futures::future;
+        /// use futures::select;
+        /// let mut a_fut = future::ready(4);
+        /// let mut b_fut = future::ready(6);
+        /// let mut total = 0;
+        ///
+        /// loop {
+        ///     select! {
+        ///         a = a_fut, if total < 5 => total += a,
+        ///         b = b_fut, if total >= 4 => total += b,
+        ///         complete => break,
+        ///     };
+        /// }
+        /// assert_eq!(total, 10);
+        /// # });
+        /// ```
+        ///
         /// Note that the futures that have been matched over can still be mutated
         /// from inside the `select!` block's branches. This can be used to implement
         /// more complex behavior such as timer resets or writing into the head of
@@ -271,9 +301,10 @@ macro_rules! document_select_macro {
         ///
         /// `select_biased` also accepts a `complete` branch and a `default` branch.
         /// `complete` will run if all futures and streams have already been
-        /// exhausted. `default` will run if no futures or streams are
-        /// immediately ready. `complete` takes priority over `default` in
-        /// the case where all futures have completed.
+        /// exhausted, or are disabled by a guard (see below). `default` will
+        /// run if no futures or streams are immediately ready. `complete`
+        /// takes priority over `default` in the case where all futures have
+        /// completed.
         /// A motivating use-case for passing `Future`s by name as well as for
         /// `complete` blocks is to call `select_biased!` in a loop, which is
         /// demonstrated in the following example:
@@ -298,6 +329,35 @@ macro_rules! document_select_macro {
         /// # });
         /// ```
         ///
+        /// A branch can be given a precondition with an `if` guard after its
+        /// future, as in `pattern = future, if condition => body`. The
+        /// condition is evaluated once, before any future is polled, and the
+        /// future of a branch whose guard is `false` is not polled at all, so
+        /// it does not need to be replaced with a terminated one to turn the
+        /// branch off. Without `complete`, `default` runs when some branches
+        /// are disabled and the others are exhausted or not ready, but if
+        /// every branch is exhausted, this still panics. For `complete`, a
+        /// disabled branch counts as exhausted:
+        ///
+        /// ```
+        /// # futures::executor::block_on(async {
+        /// use futures::future;
+        /// use futures::select_biased;
+        /// let mut a_fut = future::ready(4);
+        /// let mut b_fut = future::ready(6);
+        /// let mut total = 0;
+        ///
+        /// loop {
+        ///     select_biased! {
+        ///         a = a_fut, if total < 5 => total += a,
+        ///         b = b_fut, if total >= 4 => total += b,
+        ///         complete => break,
+        ///     };
+        /// }
+        /// assert_eq!(total, 10);
+        /// # });
+        /// ```
+        ///
         /// Note that the futures that have been matched over can still be mutated
         /// from inside the `select_biased!` block's branches. This can be used to implement
         /// more complex behavior such as timer resets or writing into the head of
@@ -339,3 +399,81 @@ document_select_macro! {
         }}
     }
 }
+
+/// Repeatedly selects over futures and streams until all of them are done,
+/// running the branch of whichever produces a value first.
+///
+/// `select_loop!` is a loop around [`select!`], with the bookkeeping that
+/// such loops need done for you:
+///
+/// * A stream branch, `item in stream => body`, runs once for every item.
+///   When the stream ends, it drops out of the loop without running.
+/// * A future branch, `output = future => body`, runs once, when the future
+///   completes.
+/// * `break` and `continue` apply to the loop, and `break value` makes
+///   `value` the result of the `select_loop!` expression.
+/// * The optional `on_all_complete => expr` branch runs once every branch
+///   has finished, or is disabled by its guard, and its value is the result
+///   of the loop. Without it, the loop evaluates to `()` at that point.
+///
+/// Branches accept `if` guards like [`select!`]'s, which are evaluated
+/// again on every iteration. Futures and streams are moved into the loop
+/// and pinned there, so unlike with [`select!`], they need not be `Unpin` or
+/// fused; pass them by mutable reference to keep using them afterwards.
+/// When several branches are ready, one is chosen pseudo-randomly.
+///
+/// This macro is only usable inside of async functions, closures, and blocks.
+/// It is also gated behind the `async-await` feature of this library, which is
+/// activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future;
+/// use futures::select_loop;
+/// use futures::stream;
+///
+/// let mut total = 0;
+/// let done = select_loop! {
+///     x in stream::iter(vec![1, 2, 3]) => total += x,
+///     y in stream::iter(vec![10, 20]) => total += y,
+///     z = future::ready(100) => total += z,
+///     on_all_complete => "done",
+/// };
+/// assert_eq!(done, "done");
+/// assert_eq!(total, 136);
+/// # });
+/// ```
+///
+/// Ending early with `break`:
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::select_loop;
+/// use futures::stream;
+///
+/// let first_even = select_loop! {
+///     x in stream::iter(vec![1, 3, 4, 5]) => if x % 2 == 0 {
+///         break Some(x);
+///     },
+///     on_all_complete => None,
+/// };
+/// assert_eq!(first_even, Some(4));
+/// # });
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! select_loop {
+    ($($tokens:tt)*) => {{
+        use $crate::__private as __futures_crate;
+        $crate::select_loop_internal! {
+            $( $tokens )*
+        }
+    }}
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[proc_macro_hack(support_nested, only_hack_old_rustc)]
+pub use futures_macro::select_loop_internal;
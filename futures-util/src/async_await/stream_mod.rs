@@ -0,0 +1,287 @@
+//! The `stream` macro.
+
+use proc_macro_hack::proc_macro_hack;
+
+macro_rules! document_stream_macro {
+    ($stream:item $try_stream:item) => {
+        /// Creates a stream from an async block that produces its items with
+        /// `yield`.
+        ///
+        /// The body of the macro is the body of an async block, so it can
+        /// `.await` other futures, and it is moved into the stream like an
+        /// `async move` block. Every `yield value` hands `value` to the
+        /// consumer of the stream and suspends the block until the next item
+        /// is requested. The stream ends when the block does.
+        ///
+        /// `yield` inside a closure or async block nested in the body is not
+        /// supported, and neither is `yield` inside the arguments of another
+        /// macro, such as `select!` or `vec![]`, which is rejected with an
+        /// error.
+        ///
+        /// The returned stream is not [`Unpin`]; use
+        /// [`pin_mut!`](crate::pin_mut) or [`Box::pin`] to poll it.
+        ///
+        /// This macro is gated behind the `async-await` and `alloc` features
+        /// of this library, which are activated by default.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # futures::executor::block_on(async {
+        /// use futures::stream::StreamExt;
+        /// use futures::{pin_mut, stream};
+        ///
+        /// let evens = stream! {
+        ///     let mut numbers = stream::iter(1..=10);
+        ///     while let Some(n) = numbers.next().await {
+        ///         if n % 2 == 0 {
+        ///             yield n;
+        ///         }
+        ///     }
+        /// };
+        /// pin_mut!(evens);
+        ///
+        /// assert_eq!(evens.collect::<Vec<_>>().await, vec![2, 4, 6, 8, 10]);
+        /// # });
+        /// ```
+        $stream
+
+        /// Creates a [`TryStream`](crate::stream::TryStream) from an async
+        /// block that produces its items with `yield`, and can fail with `?`.
+        ///
+        /// This works like [`stream!`], except that every `yield value`
+        /// produces `Ok(value)`. When the body fails, with `?` or by
+        /// returning `Err`, the stream produces that error and ends.
+        ///
+        /// The error type often cannot be inferred from the body alone, since
+        /// `?` converts errors with [`From`]. Annotate it where the stream is
+        /// used, or return an explicitly typed `Err`.
+        ///
+        /// This macro is gated behind the `async-await` and `alloc` features
+        /// of this library, which are activated by default.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # futures::executor::block_on(async {
+        /// use futures::stream::TryStreamExt;
+        /// use futures::{pin_mut, try_stream};
+        /// use std::num::ParseIntError;
+        ///
+        /// let numbers = try_stream! {
+        ///     for s in vec!["1", "2", "three", "4"] {
+        ///         yield s.parse::<i32>()?;
+        ///     }
+        /// };
+        /// pin_mut!(numbers);
+        ///
+        /// assert_eq!(numbers.try_next().await, Ok::<_, ParseIntError>(Some(1)));
+        /// assert_eq!(numbers.try_next().await, Ok(Some(2)));
+        /// assert!(numbers.try_next().await.is_err());
+        /// assert_eq!(numbers.try_next().await, Ok(None));
+        /// # });
+        /// ```
+        $try_stream
+    };
+}
+
+#[doc(hidden)]
+#[proc_macro_hack(support_nested, only_hack_old_rustc)]
+pub use futures_macro::stream_internal;
+
+#[doc(hidden)]
+#[proc_macro_hack(support_nested, only_hack_old_rustc)]
+pub use futures_macro::try_stream_internal;
+
+document_stream_macro! {
+    #[macro_export]
+    macro_rules! stream {
+        ($($tokens:tt)*) => {{
+            use $crate::__private as __futures_crate;
+            $crate::stream_internal! {
+                $( $tokens )*
+            }
+        }}
+    }
+
+    #[macro_export]
+    macro_rules! try_stream {
+        ($($tokens:tt)*) => {{
+            use $crate::__private as __futures_crate;
+            $crate::try_stream_internal! {
+                $( $tokens )*
+            }
+        }}
+    }
+}
+
+// Not public API. Used by the code generated by `stream!` and `try_stream!`.
+#[doc(hidden)]
+pub mod __stream {
+    use alloc::sync::Arc;
+    use core::cell::UnsafeCell;
+    use core::fmt;
+    use core::pin::Pin;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use futures_core::future::Future;
+    use futures_core::stream::{FusedStream, Stream};
+    use futures_core::task::{Context, Poll};
+    use pin_project_lite::pin_project;
+
+    // The slot an item is passed through, from the async block to the
+    // stream polling it.
+    //
+    // Both sides only touch it while the stream is being polled, so it is
+    // never contended; the flag only guards against the yielder being moved
+    // out of the async block and misused.
+    struct Slot<T> {
+        busy: AtomicBool,
+        item: UnsafeCell<Option<T>>,
+    }
+
+    unsafe impl<T: Send> Sync for Slot<T> {}
+
+    impl<T> Slot<T> {
+        fn with<R>(&self, f: impl FnOnce(&mut Option<T>) -> R) -> R {
+            if self.busy.swap(true, Ordering::Acquire) {
+                panic!("`stream!` item slot accessed concurrently");
+            }
+            // Safety: the flag makes this the only access.
+            let res = f(unsafe { &mut *self.item.get() });
+            self.busy.store(false, Ordering::Release);
+            res
+        }
+    }
+
+    /// Creates the two ends of the slot.
+    pub fn pair<T>() -> (Yielder<T>, Receiver<T>) {
+        let slot = Arc::new(Slot {
+            busy: AtomicBool::new(false),
+            item: UnsafeCell::new(None),
+        });
+        (Yielder { slot: slot.clone() }, Receiver { slot })
+    }
+
+    /// The end of the slot used by `yield`.
+    pub struct Yielder<T> {
+        slot: Arc<Slot<T>>,
+    }
+
+    impl<T> Yielder<T> {
+        /// Hands `item` to the stream, and waits until it is taken.
+        pub fn send(&self, item: T) -> YieldFuture<'_, T> {
+            YieldFuture { slot: &self.slot, item: Some(item) }
+        }
+    }
+
+    impl<T> fmt::Debug for Yielder<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Yielder").finish()
+        }
+    }
+
+    /// Future for [`Yielder::send`].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct YieldFuture<'a, T> {
+        slot: &'a Slot<T>,
+        item: Option<T>,
+    }
+
+    impl<T> Unpin for YieldFuture<'_, T> {}
+
+    impl<T> Future for YieldFuture<'_, T> {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            match self.item.take() {
+                // The stream returns the item as soon as the async block
+                // suspends, and polls it again for the next one, so there is
+                // no need to wake anything.
+                Some(item) => {
+                    self.slot.with(|slot| *slot = Some(item));
+                    Poll::Pending
+                }
+                None => Poll::Ready(()),
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for YieldFuture<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("YieldFuture").finish()
+        }
+    }
+
+    /// The end of the slot read by [`AsyncStream`].
+    pub struct Receiver<T> {
+        slot: Arc<Slot<T>>,
+    }
+
+    impl<T> fmt::Debug for Receiver<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Receiver").finish()
+        }
+    }
+
+    pin_project! {
+        /// Stream returned by the `stream!` and `try_stream!` macros.
+        #[must_use = "streams do nothing unless polled"]
+        pub struct AsyncStream<T, Fut> {
+            rx: Receiver<T>,
+            done: bool,
+            #[pin]
+            generator: Fut,
+        }
+    }
+
+    impl<T, Fut> AsyncStream<T, Fut>
+    where
+        Fut: Future<Output = ()>,
+    {
+        /// Creates a stream of the items `generator` passes to the yielder
+        /// that goes with `rx`.
+        pub fn new(rx: Receiver<T>, generator: Fut) -> Self {
+            Self { rx, done: false, generator }
+        }
+    }
+
+    impl<T, Fut> Stream for AsyncStream<T, Fut>
+    where
+        Fut: Future<Output = ()>,
+    {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            let this = self.project();
+            if *this.done {
+                return Poll::Ready(None);
+            }
+            let res = this.generator.poll(cx);
+            if let Some(item) = this.rx.slot.with(Option::take) {
+                return Poll::Ready(Some(item));
+            }
+            match res {
+                Poll::Ready(()) => {
+                    *this.done = true;
+                    Poll::Ready(None)
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<T, Fut> FusedStream for AsyncStream<T, Fut>
+    where
+        Fut: Future<Output = ()>,
+    {
+        fn is_terminated(&self) -> bool {
+            self.done
+        }
+    }
+
+    impl<T, Fut> fmt::Debug for AsyncStream<T, Fut> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("AsyncStream").field("done", &self.done).finish()
+        }
+    }
+}
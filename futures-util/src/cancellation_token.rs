@@ -0,0 +1,393 @@
+use crate::abortable::AbortInner;
+use crate::future::{AbortHandle, Abortable};
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll, Waker};
+use slab::Slab;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// A token which can be used to signal a cancellation request to one or more
+/// tasks.
+///
+/// Tasks can call [`cancelled`](CancellationToken::cancelled) to get a future
+/// which resolves once cancellation is requested, or check for it with
+/// [`is_cancelled`](CancellationToken::is_cancelled). Futures, streams and
+/// sinks can also be wrapped with
+/// [`abortable`](CancellationToken::abortable), which stops them once
+/// cancellation is requested.
+///
+/// Clones of a token share its state, so cancelling one of them cancels all
+/// of them. [`child_token`](CancellationToken::child_token) instead creates a
+/// token which is cancelled along with its parent, but which can also be
+/// cancelled on its own without affecting the parent.
+///
+/// This type is only available when the `std` feature of this library is
+/// activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future::{self, CancellationToken};
+///
+/// let token = CancellationToken::new();
+/// let child = token.child_token();
+///
+/// let worker = async {
+///     child.cancelled().await;
+///     "stopped"
+/// };
+/// let (result, ()) = future::join(worker, async { token.cancel() }).await;
+/// assert_eq!(result, "stopped");
+/// assert!(child.is_cancelled());
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct CancellationToken {
+    node: Arc<Node>,
+}
+
+struct Node {
+    // Only set while `state` is locked, so that nothing gets registered with
+    // a cancelled node.
+    cancelled: AtomicBool,
+    state: Mutex<State>,
+    // The parent of the node and the node's key among its children, unless
+    // the node was created cancelled.
+    parent: Option<(Arc<Node>, usize)>,
+}
+
+struct State {
+    children: Slab<Weak<Node>>,
+    wakers: Slab<Waker>,
+    aborts: Vec<Weak<AbortInner>>,
+}
+
+const NULL_WAKER_KEY: usize = usize::max_value();
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    /// Creates a new token, which is not cancelled.
+    pub fn new() -> Self {
+        Self { node: Arc::new(Node::new(false, None)) }
+    }
+
+    /// Creates a token which is cancelled when this token is cancelled.
+    ///
+    /// Cancelling the child token does not cancel this token. Once the child
+    /// token and all of its clones and futures are dropped, it is removed
+    /// from this token right away, so short-lived children don't pile up
+    /// under a long-lived token.
+    pub fn child_token(&self) -> Self {
+        let mut state = self.node.state.lock().unwrap();
+        if self.node.is_cancelled() {
+            return Self { node: Arc::new(Node::new(true, None)) };
+        }
+
+        let entry = state.children.vacant_entry();
+        let node = Arc::new(Node::new(false, Some((self.node.clone(), entry.key()))));
+        entry.insert(Arc::downgrade(&node));
+        Self { node }
+    }
+
+    /// Cancels this token and all of its descendants.
+    ///
+    /// Each task waiting for one of the tokens is woken once. Cancelling a
+    /// token which is already cancelled does nothing.
+    pub fn cancel(&self) {
+        // Walk the tree with an explicit stack rather than recursively, so
+        // that deep trees can't overflow the stack.
+        let mut pending = vec![self.node.clone()];
+        while let Some(node) = pending.pop() {
+            let mut state = node.state.lock().unwrap();
+            if node.is_cancelled() {
+                continue;
+            }
+            node.cancelled.store(true, Ordering::Release);
+            let mut children = mem::replace(&mut state.children, Slab::new());
+            let mut wakers = mem::replace(&mut state.wakers, Slab::new());
+            let aborts = mem::replace(&mut state.aborts, Vec::new());
+            drop(state);
+
+            for waker in wakers.drain() {
+                waker.wake();
+            }
+            for abort in aborts.iter().filter_map(Weak::upgrade) {
+                abort.abort();
+            }
+            pending.extend(children.drain().filter_map(|child| child.upgrade()));
+        }
+    }
+
+    /// Returns `true` if this token has been cancelled, either directly or
+    /// through one of its ancestors.
+    pub fn is_cancelled(&self) -> bool {
+        self.node.is_cancelled()
+    }
+
+    /// Creates a future which resolves once this token is cancelled.
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled { token: self, waker_key: NULL_WAKER_KEY, completed: false }
+    }
+
+    /// Wraps a future, stream or sink in an [`Abortable`], which is aborted
+    /// once this token is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future::{self, Aborted, CancellationToken};
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let token = CancellationToken::new();
+    /// let child = token.child_token();
+    /// let fut = child.abortable(future::pending::<()>());
+    /// let mut stream = token.abortable(stream::iter(1..));
+    ///
+    /// assert_eq!(stream.next().await, Some(1));
+    /// token.cancel();
+    /// assert_eq!(fut.await, Err(Aborted));
+    /// assert_eq!(stream.next().await, None);
+    /// # });
+    /// ```
+    pub fn abortable<T>(&self, task: T) -> Abortable<T> {
+        let (handle, reg) = AbortHandle::new_pair();
+        let mut state = self.node.state.lock().unwrap();
+        if self.node.is_cancelled() {
+            handle.abort();
+        } else {
+            let aborts = &mut state.aborts;
+            // Forget the tasks which were dropped before growing the list,
+            // which keeps the list proportional to the live tasks.
+            if aborts.len() == aborts.capacity() {
+                aborts.retain(|abort| abort.upgrade().is_some());
+            }
+            aborts.push(handle.downgrade());
+        }
+        drop(state);
+        Abortable::new(task, reg)
+    }
+
+    /// Creates a [`DropGuard`] for this token, which cancels it when dropped.
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard { token: Some(self) }
+    }
+}
+
+impl Node {
+    fn new(cancelled: bool, parent: Option<(Arc<Node>, usize)>) -> Self {
+        Self {
+            cancelled: AtomicBool::new(cancelled),
+            state: Mutex::new(State {
+                children: Slab::new(),
+                wakers: Slab::new(),
+                aborts: Vec::new(),
+            }),
+            parent,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Removes the node from the children of its parent, and returns the
+    /// parent.
+    ///
+    /// The tasks made abortable with the node may outlive it, so they are
+    /// handed over to the parent, which is cancelled whenever the node would
+    /// have been.
+    fn unregister(&mut self) -> Option<Arc<Node>> {
+        let (parent, key) = self.parent.take()?;
+        let aborts = match self.state.get_mut() {
+            Ok(state) => mem::replace(&mut state.aborts, Vec::new()),
+            Err(_) => Vec::new(),
+        };
+        let aborts = aborts.into_iter().filter_map(|abort| abort.upgrade());
+        let aborts = match parent.state.lock() {
+            Ok(mut state) => {
+                // The children are taken out when the parent is cancelled,
+                // and no children are added after that.
+                if state.children.contains(key) {
+                    state.children.remove(key);
+                }
+                if parent.is_cancelled() {
+                    aborts.collect()
+                } else {
+                    state.aborts.extend(aborts.map(|abort| Arc::downgrade(&abort)));
+                    Vec::new()
+                }
+            }
+            Err(_) => Vec::new(),
+        };
+        // Abort the tasks after releasing the lock, since waking them may
+        // drop other tokens.
+        for abort in aborts {
+            abort.abort();
+        }
+        Some(parent)
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // Release the ancestors which are only kept alive by this node one by
+        // one, since dropping a deep chain of them recursively could overflow
+        // the stack.
+        let mut parent = self.unregister();
+        while let Some(node) = parent {
+            parent = match Arc::try_unwrap(node) {
+                Ok(mut node) => node.unregister(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// Future for the [`cancelled`](CancellationToken::cancelled) method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+    waker_key: usize,
+    completed: bool,
+}
+
+impl fmt::Debug for Cancelled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cancelled")
+            .field("token", &self.token)
+            .field("waker_key", &self.waker_key)
+            .field("completed", &self.completed)
+            .finish()
+    }
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let token = self.token;
+        let node = &token.node;
+        if node.is_cancelled() {
+            self.completed = true;
+            return Poll::Ready(());
+        }
+
+        let mut state = node.state.lock().unwrap();
+        if node.is_cancelled() {
+            self.completed = true;
+            return Poll::Ready(());
+        }
+        let new_waker = cx.waker();
+        if self.waker_key == NULL_WAKER_KEY {
+            self.waker_key = state.wakers.insert(new_waker.clone());
+        } else {
+            let waker = &mut state.wakers[self.waker_key];
+            if !new_waker.will_wake(waker) {
+                *waker = new_waker.clone();
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl FusedFuture for Cancelled<'_> {
+    fn is_terminated(&self) -> bool {
+        self.completed
+    }
+}
+
+impl Drop for Cancelled<'_> {
+    fn drop(&mut self) {
+        if self.waker_key != NULL_WAKER_KEY {
+            if let Ok(mut state) = self.token.node.state.lock() {
+                // The wakers are taken out when the token is cancelled.
+                if state.wakers.contains(self.waker_key) {
+                    state.wakers.remove(self.waker_key);
+                }
+            }
+        }
+    }
+}
+
+/// A wrapper for a [`CancellationToken`] which cancels the token when it is
+/// dropped, created by the [`drop_guard`](CancellationToken::drop_guard)
+/// method.
+#[derive(Debug)]
+#[must_use = "the token is cancelled as soon as the guard is dropped"]
+pub struct DropGuard {
+    token: Option<CancellationToken>,
+}
+
+impl DropGuard {
+    /// Returns the token without cancelling it.
+    pub fn disarm(mut self) -> CancellationToken {
+        self.token.take().unwrap()
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+    use crate::future;
+
+    fn children(token: &CancellationToken) -> usize {
+        token.node.state.lock().unwrap().children.len()
+    }
+
+    #[test]
+    fn dropped_children_unregister() {
+        let root = CancellationToken::new();
+        for _ in 0..100 {
+            let child = root.child_token();
+            drop(child.child_token());
+            assert_eq!(children(&child), 0);
+        }
+        assert_eq!(children(&root), 0);
+
+        // A child stays registered while one of its descendants is alive.
+        let child = root.child_token();
+        let grandchild = child.child_token();
+        drop(child);
+        assert_eq!(children(&root), 1);
+        drop(grandchild);
+        assert_eq!(children(&root), 0);
+    }
+
+    #[test]
+    fn dropped_children_hand_over_tasks() {
+        let root = CancellationToken::new();
+        let child = root.child_token();
+        let task = child.abortable(future::pending::<()>());
+        drop(child);
+        assert_eq!(children(&root), 0);
+
+        root.cancel();
+        assert!(task.is_aborted());
+    }
+}
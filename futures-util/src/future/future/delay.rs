@@ -0,0 +1,63 @@
+use crate::time::Timer;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`delay`](super::FutureExt::delay) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Delay<Fut, T: Timer> {
+        #[pin]
+        future: Fut,
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<Fut, T: Timer> Delay<Fut, T> {
+    pub(super) fn new(future: Fut, sleep: T::Sleep) -> Self {
+        Self { future, sleep: Some(sleep) }
+    }
+
+    /// Acquires a reference to the underlying future.
+    pub fn get_ref(&self) -> &Fut {
+        &self.future
+    }
+
+    /// Acquires a mutable reference to the underlying future.
+    pub fn get_mut(&mut self) -> &mut Fut {
+        &mut self.future
+    }
+
+    /// Acquires a pinned mutable reference to the underlying future.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Fut> {
+        self.project().future
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> Fut {
+        self.future
+    }
+}
+
+impl<Fut: Future, T: Timer> Future for Delay<Fut, T> {
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+            ready!(sleep.poll(cx));
+            this.sleep.set(None);
+        }
+        this.future.poll(cx)
+    }
+}
+
+impl<Fut: FusedFuture, T: Timer> FusedFuture for Delay<Fut, T> {
+    fn is_terminated(&self) -> bool {
+        self.sleep.is_none() && self.future.is_terminated()
+    }
+}
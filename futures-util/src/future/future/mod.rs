@@ -19,6 +19,10 @@ use futures_core::{
     task::{Context, Poll},
 };
 use pin_utils::pin_mut;
+#[cfg(feature = "std")]
+use crate::time::{TimedOut, Timer};
+#[cfg(feature = "std")]
+use std::time::Duration;
 
 // Combinators
 
@@ -116,6 +120,18 @@ mod shared;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::shared::{Shared, WeakShared};
 
+#[cfg(feature = "std")]
+mod timeout;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::timeout::Timeout;
+
+#[cfg(feature = "std")]
+mod delay;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::delay::Delay;
+
 impl<T: ?Sized> FutureExt for T where T: Future {}
 
 /// An extension trait for `Future`s that provides a variety of convenient
@@ -510,6 +526,72 @@ pub trait FutureExt: Future {
         (assert_future::<(), _>(wrapped), handle)
     }
 
+    /// Requires this future to complete within `duration`, as measured by
+    /// `timer`.
+    ///
+    /// The returned future resolves to `Ok` with the output of this future if
+    /// it completes in time, or to `Err(TimedOut)` otherwise, in which case
+    /// this future is dropped along with the returned one. The deadline is
+    /// computed when this method is called.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::future::{self, FutureExt};
+    /// use futures::time::TimedOut;
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let ready = future::ready(1).timeout(&clock, Duration::from_secs(1));
+    /// assert_eq!(clock.block_on(ready), Ok(1));
+    ///
+    /// let never = future::pending::<()>().timeout(&clock, Duration::from_secs(1));
+    /// assert_eq!(clock.block_on(never), Err(TimedOut));
+    /// ```
+    #[cfg(feature = "std")]
+    fn timeout<T>(self, timer: T, duration: Duration) -> Timeout<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_future::<Result<Self::Output, TimedOut>, _>(Timeout::new(self, timer.sleep(duration)))
+    }
+
+    /// Waits for `duration`, as measured by `timer`, before polling this
+    /// future for the first time.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::future::FutureExt;
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let start = clock.now();
+    /// let elapsed = clock.block_on(async { clock.now() - start }.delay(&clock, Duration::from_secs(3)));
+    /// assert_eq!(elapsed, Duration::from_secs(3));
+    /// ```
+    #[cfg(feature = "std")]
+    fn delay<T>(self, timer: T, duration: Duration) -> Delay<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_future::<Self::Output, _>(Delay::new(self, timer.sleep(duration)))
+    }
+
     /// Wrap the future in a Box, pinning it.
     ///
     /// This method is only available when the `std` or `alloc` feature of this
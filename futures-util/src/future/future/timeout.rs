@@ -0,0 +1,71 @@
+use crate::time::{TimedOut, Timer};
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future for the [`timeout`](super::FutureExt::timeout) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Timeout<Fut, T: Timer> {
+        #[pin]
+        future: Fut,
+        #[pin]
+        sleep: T::Sleep,
+        done: bool,
+    }
+}
+
+impl<Fut, T: Timer> Timeout<Fut, T> {
+    pub(super) fn new(future: Fut, sleep: T::Sleep) -> Self {
+        Self { future, sleep, done: false }
+    }
+
+    /// Acquires a reference to the underlying future.
+    pub fn get_ref(&self) -> &Fut {
+        &self.future
+    }
+
+    /// Acquires a mutable reference to the underlying future.
+    pub fn get_mut(&mut self) -> &mut Fut {
+        &mut self.future
+    }
+
+    /// Acquires a pinned mutable reference to the underlying future.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Fut> {
+        self.project().future
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> Fut {
+        self.future
+    }
+}
+
+impl<Fut: Future, T: Timer> Future for Timeout<Fut, T> {
+    type Output = Result<Fut::Output, TimedOut>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        assert!(!*this.done, "Timeout polled after completion");
+
+        // Give the future a chance to complete even if the deadline has
+        // already passed, so that a ready value is never discarded.
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            *this.done = true;
+            return Poll::Ready(Ok(output));
+        }
+        if this.sleep.poll(cx).is_ready() {
+            *this.done = true;
+            return Poll::Ready(Err(TimedOut));
+        }
+        Poll::Pending
+    }
+}
+
+impl<Fut: Future, T: Timer> FusedFuture for Timeout<Fut, T> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
@@ -38,6 +38,9 @@ pub use self::future::{Remote, RemoteHandle};
 #[cfg(feature = "std")]
 pub use self::future::{Shared, WeakShared};
 
+#[cfg(feature = "std")]
+pub use self::future::{Delay, Timeout};
+
 mod try_future;
 pub use self::try_future::{
     AndThen, ErrInto, OkInto, InspectErr, InspectOk, IntoFuture, MapErr, MapOk, OrElse, TryFlattenStream,
@@ -112,7 +115,12 @@ cfg_target_has_atomic! {
     #[cfg(feature = "alloc")]
     mod abortable;
     #[cfg(feature = "alloc")]
-    pub use self::abortable::{abortable, Abortable, AbortHandle, AbortRegistration, Aborted};
+    pub use crate::abortable::{Abortable, AbortHandle, AbortRegistration, Aborted};
+    #[cfg(feature = "alloc")]
+    pub use self::abortable::abortable;
+
+    #[cfg(feature = "std")]
+    pub use crate::cancellation_token::{CancellationToken, Cancelled, DropGuard};
 }
 
 // Just a helper function to ensure the futures we're returning all have the
@@ -128,9 +128,15 @@ pub use self::sink::{sink, Sink};
 mod split;
 pub use self::split::{ReadHalf, WriteHalf, ReuniteError};
 
+mod stdio;
+pub use self::stdio::{stdin, stdout, stderr, Stdin, Stdout, Stderr};
+
 mod take;
 pub use self::take::Take;
 
+mod unblock;
+pub use self::unblock::Unblock;
+
 mod window;
 pub use self::window::Window;
 
@@ -0,0 +1,295 @@
+use super::Unblock;
+use crate::task::{waker_ref, ArcWake};
+use futures_core::ready;
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use std::fmt;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Arc, Mutex, Once};
+
+/// A handle to the standard input of the current process, created by
+/// [`stdin`].
+///
+/// All handles share one [`Unblock`] that reads from [`std::io::Stdin`] on a
+/// background thread. Each handle buffers at most one line at a time, so
+/// that tasks reading lines through clones of the same handle never see a
+/// line split between them.
+pub struct Stdin {
+    shared: &'static Shared<io::Stdin>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+/// A handle to the standard output of the current process, created by
+/// [`stdout`].
+///
+/// All handles share one [`Unblock`] that writes to [`std::io::Stdout`] on a
+/// background thread. Output is line-buffered: a write containing a newline
+/// starts flushing in the background, and the next write waits for that to
+/// finish. Flush the handle before the process exits to make sure nothing is
+/// lost.
+#[derive(Clone)]
+pub struct Stdout {
+    shared: &'static Shared<io::Stdout>,
+}
+
+/// A handle to the standard error of the current process, created by
+/// [`stderr`].
+///
+/// This behaves like [`Stdout`], writing to [`std::io::Stderr`] instead.
+#[derive(Clone)]
+pub struct Stderr {
+    shared: &'static Shared<io::Stderr>,
+}
+
+/// Constructs a new handle to the standard input of the current process.
+///
+/// The returned handle implements [`AsyncBufRead`], so that
+/// [`lines`](super::AsyncBufReadExt::lines) works on it directly. It can be
+/// cloned and used from several tasks at once.
+///
+/// # Examples
+///
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::io::{self, AsyncBufReadExt, AsyncWriteExt};
+/// use futures::stream::TryStreamExt;
+///
+/// let mut lines = io::stdin().lines();
+/// let mut stdout = io::stdout();
+/// while let Some(line) = lines.try_next().await? {
+///     stdout.write_all(line.to_uppercase().as_bytes()).await?;
+///     stdout.write_all(b"\n").await?;
+/// }
+/// stdout.flush().await?;
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn stdin() -> Stdin {
+    static INIT: Once = Once::new();
+    static GLOBAL: AtomicPtr<Shared<io::Stdin>> = AtomicPtr::new(ptr::null_mut());
+
+    Stdin {
+        shared: Shared::global(&INIT, &GLOBAL, io::stdin),
+        buf: Vec::new(),
+        pos: 0,
+    }
+}
+
+/// Constructs a new handle to the standard output of the current process.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{self, AsyncWriteExt};
+///
+/// let mut stdout = io::stdout();
+/// stdout.write_all(b"hello, world\n").await?;
+/// stdout.flush().await?;
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn stdout() -> Stdout {
+    static INIT: Once = Once::new();
+    static GLOBAL: AtomicPtr<Shared<io::Stdout>> = AtomicPtr::new(ptr::null_mut());
+
+    Stdout {
+        shared: Shared::global(&INIT, &GLOBAL, io::stdout),
+    }
+}
+
+/// Constructs a new handle to the standard error of the current process.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{self, AsyncWriteExt};
+///
+/// io::stderr().write_all(b"something went wrong\n").await?;
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn stderr() -> Stderr {
+    static INIT: Once = Once::new();
+    static GLOBAL: AtomicPtr<Shared<io::Stderr>> = AtomicPtr::new(ptr::null_mut());
+
+    Stderr {
+        shared: Shared::global(&INIT, &GLOBAL, io::stderr),
+    }
+}
+
+// The `Unblock` behind all handles to one stream, along with every task
+// waiting on it: `Unblock` only remembers a single waker.
+struct Shared<T> {
+    io: Mutex<Unblock<T>>,
+    wakers: Arc<Wakers>,
+}
+
+struct Wakers(Mutex<Vec<Waker>>);
+
+impl ArcWake for Wakers {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let wakers = mem::replace(&mut *arc_self.0.lock().unwrap(), Vec::new());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Send + 'static> Shared<T> {
+    fn global(
+        init: &Once,
+        global: &AtomicPtr<Self>,
+        make: fn() -> T,
+    ) -> &'static Self {
+        init.call_once(|| {
+            let shared: &'static Self = Box::leak(Box::new(Self {
+                io: Mutex::new(Unblock::new(make())),
+                wakers: Arc::new(Wakers(Mutex::new(Vec::new()))),
+            }));
+            global.store(shared as *const Self as *mut Self, Ordering::Release);
+        });
+
+        // Safety: `global` is set to a leaked, never freed allocation by
+        // `init`, which has completed at this point.
+        unsafe { &*global.load(Ordering::Acquire) }
+    }
+
+    // Calls `f` with the `Unblock` and a context that wakes every task
+    // currently waiting on it.
+    fn poll<R>(
+        &self,
+        cx: &mut Context<'_>,
+        f: impl FnOnce(Pin<&mut Unblock<T>>, &mut Context<'_>) -> Poll<R>,
+    ) -> Poll<R> {
+        let mut io = self.io.lock().unwrap();
+        {
+            // Registered before polling, so that a wakeup in between is not
+            // lost.
+            let mut wakers = self.wakers.0.lock().unwrap();
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+        let waker = waker_ref(&self.wakers);
+        let res = f(Pin::new(&mut *io), &mut Context::from_waker(&waker));
+        if res.is_ready() {
+            self.wakers.0.lock().unwrap().retain(|w| !w.will_wake(cx.waker()));
+        }
+        res
+    }
+}
+
+impl Clone for Stdin {
+    /// Returns a new handle to the standard input. Data buffered by `self`
+    /// is not shared with the clone.
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl AsyncBufRead for Stdin {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.pos == this.buf.len() {
+            let buf = &mut this.buf;
+            ready!(this.shared.poll(cx, |mut io, cx| {
+                let available = ready!(io.as_mut().poll_fill_buf(cx))?;
+                let len = match memchr::memchr(b'\n', available) {
+                    Some(i) => i + 1,
+                    None => available.len(),
+                };
+                buf.clear();
+                buf.extend_from_slice(&available[..len]);
+                io.consume(len);
+                Poll::Ready(Ok::<(), io::Error>(()))
+            }))?;
+            this.pos = 0;
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.pos = std::cmp::min(self.pos + amt, self.buf.len());
+    }
+}
+
+impl AsyncRead for Stdin {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = std::cmp::min(available.len(), buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+// Writes `buf`, and starts flushing if it completed a line.
+fn poll_write_line<T: io::Write + Send + 'static>(
+    shared: &Shared<T>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    shared.poll(cx, |mut io, cx| {
+        let n = ready!(io.as_mut().poll_write(cx, buf))?;
+        if memchr::memchr(b'\n', &buf[..n]).is_some() {
+            // Right after a write this is always pending; an error flushing
+            // is returned by the next write or flush.
+            let _ = io.poll_flush(cx);
+        }
+        Poll::Ready(Ok(n))
+    })
+}
+
+impl AsyncWrite for Stdout {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        poll_write_line(self.shared, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.shared.poll(cx, |io, cx| io.poll_flush(cx))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncWrite for Stderr {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        poll_write_line(self.shared, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.shared.poll(cx, |io, cx| io.poll_flush(cx))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl fmt::Debug for Stdin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stdin").finish()
+    }
+}
+
+impl fmt::Debug for Stdout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stdout").finish()
+    }
+}
+
+impl fmt::Debug for Stderr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stderr").finish()
+    }
+}
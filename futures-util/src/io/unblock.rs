@@ -0,0 +1,475 @@
+use super::DEFAULT_BUF_SIZE;
+use crate::task::AtomicWaker;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+use std::cmp;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Read, Seek, Write};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+/// Runs blocking I/O on a dedicated helper thread.
+///
+/// Unlike [`AllowStdIo`](super::AllowStdIo), which calls into `std::io`
+/// inline and blocks the executor, `Unblock` moves the I/O object onto its own
+/// thread, started the first time it is used. Reads are performed ahead of
+/// time and writes in the background, through bounded buffers, so that the
+/// helper thread and the task using the `Unblock` can make progress at the
+/// same time.
+///
+/// * Reading starts filling the read-ahead buffer. Switching to writing or
+///   seeking discards whatever was read ahead; seeking with
+///   [`SeekFrom::Current`] accounts for it.
+/// * Switching from reading to writing does not move the position of the I/O
+///   object back over the discarded data, so writing right after reading is
+///   only meaningful for streams that are not seekable, such as sockets. For
+///   a seekable object such as a file, the data would be written after the
+///   read-ahead instead of at the position the reader got to; seek with
+///   `SeekFrom::Current(0)` before writing to write there.
+/// * Written data is buffered and written out by the helper thread. Flush
+///   or close the `Unblock` to wait until it has been written and the I/O
+///   object has been flushed; reading and seeking do so implicitly.
+///
+/// Dropping an `Unblock` stops its thread once the I/O object is done with
+/// the operation in progress. Data that was written but not flushed is still
+/// written out, but errors doing so are lost. A thread blocked in a read that
+/// never completes, such as on a terminal, cannot be stopped.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncBufReadExt, Unblock};
+/// use futures::stream::TryStreamExt;
+/// use std::io::Cursor;
+///
+/// let reader = Unblock::new(Cursor::new("hello\nworld\n"));
+/// let lines: Vec<String> = reader.lines().try_collect().await?;
+/// assert_eq!(lines, ["hello", "world"]);
+/// # Ok::<(), Box<dyn std::error::Error>>(()) }).unwrap();
+/// ```
+pub struct Unblock<T> {
+    // The I/O object, until it is moved onto the helper thread.
+    io: Option<T>,
+    inner: Arc<Inner<T>>,
+    // Data taken from the shared read-ahead buffer, for `poll_fill_buf`.
+    read_buf: Vec<u8>,
+    read_pos: usize,
+}
+
+impl<T> Unpin for Unblock<T> {}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+    // Signalled when the helper thread has something to do.
+    thread_cond: Condvar,
+    // Woken when the helper thread has made progress.
+    waker: AtomicWaker,
+}
+
+type ReadFn<T> = fn(&mut T, &mut [u8]) -> io::Result<usize>;
+type SeekFn<T> = fn(&mut T, SeekFrom) -> io::Result<u64>;
+
+struct WriteFns<T> {
+    write_all: fn(&mut T, &[u8]) -> io::Result<()>,
+    flush: fn(&mut T) -> io::Result<()>,
+}
+
+enum Op<T> {
+    Idle,
+    Read(ReadFn<T>),
+    Write,
+    Flush,
+    Seek(SeekFn<T>, SeekFrom),
+}
+
+struct State<T> {
+    op: Op<T>,
+    // Read-ahead data while reading, pending writes while writing.
+    buf: VecDeque<u8>,
+    capacity: usize,
+    // How reading ended: `Ok` at end of file.
+    read_done: Option<io::Result<()>>,
+    // An error from writing, returned by the next write or flush.
+    write_err: Option<io::Error>,
+    flushed: Option<io::Result<()>>,
+    // The result of the last seek, and the position that was asked for, since
+    // the call that started it may have been abandoned.
+    seeked: Option<io::Result<u64>>,
+    seek_pos: Option<SeekFrom>,
+    write_fns: Option<WriteFns<T>>,
+    // The `Unblock` was dropped.
+    closed: bool,
+}
+
+// `fn` pointers are `Copy` regardless of `T`, which `derive` does not know.
+impl<T> Clone for Op<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Op<T> {}
+
+impl<T> Clone for WriteFns<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WriteFns<T> {}
+
+impl<T: Send + 'static> Unblock<T> {
+    /// Creates a new `Unblock` with the default buffer capacity of 64 KiB.
+    pub fn new(io: T) -> Self {
+        Self::with_capacity(8 * DEFAULT_BUF_SIZE, io)
+    }
+
+    /// Creates a new `Unblock` that buffers at most `capacity` bytes ahead
+    /// of the reader, or behind the writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(capacity: usize, io: T) -> Self {
+        assert!(capacity > 0, "`capacity` must be non-zero");
+        Self {
+            io: Some(io),
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    op: Op::Idle,
+                    buf: VecDeque::new(),
+                    capacity,
+                    read_done: None,
+                    write_err: None,
+                    flushed: None,
+                    seeked: None,
+                    seek_pos: None,
+                    write_fns: None,
+                    closed: false,
+                }),
+                thread_cond: Condvar::new(),
+                waker: AtomicWaker::new(),
+            }),
+            read_buf: Vec::new(),
+            read_pos: 0,
+        }
+    }
+
+    // Moves the I/O object onto its thread if it has not been already, and
+    // locks the shared state.
+    fn start(&mut self) -> (MutexGuard<'_, State<T>>, &Inner<T>, ReadBuf<'_>) {
+        if let Some(io) = self.io.take() {
+            let inner = self.inner.clone();
+            thread::Builder::new()
+                .name("futures-unblock".to_string())
+                .spawn(move || inner.run(io))
+                .expect("failed to spawn the `Unblock` thread");
+        }
+        let read_buf = ReadBuf {
+            buf: &mut self.read_buf,
+            pos: &mut self.read_pos,
+        };
+        (self.inner.state.lock().unwrap(), &self.inner, read_buf)
+    }
+}
+
+struct ReadBuf<'a> {
+    buf: &'a mut Vec<u8>,
+    pos: &'a mut usize,
+}
+
+impl ReadBuf<'_> {
+    // Throws away data that was read ahead, returning how much there was.
+    fn discard<T>(&mut self, state: &mut State<T>) -> usize {
+        let unread = state.buf.len() + self.buf.len() - *self.pos;
+        state.buf.clear();
+        state.read_done = None;
+        self.buf.clear();
+        *self.pos = 0;
+        unread
+    }
+}
+
+impl<T> Inner<T> {
+    // The helper thread: carries out whatever `op` asks for until the
+    // `Unblock` is dropped.
+    fn run(&self, mut io: T) {
+        let mut chunk = vec![0; DEFAULT_BUF_SIZE];
+        let mut state = self.state.lock().unwrap();
+        loop {
+            match state.op {
+                Op::Seek(seek, pos) => {
+                    drop(state);
+                    let res = seek(&mut io, pos);
+                    state = self.state.lock().unwrap();
+                    state.seeked = Some(res);
+                    state.op = Op::Idle;
+                }
+                Op::Write | Op::Flush if !state.buf.is_empty() && state.write_err.is_none() => {
+                    let write_all = state.write_fns.as_ref().unwrap().write_all;
+                    let len = cmp::min(chunk.len(), state.buf.len());
+                    for (dst, src) in chunk.iter_mut().zip(state.buf.drain(..len)) {
+                        *dst = src;
+                    }
+                    drop(state);
+                    let res = write_all(&mut io, &chunk[..len]);
+                    state = self.state.lock().unwrap();
+                    if let Err(e) = res {
+                        state.buf.clear();
+                        state.write_err = Some(e);
+                    }
+                }
+                Op::Flush => {
+                    let res = match state.write_err.take() {
+                        Some(e) => Err(e),
+                        None => {
+                            let flush = state.write_fns.as_ref().unwrap().flush;
+                            drop(state);
+                            let res = flush(&mut io);
+                            state = self.state.lock().unwrap();
+                            res
+                        }
+                    };
+                    state.flushed = Some(res);
+                    state.op = Op::Idle;
+                }
+                Op::Read(read)
+                    if !state.closed
+                        && state.read_done.is_none()
+                        && state.buf.len() < state.capacity =>
+                {
+                    let len = cmp::min(chunk.len(), state.capacity - state.buf.len());
+                    drop(state);
+                    let res = loop {
+                        match read(&mut io, &mut chunk[..len]) {
+                            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                            res => break res,
+                        }
+                    };
+                    state = self.state.lock().unwrap();
+                    match (state.op, res) {
+                        (Op::Read(_), Ok(0)) => state.read_done = Some(Ok(())),
+                        (Op::Read(_), Ok(n)) => state.buf.extend(&chunk[..n]),
+                        (Op::Read(_), Err(e)) => state.read_done = Some(Err(e)),
+                        // We were asked to seek while reading; the position
+                        // has moved past what the task has seen.
+                        (Op::Seek(seek, SeekFrom::Current(offset)), Ok(n)) => {
+                            state.op = Op::Seek(seek, SeekFrom::Current(offset - n as i64));
+                        }
+                        // Otherwise the data is no longer wanted.
+                        _ => {}
+                    }
+                }
+                _ if state.closed => return,
+                _ => {
+                    state = self.thread_cond.wait(state).unwrap();
+                    continue;
+                }
+            }
+            self.waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Unblock<T> {
+    fn drop(&mut self) {
+        if self.io.is_some() {
+            return;
+        }
+        let mut state = self.inner.state.lock().unwrap();
+        state.closed = true;
+        if let Op::Write = state.op {
+            state.op = Op::Flush;
+        }
+        self.inner.thread_cond.notify_one();
+    }
+}
+
+impl<T> fmt::Debug for Unblock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unblock")
+            .field("started", &self.io.is_none())
+            .finish()
+    }
+}
+
+impl<T: Read + Send + 'static> AsyncBufRead for Unblock<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.read_pos == this.read_buf.len() {
+            let (mut state, inner, read_buf) = this.start();
+            loop {
+                match state.op {
+                    Op::Read(_) if !state.buf.is_empty() => {
+                        read_buf.buf.clear();
+                        read_buf.buf.extend(state.buf.drain(..));
+                        *read_buf.pos = 0;
+                        inner.thread_cond.notify_one();
+                        break;
+                    }
+                    Op::Read(_) => match state.read_done.take() {
+                        Some(res) => {
+                            state.op = Op::Idle;
+                            res?;
+                            break;
+                        }
+                        None => {
+                            inner.waker.register(cx.waker());
+                            return Poll::Pending;
+                        }
+                    },
+                    Op::Idle => {
+                        if let Some(Err(e)) = state.flushed.take() {
+                            return Poll::Ready(Err(e));
+                        }
+                        state.seeked = None;
+                        state.op = Op::Read(T::read);
+                        inner.thread_cond.notify_one();
+                    }
+                    Op::Write => {
+                        state.op = Op::Flush;
+                        inner.thread_cond.notify_one();
+                    }
+                    Op::Flush | Op::Seek(..) => {
+                        inner.waker.register(cx.waker());
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+        Poll::Ready(Ok(&this.read_buf[this.read_pos..]))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.read_pos = cmp::min(self.read_pos + amt, self.read_buf.len());
+    }
+}
+
+impl<T: Read + Send + 'static> AsyncRead for Unblock<T> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(available)) => available,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let len = cmp::min(available.len(), buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl<T: Write + Send + 'static> AsyncWrite for Unblock<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let (mut state, inner, mut read_buf) = self.get_mut().start();
+        loop {
+            match state.op {
+                Op::Write => {
+                    if let Some(e) = state.write_err.take() {
+                        return Poll::Ready(Err(e));
+                    }
+                    if state.buf.len() >= state.capacity {
+                        inner.waker.register(cx.waker());
+                        return Poll::Pending;
+                    }
+                    let len = cmp::min(state.capacity - state.buf.len(), buf.len());
+                    state.buf.extend(&buf[..len]);
+                    inner.thread_cond.notify_one();
+                    return Poll::Ready(Ok(len));
+                }
+                Op::Idle => {
+                    if let Some(Err(e)) = state.flushed.take() {
+                        return Poll::Ready(Err(e));
+                    }
+                    state.seeked = None;
+                    state.write_fns = Some(WriteFns {
+                        write_all: T::write_all,
+                        flush: T::flush,
+                    });
+                    state.op = Op::Write;
+                }
+                Op::Read(_) => {
+                    read_buf.discard(&mut state);
+                    state.op = Op::Idle;
+                }
+                Op::Flush | Op::Seek(..) => {
+                    inner.waker.register(cx.waker());
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.io.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let mut state = this.inner.state.lock().unwrap();
+        loop {
+            match state.op {
+                Op::Write => {
+                    state.op = Op::Flush;
+                    this.inner.thread_cond.notify_one();
+                }
+                Op::Flush => {
+                    this.inner.waker.register(cx.waker());
+                    return Poll::Pending;
+                }
+                Op::Idle => return Poll::Ready(state.flushed.take().unwrap_or(Ok(()))),
+                Op::Read(_) | Op::Seek(..) => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T: Seek + Send + 'static> AsyncSeek for Unblock<T> {
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<io::Result<u64>> {
+        let (mut state, inner, mut read_buf) = self.get_mut().start();
+        loop {
+            match state.op {
+                Op::Idle => {
+                    if let Some(res) = state.seeked.take() {
+                        if state.seek_pos.take() == Some(pos) {
+                            return Poll::Ready(res);
+                        }
+                    }
+                    if let Some(Err(e)) = state.flushed.take() {
+                        return Poll::Ready(Err(e));
+                    }
+                    state.seek_pos = Some(pos);
+                    state.op = Op::Seek(T::seek, pos);
+                    inner.thread_cond.notify_one();
+                }
+                Op::Read(_) => {
+                    let unread = read_buf.discard(&mut state) as i64;
+                    let adjusted = match pos {
+                        SeekFrom::Current(offset) => SeekFrom::Current(offset - unread),
+                        pos => pos,
+                    };
+                    state.seek_pos = Some(pos);
+                    state.op = Op::Seek(T::seek, adjusted);
+                    inner.thread_cond.notify_one();
+                }
+                Op::Write => {
+                    state.op = Op::Flush;
+                    inner.thread_cond.notify_one();
+                }
+                Op::Flush | Op::Seek(..) => {
+                    inner.waker.register(cx.waker());
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
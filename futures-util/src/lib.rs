@@ -342,5 +342,16 @@ pub use crate::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 #[cfg(feature = "alloc")]
 pub mod lock;
 
+#[cfg(feature = "std")]
+pub mod time;
+
 mod fns;
 mod unfold_state;
+
+cfg_target_has_atomic! {
+    #[cfg(feature = "alloc")]
+    mod abortable;
+
+    #[cfg(feature = "std")]
+    mod cancellation_token;
+}
@@ -7,10 +7,10 @@ use core::cell::UnsafeCell;
 use core::fmt;
 use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
-use core::sync::atomic::AtomicUsize;
 use core::sync::atomic::Ordering::SeqCst;
 use alloc::boxed::Box;
-use alloc::sync::Arc;
+
+use super::loom::{Arc, AtomicUsize};
 
 /// A type of futures-powered synchronization primitive which is a mutex between
 /// two possible owners.
@@ -0,0 +1,19 @@
+//! The synchronization primitives used by the locks.
+//!
+//! Building with `RUSTFLAGS="--cfg loom"` swaps them for the model-checked
+//! versions from loom, so that the tests in `tests/loom_lock.rs` can explore
+//! every interleaving of the threads sharing a lock.
+
+#![allow(unused_imports)]
+
+#[cfg(not(loom))]
+pub(super) use alloc::sync::Arc;
+#[cfg(not(loom))]
+pub(super) use core::sync::atomic::AtomicUsize;
+#[cfg(all(not(loom), feature = "std"))]
+pub(super) use std::sync::Mutex as StdMutex;
+
+#[cfg(loom)]
+pub(super) use ::loom::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+pub(super) use ::loom::sync::{Arc, Mutex as StdMutex};
@@ -4,6 +4,9 @@
 //! library is activated, and it is activated by default.
 
 cfg_target_has_atomic! {
+    #[cfg(any(feature = "std", feature = "bilock", feature = "sink", feature = "io"))]
+    mod loom;
+
     #[cfg(feature = "std")]
     mod mutex;
     #[cfg(feature = "std")]
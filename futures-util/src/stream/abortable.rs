@@ -0,0 +1,54 @@
+use super::assert_stream;
+use crate::stream::{AbortHandle, Abortable};
+use futures_core::stream::Stream;
+
+/// Creates a new `Abortable` stream and an `AbortHandle` which can be used to stop it.
+///
+/// This function is a convenient (but less flexible) alternative to calling
+/// `AbortHandle::new` and `Abortable::new` manually.
+///
+/// Once aborted, the stream ends: it yields `None` without polling the
+/// underlying stream again. A future consuming the stream, such as the one
+/// returned by [`for_each`](super::StreamExt::for_each) or
+/// [`forward`](super::StreamExt::forward), then completes as usual, so the
+/// handle stops the whole pipeline.
+///
+/// This function is only available when the `std` or `alloc` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::channel::mpsc;
+/// use futures::future;
+/// use futures::stream::{self, StreamExt};
+///
+/// let (tx, rx) = mpsc::unbounded();
+/// let (rx, abort_handle) = stream::abortable(rx);
+/// let mut seen = Vec::new();
+///
+/// tx.unbounded_send(1).unwrap();
+/// tx.unbounded_send(2).unwrap();
+/// let pipeline = rx.for_each(|item| {
+///     seen.push(item);
+///     if item == 2 {
+///         abort_handle.abort();
+///     }
+///     future::ready(())
+/// });
+///
+/// // The channel is still open, but the pipeline stops anyway.
+/// pipeline.await;
+/// assert_eq!(seen, vec![1, 2]);
+/// drop(tx);
+/// # });
+/// ```
+pub fn abortable<St>(stream: St) -> (Abortable<St>, AbortHandle)
+where
+    St: Stream,
+{
+    let (handle, reg) = AbortHandle::new_pair();
+    let abortable = assert_stream::<St::Item, _>(Abortable::new(stream, reg));
+    (abortable, handle)
+}
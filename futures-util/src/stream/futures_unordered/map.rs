@@ -0,0 +1,320 @@
+use super::task::Task;
+use super::FuturesUnordered;
+use core::fmt::{self, Debug};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::pin::Pin;
+use alloc::sync::Arc;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::collections::hash_map::{self, HashMap};
+use std::hash::Hash;
+
+pin_project! {
+    // A future that resolves to its output together with its key.
+    struct Keyed<K, Fut> {
+        key: Option<K>,
+        #[pin]
+        future: Fut,
+    }
+}
+
+impl<K, Fut: Future> Future for Keyed<K, Fut> {
+    type Output = (K, Fut::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = ready!(this.future.poll(cx));
+        Poll::Ready((this.key.take().expect("Keyed polled after completion"), output))
+    }
+}
+
+/// A set of futures, each stored under a key, which may complete in any
+/// order.
+///
+/// This is a [`FuturesUnordered`] which remembers the key each future was
+/// [`insert`](FuturesMap::insert)ed with. It yields the key of each future
+/// together with its output, and allows futures that are still running to
+/// be looked up and [`cancel`](FuturesMap::cancel)ed by key in constant
+/// time.
+///
+/// This type is only available when the `std` feature of this library is
+/// activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::future::{self, FutureExt};
+/// use futures::stream::{FuturesMap, StreamExt};
+///
+/// let mut requests = FuturesMap::new();
+/// requests.insert("fast", future::ready(1).boxed());
+/// requests.insert("slow", future::pending().boxed());
+///
+/// assert_eq!(requests.next().await, Some(("fast", 1)));
+/// assert!(requests.contains_key(&"slow"));
+/// assert!(requests.cancel(&"slow"));
+/// assert_eq!(requests.next().await, None);
+/// # });
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct FuturesMap<K, Fut> {
+    futures: FuturesUnordered<Keyed<K, Fut>>,
+    // Each task is also referenced from here, so that it can still be looked
+    // up safely after `FuturesUnordered` has released it.
+    tasks: HashMap<K, Arc<Task<Keyed<K, Fut>>>>,
+}
+
+// `tasks` only gives access to the futures when `FuturesMap` is borrowed
+// mutably, just like `FuturesUnordered` does.
+unsafe impl<K: Send, Fut: Send> Send for FuturesMap<K, Fut> {}
+unsafe impl<K: Sync, Fut: Sync> Sync for FuturesMap<K, Fut> {}
+impl<K, Fut> Unpin for FuturesMap<K, Fut> {}
+
+impl<K, Fut> Default for FuturesMap<K, Fut> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, Fut> FuturesMap<K, Fut> {
+    /// Constructs a new, empty [`FuturesMap`].
+    ///
+    /// The returned [`FuturesMap`] does not contain any futures.
+    /// In this state, [`FuturesMap::poll_next`](Stream::poll_next) will
+    /// return [`Poll::Ready(None)`](Poll::Ready).
+    pub fn new() -> Self {
+        Self { futures: FuturesUnordered::new(), tasks: HashMap::new() }
+    }
+
+    /// Returns the number of futures contained in the map.
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    /// Returns `true` if the map contains no futures.
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+
+    /// Returns an iterator that allows inspecting each key and future in the
+    /// map, in arbitrary order.
+    pub fn iter(&self) -> MapIter<'_, K, Fut> {
+        MapIter { tasks: self.tasks.iter(), _marker: PhantomData }
+    }
+
+    /// Returns an iterator that allows modifying each future in the map, in
+    /// arbitrary order.
+    pub fn iter_mut(&mut self) -> MapIterMut<'_, K, Fut>
+    where
+        Fut: Unpin,
+    {
+        MapIterMut { tasks: self.tasks.iter_mut(), _marker: PhantomData }
+    }
+}
+
+impl<K, Fut> FuturesMap<K, Fut>
+where
+    K: Clone + Eq + Hash,
+{
+    /// Inserts a future into the map under the given key.
+    ///
+    /// If the map already contained a future under this key, that future is
+    /// dropped and `true` is returned.
+    ///
+    /// This method will not call [`poll`](core::future::Future::poll) on the
+    /// submitted future. The caller must ensure that
+    /// [`FuturesMap::poll_next`](Stream::poll_next) is called in order to
+    /// receive wake-up notifications for the given future.
+    pub fn insert(&mut self, key: K, future: Fut) -> bool {
+        let replaced = self.cancel(&key);
+        let ptr = self.futures.push_task(Keyed { key: Some(key.clone()), future });
+        // Safety: the task was just linked, so the set holds a reference to
+        // it which keeps it alive while we take our own.
+        let task = unsafe { ManuallyDrop::new(Arc::from_raw(ptr)) };
+        self.tasks.insert(key, Arc::clone(&task));
+        replaced
+    }
+
+    /// Returns `true` if the map contains a future under the given key which
+    /// has not completed yet.
+    pub fn contains_key(&self, key: &K) -> bool {
+        match self.tasks.get(key) {
+            // Safety: the map is borrowed, so the future cannot be polled or
+            // dropped meanwhile. It is missing if it panicked.
+            Some(task) => unsafe { (*task.future.get()).is_some() },
+            None => false,
+        }
+    }
+
+    /// Removes the future stored under the given key from the map, and
+    /// returns it if it has not completed yet.
+    pub fn remove(&mut self, key: &K) -> Option<Fut>
+    where
+        Fut: Unpin,
+    {
+        let task = self.unlink(key)?;
+        // Safety: the future is unpinned, and we have exclusive access to it
+        // since the task was unlinked.
+        let future = unsafe { (*task.future.get()).take() };
+        self.futures.release_task(task);
+        future.map(|keyed| keyed.future)
+    }
+
+    /// Drops the future stored under the given key, and returns `true` if
+    /// there was one which had not completed yet.
+    ///
+    /// Unlike [`remove`](FuturesMap::remove), this does not require the
+    /// future to be [`Unpin`].
+    pub fn cancel(&mut self, key: &K) -> bool {
+        match self.unlink(key) {
+            Some(task) => {
+                self.futures.release_task(task);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unlinks the task stored under the given key from the set of futures.
+    fn unlink(&mut self, key: &K) -> Option<Arc<Task<Keyed<K, Fut>>>> {
+        let task = self.tasks.remove(key)?;
+        // Safety: we have exclusive access to the set, and the task is only
+        // linked while its future is present.
+        // The future is missing if it panicked while it was polled.
+        unsafe { (*task.future.get()).as_ref()? };
+        Some(unsafe { self.futures.unlink(&*task) })
+    }
+}
+
+impl<K, Fut> Stream for FuturesMap<K, Fut>
+where
+    K: Clone + Eq + Hash,
+    Fut: Future,
+{
+    type Item = (K, Fut::Output);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (key, output) = match ready!(Pin::new(&mut self.futures).poll_next(cx)) {
+            Some(item) => item,
+            None => return Poll::Ready(None),
+        };
+        self.tasks.remove(&key);
+        Poll::Ready(Some((key, output)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.futures.size_hint()
+    }
+}
+
+impl<K, Fut> FusedStream for FuturesMap<K, Fut>
+where
+    K: Clone + Eq + Hash,
+    Fut: Future,
+{
+    fn is_terminated(&self) -> bool {
+        self.futures.is_terminated()
+    }
+}
+
+impl<K, Fut> Debug for FuturesMap<K, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FuturesMap {{ ... }}")
+    }
+}
+
+impl<K, Fut> FromIterator<(K, Fut)> for FuturesMap<K, Fut>
+where
+    K: Clone + Eq + Hash,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Fut)>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, Fut> Extend<(K, Fut)> for FuturesMap<K, Fut>
+where
+    K: Clone + Eq + Hash,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, Fut)>,
+    {
+        for (key, future) in iter {
+            self.insert(key, future);
+        }
+    }
+}
+
+/// Immutable iterator over all keys and futures in a [`FuturesMap`].
+pub struct MapIter<'a, K, Fut> {
+    tasks: hash_map::Iter<'a, K, Arc<Task<Keyed<K, Fut>>>>,
+    _marker: PhantomData<&'a FuturesMap<K, Fut>>,
+}
+
+impl<'a, K, Fut> Iterator for MapIter<'a, K, Fut> {
+    type Item = (&'a K, &'a Fut);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, task) in &mut self.tasks {
+            // Safety: the map is borrowed, so the future cannot be polled or
+            // dropped meanwhile. It is missing if it panicked.
+            if let Some(keyed) = unsafe { (*task.future.get()).as_ref() } {
+                return Some((key, &keyed.future));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.tasks.size_hint().1)
+    }
+}
+
+impl<K, Fut> Debug for MapIter<'_, K, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MapIter {{ ... }}")
+    }
+}
+
+/// Mutable iterator over all keys and futures in a [`FuturesMap`].
+pub struct MapIterMut<'a, K, Fut: Unpin> {
+    tasks: hash_map::IterMut<'a, K, Arc<Task<Keyed<K, Fut>>>>,
+    _marker: PhantomData<&'a mut FuturesMap<K, Fut>>,
+}
+
+impl<'a, K, Fut: Unpin> Iterator for MapIterMut<'a, K, Fut> {
+    type Item = (&'a K, &'a mut Fut);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, task) in &mut self.tasks {
+            // Safety: the map is borrowed mutably, so each future is only
+            // handed out once, and the futures are unpinned.
+            if let Some(keyed) = unsafe { (*task.future.get()).as_mut() } {
+                return Some((key, &mut keyed.future));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.tasks.size_hint().1)
+    }
+}
+
+impl<K, Fut: Unpin> Debug for MapIterMut<'_, K, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MapIterMut {{ ... }}")
+    }
+}
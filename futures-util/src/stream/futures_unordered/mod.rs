@@ -30,6 +30,11 @@ use self::task::Task;
 mod ready_to_run_queue;
 use self::ready_to_run_queue::{ReadyToRunQueue, Dequeue};
 
+#[cfg(feature = "std")]
+mod map;
+#[cfg(feature = "std")]
+pub use self::map::{FuturesMap, MapIter, MapIterMut};
+
 /// Constant used for a `FuturesUnordered` to determine how many times it is
 /// allowed to poll underlying futures without yielding.
 ///
@@ -180,6 +185,13 @@ impl<Fut> FuturesUnordered<Fut> {
     /// ensure that [`FuturesUnordered::poll_next`](Stream::poll_next) is called
     /// in order to receive wake-up notifications for the given future.
     pub fn push(&self, future: Fut) {
+        self.push_task(future);
+    }
+
+    /// Pushes a future into the set and returns a pointer to its task.
+    ///
+    /// The pointer stays valid for as long as the task is linked.
+    fn push_task(&self, future: Fut) -> *const Task<Fut> {
         let task = Arc::new(Task {
             future: UnsafeCell::new(Some(future)),
             next_all: AtomicPtr::new(self.pending_next_all()),
@@ -204,6 +216,8 @@ impl<Fut> FuturesUnordered<Fut> {
         // futures are ready. To do that we unconditionally enqueue it for
         // polling here.
         self.ready_to_run_queue.enqueue(ptr);
+
+        ptr
     }
 
     /// Returns an iterator that allows inspecting each future in the set.
@@ -24,7 +24,10 @@ pub use self::stream::{
 };
 
 #[cfg(feature = "std")]
-pub use self::stream::CatchUnwind;
+pub use self::stream::{CatchUnwind, Fork, Replay, SharedStream};
+
+#[cfg(feature = "std")]
+pub use self::stream::{ChunksTimeout, Debounce, Delay, Sample, Throttle, Timeout};
 
 #[cfg(feature = "alloc")]
 pub use self::stream::Chunks;
@@ -38,7 +41,9 @@ pub use self::stream::Forward;
 
 #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
 #[cfg(feature = "alloc")]
-pub use self::stream::{BufferUnordered, Buffered, ForEachConcurrent};
+pub use self::stream::{
+    BufferUnordered, Buffered, FlatMapUnordered, FlattenUnordered, ForEachConcurrent,
+};
 
 #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
 #[cfg(feature = "sink")]
@@ -60,7 +65,9 @@ pub use self::try_stream::IntoAsyncRead;
 
 #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
 #[cfg(feature = "alloc")]
-pub use self::try_stream::{TryBufferUnordered, TryBuffered, TryForEachConcurrent};
+pub use self::try_stream::{
+    TryBufferUnordered, TryBuffered, TryFlattenUnordered, TryForEachConcurrent,
+};
 
 // Primitive streams
 
@@ -88,10 +95,20 @@ pub use self::poll_fn::{poll_fn, PollFn};
 mod select;
 pub use self::select::{select, Select};
 
+mod select_with_strategy;
+pub use self::select_with_strategy::{select_with_strategy, PollNext, SelectWithStrategy};
+
 mod unfold;
 pub use self::unfold::{unfold, Unfold};
 
 cfg_target_has_atomic! {
+    #[cfg(feature = "alloc")]
+    mod abortable;
+    #[cfg(feature = "alloc")]
+    pub use crate::abortable::{Abortable, AbortHandle, AbortRegistration, Aborted};
+    #[cfg(feature = "alloc")]
+    pub use self::abortable::abortable;
+
     #[cfg(feature = "alloc")]
     mod futures_ordered;
     #[cfg(feature = "alloc")]
@@ -102,11 +119,19 @@ cfg_target_has_atomic! {
     #[cfg(feature = "alloc")]
     #[doc(inline)]
     pub use self::futures_unordered::FuturesUnordered;
+    #[cfg(feature = "std")]
+    pub use self::futures_unordered::FuturesMap;
 
     #[cfg(feature = "alloc")]
     mod select_all;
     #[cfg(feature = "alloc")]
     pub use self::select_all::{select_all, SelectAll};
+
+    #[cfg(feature = "std")]
+    pub mod stream_map;
+    #[cfg(feature = "std")]
+    #[doc(inline)]
+    pub use self::stream_map::StreamMap;
 }
 
 // Just a helper function to ensure the streams we're returning all have the
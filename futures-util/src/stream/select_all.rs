@@ -23,9 +23,38 @@ use crate::stream::{StreamExt, StreamFuture, FuturesUnordered};
 /// Note that you can create a ready-made `SelectAll` via the
 /// `select_all` function in the `stream` module, or you can start with an
 /// empty set with the `SelectAll::new` constructor.
+///
+/// # Fairness
+///
+/// Each stream has a weight, which is 1 unless it was given another one
+/// with [`SelectAll::push_weighted`] or [`SelectAll::with_weights`]. After a
+/// stream has been woken, it yields up to its weight in items in a row
+/// before the streams that were woken after it get their turn, so streams
+/// that are always ready share the output in proportion to their weights.
+/// A ready stream is never starved: the streams ahead of it yield at most
+/// the sum of their weights in items before it is polled.
 #[must_use = "streams do nothing unless polled"]
 pub struct SelectAll<St> {
-    inner: FuturesUnordered<StreamFuture<St>>,
+    inner: FuturesUnordered<StreamFuture<Weighted<St>>>,
+    // The stream that yielded the last item, and how many more items it may
+    // yield before it has to go back to the end of the queue.
+    current: Option<(Weighted<St>, usize)>,
+}
+
+impl<St> Unpin for SelectAll<St> {}
+
+// A stream together with its weight.
+struct Weighted<St> {
+    stream: St,
+    weight: usize,
+}
+
+impl<St: Stream + Unpin> Stream for Weighted<St> {
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
 }
 
 impl<St: Debug> Debug for SelectAll<St> {
@@ -40,19 +69,50 @@ impl<St: Stream + Unpin> SelectAll<St> {
     /// The returned `SelectAll` does not contain any streams and, in this
     /// state, `SelectAll::poll` will return `Poll::Ready(None)`.
     pub fn new() -> Self {
-        Self { inner: FuturesUnordered::new() }
+        Self { inner: FuturesUnordered::new(), current: None }
+    }
+
+    /// Constructs a new `SelectAll` from streams and their weights.
+    ///
+    /// This is like [`select_all`], except that each stream yields up to its
+    /// weight in items in a row, see the type's documentation.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if any of the weights is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{repeat, SelectAll, StreamExt};
+    ///
+    /// let set = SelectAll::with_weights(vec![(repeat(1), 3), (repeat(2), 1)]);
+    /// let items = set.take(400).collect::<Vec<_>>().await;
+    /// assert_eq!(items.iter().filter(|&&item| item == 1).count(), 300);
+    /// # });
+    /// ```
+    pub fn with_weights<I>(streams: I) -> Self
+    where
+        I: IntoIterator<Item = (St, usize)>,
+    {
+        let mut set = Self::new();
+        for (stream, weight) in streams {
+            set.push_weighted(stream, weight);
+        }
+        set
     }
 
     /// Returns the number of streams contained in the set.
     ///
     /// This represents the total number of in-flight streams.
     pub fn len(&self) -> usize {
-        self.inner.len()
+        self.inner.len() + self.current.is_some() as usize
     }
 
     /// Returns `true` if the set contains no streams
     pub fn is_empty(&self) -> bool {
-        self.inner.is_empty()
+        self.inner.is_empty() && self.current.is_none()
     }
 
     /// Push a stream into the set.
@@ -62,7 +122,36 @@ impl<St: Stream + Unpin> SelectAll<St> {
     /// ensure that `SelectAll::poll` is called in order to receive task
     /// notifications.
     pub fn push(&mut self, stream: St) {
-        self.inner.push(stream.into_future());
+        self.push_weighted(stream, 1);
+    }
+
+    /// Push a stream into the set with the given weight.
+    ///
+    /// The stream will yield up to `weight` items in a row, see the type's
+    /// documentation.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `weight` is zero.
+    pub fn push_weighted(&mut self, stream: St, weight: usize) {
+        assert!(weight > 0, "stream weight must be non-zero");
+        self.inner.push(Weighted { stream, weight }.into_future());
+    }
+
+    // Returns an item from a stream, and keeps that stream around to be
+    // polled first if it has weight left.
+    fn yield_item(
+        &mut self,
+        item: St::Item,
+        stream: Weighted<St>,
+        remaining: usize,
+    ) -> Poll<Option<St::Item>> {
+        if remaining > 0 {
+            self.current = Some((stream, remaining));
+        } else {
+            self.inner.push(stream.into_future());
+        }
+        Poll::Ready(Some(item))
     }
 }
 
@@ -79,11 +168,20 @@ impl<St: Stream + Unpin> Stream for SelectAll<St> {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if let Some((mut stream, remaining)) = self.current.take() {
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => return self.yield_item(item, stream, remaining - 1),
+                Poll::Ready(None) => {}
+                // Wait for wakeups through `FuturesUnordered` again.
+                Poll::Pending => self.inner.push(stream.into_future()),
+            }
+        }
+
         loop {
             match ready!(self.inner.poll_next_unpin(cx)) {
                 Some((Some(item), remaining)) => {
-                    self.push(remaining);
-                    return Poll::Ready(Some(item));
+                    let weight = remaining.weight;
+                    return self.yield_item(item, remaining, weight - 1);
                 }
                 Some((None, _)) => {
                     // `FuturesUnordered` thinks it isn't terminated
@@ -99,7 +197,7 @@ impl<St: Stream + Unpin> Stream for SelectAll<St> {
 
 impl<St: Stream + Unpin> FusedStream for SelectAll<St> {
     fn is_terminated(&self) -> bool {
-        self.inner.is_terminated()
+        self.inner.is_terminated() && self.current.is_none()
     }
 }
 
@@ -0,0 +1,217 @@
+use super::assert_stream;
+use crate::stream::{Fuse, StreamExt};
+use core::fmt;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+/// Type to tell [`SelectWithStrategy`] which stream to poll next.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum PollNext {
+    /// Poll the first stream.
+    Left,
+    /// Poll the second stream.
+    Right,
+}
+
+impl PollNext {
+    /// Toggle the value and return the old one.
+    pub fn toggle(&mut self) -> Self {
+        let old = *self;
+        *self = match self {
+            PollNext::Left => PollNext::Right,
+            PollNext::Right => PollNext::Left,
+        };
+        old
+    }
+}
+
+impl Default for PollNext {
+    fn default() -> Self {
+        PollNext::Left
+    }
+}
+
+pin_project! {
+    /// Stream for the [`select_with_strategy()`] function. See function docs
+    /// for details.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SelectWithStrategy<St1, St2, Clos, State> {
+        #[pin]
+        stream1: Fuse<St1>,
+        #[pin]
+        stream2: Fuse<St2>,
+        state: State,
+        clos: Clos,
+    }
+}
+
+/// This function will attempt to pull items from both streams. You provide a
+/// closure to tell [`SelectWithStrategy`] which stream to poll first. The
+/// closure is called with a mutable reference to a `State`, which starts out
+/// as `State::default()`, once for each call to `poll_next`.
+///
+/// If the preferred stream is not ready, or has completed, the other stream
+/// is polled in the same call. The returned stream completes when both input
+/// streams have completed.
+///
+/// # Starvation
+///
+/// Whether a stream can be starved depends only on the closure:
+///
+/// - Returning [`PollNext::toggle`] of a [`PollNext`] state alternates
+///   between the streams, which is what [`select`](super::select()) does.
+///   Neither stream can be starved: when both are ready, their items are
+///   interleaved one by one.
+/// - Always returning [`PollNext::Left`] gives the first stream priority.
+///   The second stream is only polled when the first one is not ready, so it
+///   is starved for as long as the first one keeps producing items.
+/// - Counting items in the state gives a weighted round robin, as in the
+///   example below. A ready stream waits for at most as many items as the
+///   other stream's weight.
+///
+/// Note that this function consumes both streams and returns a wrapped
+/// version of them.
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, repeat, select_with_strategy, PollNext, StreamExt};
+///
+/// let left = repeat(1);
+/// let right = repeat(2);
+///
+/// // Poll the left stream twice as often as the right one.
+/// let weighted = |count: &mut usize| {
+///     *count = (*count + 1) % 3;
+///     if *count == 0 { PollNext::Right } else { PollNext::Left }
+/// };
+/// let out = select_with_strategy(left, right, weighted);
+/// assert_eq!(out.take(6).collect::<Vec<_>>().await, vec![1, 1, 2, 1, 1, 2]);
+///
+/// // Give the left stream priority: the right one is starved.
+/// let out = select_with_strategy(repeat(1), repeat(2), |_: &mut ()| PollNext::Left);
+/// assert_eq!(out.take(3).collect::<Vec<_>>().await, vec![1, 1, 1]);
+///
+/// // Alternate, like `select`.
+/// let out = select_with_strategy(stream::iter(vec![1, 1]), repeat(2), PollNext::toggle);
+/// assert_eq!(out.take(5).collect::<Vec<_>>().await, vec![1, 2, 1, 2, 2]);
+/// # });
+/// ```
+pub fn select_with_strategy<St1, St2, Clos, State>(
+    stream1: St1,
+    stream2: St2,
+    which: Clos,
+) -> SelectWithStrategy<St1, St2, Clos, State>
+    where St1: Stream,
+          St2: Stream<Item = St1::Item>,
+          Clos: FnMut(&mut State) -> PollNext,
+          State: Default,
+{
+    assert_stream::<St1::Item, _>(SelectWithStrategy {
+        stream1: stream1.fuse(),
+        stream2: stream2.fuse(),
+        state: Default::default(),
+        clos: which,
+    })
+}
+
+impl<St1, St2, Clos, State> SelectWithStrategy<St1, St2, Clos, State> {
+    /// Acquires a reference to the underlying streams that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> (&St1, &St2) {
+        (self.stream1.get_ref(), self.stream2.get_ref())
+    }
+
+    /// Acquires a mutable reference to the underlying streams that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> (&mut St1, &mut St2) {
+        (self.stream1.get_mut(), self.stream2.get_mut())
+    }
+
+    /// Acquires a pinned mutable reference to the underlying streams that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut St1>, Pin<&mut St2>) {
+        let this = self.project();
+        (this.stream1.get_pin_mut(), this.stream2.get_pin_mut())
+    }
+
+    /// Consumes this combinator, returning the underlying streams.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> (St1, St2) {
+        (self.stream1.into_inner(), self.stream2.into_inner())
+    }
+}
+
+impl<St1, St2, Clos, State> FusedStream for SelectWithStrategy<St1, St2, Clos, State>
+    where St1: Stream,
+          St2: Stream<Item = St1::Item>,
+          Clos: FnMut(&mut State) -> PollNext,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream1.is_terminated() && self.stream2.is_terminated()
+    }
+}
+
+impl<St1, St2, Clos, State> Stream for SelectWithStrategy<St1, St2, Clos, State>
+    where St1: Stream,
+          St2: Stream<Item = St1::Item>,
+          Clos: FnMut(&mut State) -> PollNext,
+{
+    type Item = St1::Item;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<St1::Item>> {
+        let this = self.project();
+        match (this.clos)(this.state) {
+            PollNext::Left => poll_inner(this.stream1, this.stream2, cx),
+            PollNext::Right => poll_inner(this.stream2, this.stream1, cx),
+        }
+    }
+}
+
+fn poll_inner<St1, St2>(
+    a: Pin<&mut St1>,
+    b: Pin<&mut St2>,
+    cx: &mut Context<'_>
+) -> Poll<Option<St1::Item>>
+    where St1: Stream, St2: Stream<Item = St1::Item>
+{
+    let a_done = match a.poll_next(cx) {
+        Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+        Poll::Ready(None) => true,
+        Poll::Pending => false,
+    };
+
+    match b.poll_next(cx) {
+        Poll::Ready(Some(item)) => Poll::Ready(Some(item)),
+        Poll::Ready(None) if a_done => Poll::Ready(None),
+        Poll::Ready(None) | Poll::Pending => Poll::Pending,
+    }
+}
+
+impl<St1, St2, Clos, State> fmt::Debug for SelectWithStrategy<St1, St2, Clos, State>
+    where St1: fmt::Debug,
+          St2: fmt::Debug,
+          State: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectWithStrategy")
+            .field("stream1", &self.stream1)
+            .field("stream2", &self.stream2)
+            .field("state", &self.state)
+            .finish()
+    }
+}
@@ -0,0 +1,303 @@
+//! The state shared by the branches of [`Fork`](super::Fork) and the clones
+//! of [`SharedStream`](super::SharedStream).
+
+use crate::stream::{Fuse, StreamExt};
+use crate::task::{waker_ref, ArcWake};
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll, Waker};
+use slab::Slab;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// A handle which yields every item of a stream from some position on,
+/// cloning the items for the other handles.
+pub(super) struct Branch<St: Stream> {
+    inner: Arc<Inner<St>>,
+    // Key of this branch in `State::positions`.
+    key: usize,
+    // Key of this branch's waker in `Notifier::wakers`.
+    waker_key: usize,
+    terminated: bool,
+}
+
+struct Inner<St: Stream> {
+    state: Mutex<State<St>>,
+    notifier: Arc<Notifier>,
+}
+
+struct State<St: Stream> {
+    // Never moved out of the `Arc`, so it stays pinned.
+    stream: Fuse<St>,
+    // The items that some branches have not seen yet, preceded by the items
+    // kept for replaying.
+    buffer: VecDeque<Slot<St::Item>>,
+    // The position in the stream of the first item in `buffer`.
+    offset: u64,
+    // The position in the stream of the next item of each branch.
+    positions: Slab<u64>,
+    // The number of items that may wait for the slowest branch.
+    capacity: Option<usize>,
+    // The number of items to keep for new branches after every branch has
+    // seen them.
+    replay: usize,
+}
+
+struct Slot<T> {
+    item: T,
+    // The number of branches that have not seen the item yet.
+    remaining: usize,
+}
+
+struct Notifier {
+    wakers: Mutex<Slab<Option<Waker>>>,
+}
+
+const NULL_WAKER_KEY: usize = usize::max_value();
+
+impl<St: Stream> fmt::Debug for Branch<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Branch")
+            .field("key", &self.key)
+            .field("waker_key", &self.waker_key)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<St: Stream> Branch<St> {
+    pub(super) fn new(stream: St, capacity: Option<usize>, replay: usize) -> Self {
+        let mut positions = Slab::new();
+        let key = positions.insert(0);
+        let state = State {
+            stream: stream.fuse(),
+            buffer: VecDeque::new(),
+            offset: 0,
+            positions,
+            capacity,
+            replay,
+        };
+        let inner = Inner {
+            state: Mutex::new(state),
+            notifier: Arc::new(Notifier { wakers: Mutex::new(Slab::new()) }),
+        };
+
+        Self {
+            inner: Arc::new(inner),
+            key,
+            waker_key: NULL_WAKER_KEY,
+            terminated: false,
+        }
+    }
+
+    pub(super) fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Creates a new branch which starts at the next item of this one.
+    pub(super) fn fork(&self) -> Self {
+        let mut state = self.inner.state.lock().unwrap();
+        let pos = state.positions[self.key];
+        let mut branch = self.add_branch(&mut state, pos);
+        branch.terminated = self.terminated;
+        branch
+    }
+
+    /// Creates a new branch which starts at the oldest item kept for
+    /// replaying, or at the next item of the stream if there is none.
+    pub(super) fn subscribe(&self) -> Self {
+        let mut state = self.inner.state.lock().unwrap();
+        let head = state.offset + state.buffer.len() as u64;
+        let replayed = state.buffer.len().min(state.replay) as u64;
+        self.add_branch(&mut state, head - replayed)
+    }
+
+    fn add_branch(&self, state: &mut State<St>, pos: u64) -> Self {
+        let key = state.positions.insert(pos);
+        // The new branch needs to see every item from `pos` on.
+        let start = (pos - state.offset) as usize;
+        for slot in state.buffer.iter_mut().skip(start) {
+            slot.remaining += 1;
+        }
+
+        Self {
+            inner: self.inner.clone(),
+            key,
+            waker_key: NULL_WAKER_KEY,
+            terminated: false,
+        }
+    }
+
+    /// Registers the current task to receive a wakeup when new items are
+    /// available, or when there is room in the buffer again.
+    fn record_waker(&mut self, cx: &mut Context<'_>) {
+        let mut wakers = self.inner.notifier.wakers.lock().unwrap();
+        let new_waker = cx.waker();
+
+        if self.waker_key == NULL_WAKER_KEY {
+            self.waker_key = wakers.insert(Some(new_waker.clone()));
+        } else {
+            match wakers[self.waker_key] {
+                Some(ref old_waker) if new_waker.will_wake(old_waker) => {}
+                // Could use clone_from here, but Waker doesn't specialize it.
+                ref mut slot => *slot = Some(new_waker.clone()),
+            }
+        }
+    }
+
+    pub(super) fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<St::Item>>
+    where
+        St::Item: Clone,
+    {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        let inner = self.inner.clone();
+        let mut state = inner.state.lock().unwrap();
+        let state = &mut *state;
+        let pos = state.positions[self.key];
+
+        // Fast path for when another branch has already pulled the item
+        let index = (pos - state.offset) as usize;
+        if index < state.buffer.len() {
+            let (item, made_room) = state.take(index);
+            state.positions[self.key] += 1;
+            if made_room {
+                // Wake the branches that are waiting for room in the buffer
+                ArcWake::wake_by_ref(&inner.notifier);
+            }
+            return Poll::Ready(Some(item));
+        }
+
+        // This branch has seen every item pulled so far, so the next one has
+        // to come from the stream.
+        self.record_waker(cx);
+        if state.stream.is_done() {
+            self.terminated = true;
+            return Poll::Ready(None);
+        }
+        let others = state.positions.len() - 1;
+        if others > 0 && state.is_full() {
+            // Wait for the slowest branch to catch up
+            return Poll::Pending;
+        }
+
+        // Poll the stream with a waker that wakes every waiting branch, since
+        // all of them are waiting for the same item.
+        let waker = waker_ref(&inner.notifier);
+        let mut notifier_cx = Context::from_waker(&waker);
+        let stream = unsafe { Pin::new_unchecked(&mut state.stream) };
+        match stream.poll_next(&mut notifier_cx) {
+            Poll::Ready(Some(item)) => {
+                state.positions[self.key] += 1;
+                if others > 0 || state.replay > 0 {
+                    state.buffer.push_back(Slot { item: item.clone(), remaining: others });
+                    state.trim();
+                } else {
+                    state.offset += 1;
+                }
+                ArcWake::wake_by_ref(&inner.notifier);
+                Poll::Ready(Some(item))
+            }
+            Poll::Ready(None) => {
+                self.terminated = true;
+                ArcWake::wake_by_ref(&inner.notifier);
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<St: Stream> State<St> {
+    fn unseen(&self) -> usize {
+        let mut unseen = self.buffer.len();
+        for slot in &self.buffer {
+            if slot.remaining > 0 {
+                break;
+            }
+            unseen -= 1;
+        }
+        unseen
+    }
+
+    fn is_full(&self) -> bool {
+        self.capacity.map_or(false, |capacity| self.unseen() >= capacity)
+    }
+
+    /// Returns the buffered item at `index` for a branch, and removes it from
+    /// the buffer once it is no longer needed. Returns whether room was made
+    /// in a full buffer.
+    fn take(&mut self, index: usize) -> (St::Item, bool)
+    where
+        St::Item: Clone,
+    {
+        let was_full = self.is_full();
+        let retained = self.buffer.len() <= self.replay;
+        let slot = &mut self.buffer[index];
+        slot.remaining -= 1;
+        let item = if slot.remaining > 0 || retained {
+            slot.item.clone()
+        } else {
+            // Every branch ahead of this one has already seen the items in
+            // front of it, so if no other branch needs this item it is the
+            // first one.
+            debug_assert_eq!(index, 0);
+            self.offset += 1;
+            self.buffer.pop_front().unwrap().item
+        };
+        (item, was_full && !self.is_full())
+    }
+
+    /// Forgets the items after `pos` for a branch that went away. Returns
+    /// whether room was made in a full buffer.
+    fn release(&mut self, pos: u64) -> bool {
+        let was_full = self.is_full();
+        let start = (pos - self.offset) as usize;
+        for slot in self.buffer.iter_mut().skip(start) {
+            slot.remaining -= 1;
+        }
+        self.trim();
+        was_full && !self.is_full()
+    }
+
+    /// Removes the items that every branch has seen, except for the ones
+    /// kept for replaying.
+    fn trim(&mut self) {
+        while self.buffer.len() > self.replay
+            && self.buffer.front().map_or(false, |slot| slot.remaining == 0)
+        {
+            self.buffer.pop_front();
+            self.offset += 1;
+        }
+    }
+}
+
+impl<St: Stream> Drop for Branch<St> {
+    fn drop(&mut self) {
+        if self.waker_key != NULL_WAKER_KEY {
+            if let Ok(mut wakers) = self.inner.notifier.wakers.lock() {
+                wakers.remove(self.waker_key);
+            }
+        }
+        if let Ok(mut state) = self.inner.state.lock() {
+            let pos = state.positions.remove(self.key);
+            if state.release(pos) {
+                ArcWake::wake_by_ref(&self.inner.notifier);
+            }
+        }
+    }
+}
+
+impl ArcWake for Notifier {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let wakers = &mut *arc_self.wakers.lock().unwrap();
+        for (_key, opt_waker) in wakers {
+            if let Some(waker) = opt_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
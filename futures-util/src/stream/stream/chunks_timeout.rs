@@ -0,0 +1,105 @@
+use crate::stream::{Fuse, StreamExt};
+use crate::time::Timer;
+use core::mem;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`chunks_timeout`](super::StreamExt::chunks_timeout) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ChunksTimeout<St: Stream, T: Timer> {
+        #[pin]
+        stream: Fuse<St>,
+        items: Vec<St::Item>,
+        cap: usize,
+        timer: T,
+        duration: Duration,
+        // Invariant: `sleep` is `Some` if and only if `items` is non-empty.
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<St: Stream, T: Timer> ChunksTimeout<St, T> {
+    pub(super) fn new(stream: St, capacity: usize, timer: T, duration: Duration) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            stream: stream.fuse(),
+            items: Vec::with_capacity(capacity),
+            cap: capacity,
+            timer,
+            duration,
+            sleep: None,
+        }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, T: Timer> Stream for ChunksTimeout<St, T> {
+    type Item = Vec<St::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    // The first item of a chunk starts its timer.
+                    if this.items.is_empty() {
+                        this.sleep.set(Some(this.timer.sleep(*this.duration)));
+                    }
+                    this.items.push(item);
+                    if this.items.len() >= *this.cap {
+                        this.sleep.set(None);
+                        let cap = *this.cap;
+                        return Poll::Ready(Some(mem::replace(this.items, Vec::with_capacity(cap))));
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.sleep.set(None);
+                    let last = if this.items.is_empty() {
+                        None
+                    } else {
+                        Some(mem::replace(this.items, Vec::new()))
+                    };
+                    return Poll::Ready(last);
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        match this.sleep.as_mut().as_pin_mut() {
+            Some(sleep) => {
+                ready!(sleep.poll(cx));
+                this.sleep.set(None);
+                let cap = *this.cap;
+                Poll::Ready(Some(mem::replace(this.items, Vec::with_capacity(cap))))
+            }
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunk_len = if self.items.is_empty() { 0 } else { 1 };
+        let (_, upper) = self.stream.size_hint();
+        let upper = match upper {
+            Some(x) => x.checked_add(chunk_len),
+            None => None,
+        };
+        (chunk_len, upper)
+    }
+}
+
+impl<St: Stream, T: Timer> FusedStream for ChunksTimeout<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.items.is_empty()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::stream::{Fuse, StreamExt};
+use crate::time::Timer;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`debounce`](super::StreamExt::debounce) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Debounce<St: Stream, T: Timer> {
+        #[pin]
+        stream: Fuse<St>,
+        timer: T,
+        duration: Duration,
+        // Invariant: `latest` is `Some` if and only if `sleep` is `Some`.
+        latest: Option<St::Item>,
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<St: Stream, T: Timer> Debounce<St, T> {
+    pub(super) fn new(stream: St, timer: T, duration: Duration) -> Self {
+        Self { stream: stream.fuse(), timer, duration, latest: None, sleep: None }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, T: Timer> Stream for Debounce<St, T> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    // A newer item restarts the quiet period.
+                    *this.latest = Some(item);
+                    this.sleep.set(Some(this.timer.sleep(*this.duration)));
+                }
+                Poll::Ready(None) => {
+                    this.sleep.set(None);
+                    return Poll::Ready(this.latest.take());
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        match this.sleep.as_mut().as_pin_mut() {
+            Some(sleep) => {
+                ready!(sleep.poll(cx));
+                this.sleep.set(None);
+                Poll::Ready(this.latest.take())
+            }
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let latest = if self.latest.is_some() { 1 } else { 0 };
+        let (_, upper) = self.stream.size_hint();
+        (latest, upper.and_then(|upper| upper.checked_add(latest)))
+    }
+}
+
+impl<St: Stream, T: Timer> FusedStream for Debounce<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.latest.is_none()
+    }
+}
@@ -0,0 +1,50 @@
+use crate::time::Timer;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`delay`](super::StreamExt::delay) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Delay<St, T: Timer> {
+        #[pin]
+        stream: St,
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<St: Stream, T: Timer> Delay<St, T> {
+    pub(super) fn new(stream: St, sleep: T::Sleep) -> Self {
+        Self { stream, sleep: Some(sleep) }
+    }
+
+    delegate_access_inner!(stream, St, ());
+}
+
+impl<St: Stream, T: Timer> Stream for Delay<St, T> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+        if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+            ready!(sleep.poll(cx));
+            this.sleep.set(None);
+        }
+        this.stream.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<St: FusedStream, T: Timer> FusedStream for Delay<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.sleep.is_none() && self.stream.is_terminated()
+    }
+}
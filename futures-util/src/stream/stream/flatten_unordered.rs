@@ -0,0 +1,131 @@
+use crate::stream::{Fuse, FuturesUnordered, StreamExt, StreamFuture};
+use core::fmt;
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`flatten_unordered`](super::StreamExt::flatten_unordered)
+    /// method.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct FlattenUnordered<St>
+    where
+        St: Stream,
+    {
+        #[pin]
+        stream: Fuse<St>,
+        inner_streams: FuturesUnordered<StreamFuture<St::Item>>,
+        limit: Option<NonZeroUsize>,
+    }
+}
+
+impl<St> fmt::Debug for FlattenUnordered<St>
+where
+    St: Stream + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlattenUnordered")
+            .field("stream", &self.stream)
+            .field("inner_streams", &self.inner_streams)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<St> FlattenUnordered<St>
+where
+    St: Stream,
+    St::Item: Stream + Unpin,
+{
+    pub(super) fn new(stream: St, limit: Option<usize>) -> Self {
+        Self {
+            stream: super::Fuse::new(stream),
+            inner_streams: FuturesUnordered::new(),
+            // Note: `limit` = 0 gets ignored.
+            limit: limit.and_then(NonZeroUsize::new),
+        }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St> Stream for FlattenUnordered<St>
+where
+    St: Stream,
+    St::Item: Stream + Unpin,
+{
+    type Item = <St::Item as Stream>::Item;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            // First up, try to pull in as many inner streams as the limit
+            // allows.
+            while this.limit.map(|limit| limit.get() > this.inner_streams.len()).unwrap_or(true) {
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(stream)) => this.inner_streams.push(stream.into_future()),
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+
+            // Only the inner streams that were woken are polled here, each
+            // with its own waker.
+            match this.inner_streams.poll_next_unpin(cx) {
+                Poll::Ready(Some((Some(item), stream))) => {
+                    this.inner_streams.push(stream.into_future());
+                    return Poll::Ready(Some(item));
+                }
+                // An inner stream ended, so there may be room for another
+                // one now.
+                Poll::Ready(Some((None, _))) => {}
+                Poll::Ready(None) => {
+                    // If more streams are still coming, we're not done yet
+                    return if this.stream.is_done() {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Pending
+                    };
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.stream.is_done() && self.inner_streams.is_empty() {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<St> FusedStream for FlattenUnordered<St>
+where
+    St: Stream,
+    St::Item: Stream + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.inner_streams.is_empty()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Item> Sink<Item> for FlattenUnordered<S>
+where
+    S: Stream + Sink<Item>,
+    S::Item: Stream + Unpin,
+{
+    type Error = S::Error;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,59 @@
+use super::broadcast::Branch;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::fmt;
+use std::pin::Pin;
+
+/// Stream for the [`fork`](super::StreamExt::fork) method.
+///
+/// Each clone is a branch of the same underlying stream, which yields every
+/// item of the stream from the point where it was cloned.
+#[must_use = "streams do nothing unless polled"]
+pub struct Fork<St: Stream> {
+    branch: Branch<St>,
+}
+
+// The stream is polled behind an `Arc`, so it won't be moved when `Fork` is
+// moved.
+impl<St: Stream> Unpin for Fork<St> {}
+
+impl<St: Stream> fmt::Debug for Fork<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fork").field("branch", &self.branch).finish()
+    }
+}
+
+impl<St: Stream> Fork<St> {
+    pub(super) fn new(stream: St, capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self { branch: Branch::new(stream, Some(capacity), 0) }
+    }
+}
+
+impl<St> FusedStream for Fork<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.branch.is_terminated()
+    }
+}
+
+impl<St> Stream for Fork<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        self.branch.poll_next(cx)
+    }
+}
+
+impl<St: Stream> Clone for Fork<St> {
+    fn clone(&self) -> Self {
+        Self { branch: self.branch.fork() }
+    }
+}
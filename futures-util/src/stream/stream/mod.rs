@@ -21,6 +21,10 @@ use futures_core::{
 };
 #[cfg(feature = "sink")]
 use futures_sink::Sink;
+#[cfg(feature = "std")]
+use crate::time::{TimedOut, Timer};
+#[cfg(feature = "std")]
+use std::time::Duration;
 
 use crate::fns::{inspect_fn, InspectFn};
 
@@ -182,6 +186,21 @@ cfg_target_has_atomic! {
     #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
     pub use self::buffered::Buffered;
 
+    #[cfg(feature = "alloc")]
+    mod flatten_unordered;
+    #[cfg(feature = "alloc")]
+    #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+    pub use self::flatten_unordered::FlattenUnordered;
+
+    #[cfg(feature = "alloc")]
+    delegate_all!(
+        /// Stream for the [`flat_map_unordered`](StreamExt::flat_map_unordered) method.
+        FlatMapUnordered<St, U, F>(
+            FlattenUnordered<Map<St, F>>
+        ): Debug + Sink + Stream + FusedStream + AccessInner[St, (. .)] + New[|x: St, limit: Option<usize>, f: F| FlattenUnordered::new(Map::new(x, f), limit)]
+        where St: Stream, U: Stream, U: Unpin, F: FnMut(St::Item) -> U
+    );
+
     #[cfg(feature = "alloc")]
     mod for_each_concurrent;
     #[cfg(feature = "alloc")]
@@ -199,12 +218,63 @@ cfg_target_has_atomic! {
     pub use self::split::{SplitStream, SplitSink, ReuniteError};
 }
 
+#[cfg(feature = "std")]
+mod broadcast;
+
 #[cfg(feature = "std")]
 mod catch_unwind;
 #[cfg(feature = "std")]
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::catch_unwind::CatchUnwind;
 
+#[cfg(feature = "std")]
+mod chunks_timeout;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::chunks_timeout::ChunksTimeout;
+
+#[cfg(feature = "std")]
+mod debounce;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::debounce::Debounce;
+
+#[cfg(feature = "std")]
+mod delay;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::delay::Delay;
+
+#[cfg(feature = "std")]
+mod fork;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::fork::Fork;
+
+#[cfg(feature = "std")]
+mod sample;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::sample::Sample;
+
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::shared::{Replay, SharedStream};
+
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::throttle::Throttle;
+
+#[cfg(feature = "std")]
+mod timeout;
+#[cfg(feature = "std")]
+#[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+pub use self::timeout::Timeout;
+
 impl<T: ?Sized> StreamExt for T where T: Stream {}
 
 /// An extension trait for `Stream`s that provides a variety of convenient
@@ -694,6 +764,94 @@ pub trait StreamExt: Stream {
         assert_stream::<U::Item, _>(FlatMap::new(self, f))
     }
 
+    /// Flattens a stream of streams into just one stream, polling the inner
+    /// streams concurrently.
+    ///
+    /// Unlike [`StreamExt::flatten`], which exhausts each inner stream before
+    /// moving on to the next one, this pulls in up to `limit` inner streams
+    /// at a time and yields their items in the order in which they become
+    /// ready. Each inner stream is woken individually, so only the ones that
+    /// can make progress are polled again.
+    ///
+    /// The `limit` argument is of type `Into<Option<usize>>`, and so can be
+    /// provided as either `None`, `Some(10)`, or just `10`. Note: a limit of
+    /// zero is interpreted as no limit at all, and will have the same result
+    /// as passing in `None`.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let (tx1, rx1) = mpsc::unbounded();
+    /// let (tx2, rx2) = mpsc::unbounded();
+    /// let mut stream = stream::iter(vec![rx1, rx2]).flatten_unordered(None);
+    ///
+    /// // The second stream is not blocked by the first one.
+    /// tx2.unbounded_send(2).unwrap();
+    /// assert_eq!(stream.next().await, Some(2));
+    ///
+    /// tx1.unbounded_send(1).unwrap();
+    /// assert_eq!(stream.next().await, Some(1));
+    ///
+    /// drop((tx1, tx2));
+    /// assert_eq!(stream.next().await, None);
+    /// # });
+    /// ```
+    #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
+    #[cfg(feature = "alloc")]
+    fn flatten_unordered(self, limit: impl Into<Option<usize>>) -> FlattenUnordered<Self>
+    where
+        Self::Item: Stream + Unpin,
+        Self: Sized,
+    {
+        assert_stream::<<Self::Item as Stream>::Item, _>(FlattenUnordered::new(self, limit.into()))
+    }
+
+    /// Maps a stream like [`StreamExt::map`] but flattens the resulting
+    /// streams concurrently, like [`StreamExt::flatten_unordered`].
+    ///
+    /// Up to `limit` of the streams produced by the closure are polled at a
+    /// time, and their items are yielded in the order in which they become
+    /// ready. A `limit` of `None` or zero means no limit.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let stream = stream::iter(1..=3);
+    /// let stream = stream.flat_map_unordered(2, |x| stream::iter(vec![x; x]));
+    ///
+    /// let mut items = stream.collect::<Vec<_>>().await;
+    /// items.sort();
+    /// assert_eq!(vec![1, 2, 2, 3, 3, 3], items);
+    /// # });
+    /// ```
+    #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
+    #[cfg(feature = "alloc")]
+    fn flat_map_unordered<U, F>(
+        self,
+        limit: impl Into<Option<usize>>,
+        f: F,
+    ) -> FlatMapUnordered<Self, U, F>
+    where
+        U: Stream + Unpin,
+        F: FnMut(Self::Item) -> U,
+        Self: Sized,
+    {
+        assert_stream::<U::Item, _>(FlatMapUnordered::new(self, limit.into(), f))
+    }
+
     /// Combinator similar to [`StreamExt::fold`] that holds internal state
     /// and produces a new stream.
     ///
@@ -1104,6 +1262,105 @@ pub trait StreamExt: Stream {
         assert_stream(CatchUnwind::new(self))
     }
 
+    /// Splits this stream into branches which each yield every item of the
+    /// stream.
+    ///
+    /// The returned stream is the first branch, and each clone of it is
+    /// another one, which starts at the item its original would yield next.
+    /// Whichever branch is polled first pulls the next item from the
+    /// underlying stream, and a clone of it is kept for the other branches.
+    ///
+    /// At most `capacity` items are kept this way. Once that many items are
+    /// waiting for the slowest branch, the faster branches wait for it to
+    /// catch up, so memory use stays bounded. Dropping a branch releases the
+    /// items it had not seen yet, and never holds up the others.
+    ///
+    /// The branches are `Send` and `Sync` if the stream and its items are, so
+    /// they can be consumed from different tasks.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// let left = stream::iter(1..=3).fork(2);
+    /// let right = left.clone();
+    ///
+    /// let (left, right) = future::join(
+    ///     left.collect::<Vec<_>>(),
+    ///     right.map(|x| x * 10).collect::<Vec<_>>(),
+    /// ).await;
+    /// assert_eq!(left, vec![1, 2, 3]);
+    /// assert_eq!(right, vec![10, 20, 30]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn fork(self, capacity: usize) -> Fork<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        assert_stream::<Self::Item, _>(Fork::new(self, capacity))
+    }
+
+    /// Creates a cloneable handle to this stream, which lets several tasks
+    /// consume the items of the stream.
+    ///
+    /// This is the stream counterpart of
+    /// [`FutureExt::shared`](crate::future::FutureExt::shared). Every clone
+    /// yields every item of the stream from the point where it starts on.
+    /// Which items that is depends on `replay`:
+    ///
+    /// - [`Replay::None`]: a new clone yields the items pulled from the
+    ///   stream after it was created.
+    /// - [`Replay::Last(n)`](Replay::Last): a new clone first yields the `n`
+    ///   items pulled from the stream most recently, if there are that many.
+    /// - [`Replay::All`]: a new clone yields every item of the stream, which
+    ///   are all kept in memory for this.
+    ///
+    /// Unlike [`fork`](StreamExt::fork), there is no backpressure: items are
+    /// kept until every clone has yielded them, so a clone which is not
+    /// polled holds on to the items after its position. Such clones should be
+    /// dropped.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, Replay, StreamExt};
+    ///
+    /// let mut live = stream::iter(1..=4).shared(Replay::None);
+    /// assert_eq!(live.next().await, Some(1));
+    /// assert_eq!(live.clone().collect::<Vec<_>>().await, vec![2, 3, 4]);
+    ///
+    /// let mut last = stream::iter(1..=4).shared(Replay::Last(1));
+    /// assert_eq!(last.next().await, Some(1));
+    /// assert_eq!(last.next().await, Some(2));
+    /// assert_eq!(last.clone().collect::<Vec<_>>().await, vec![2, 3, 4]);
+    /// assert_eq!(last.collect::<Vec<_>>().await, vec![3, 4]);
+    /// # });
+    /// ```
+    #[cfg(feature = "std")]
+    fn shared(self, replay: Replay) -> SharedStream<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        assert_stream::<Self::Item, _>(SharedStream::new(self, replay))
+    }
+
     /// Wrap the stream in a Box, pinning it.
     ///
     /// This method is only available when the `std` or `alloc` feature of this
@@ -1320,6 +1577,214 @@ pub trait StreamExt: Stream {
         assert_stream::<Vec<Self::Item>, _>(ReadyChunks::new(self, capacity))
     }
 
+    /// An adaptor for chunking up items of the stream inside a vector, yielding
+    /// a partial chunk once `duration` has elapsed since its first item.
+    ///
+    /// This works like [`chunks`](StreamExt::chunks), except that a chunk is
+    /// never held back for longer than `duration`, as measured by `timer`,
+    /// waiting for it to fill up.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let stream = stream::iter(vec![1, 2, 3])
+    ///     .chain(stream::once(clock.sleep(Duration::from_secs(10))).filter_map(|()| async { None }))
+    ///     .chain(stream::iter(vec![4]))
+    ///     .chunks_timeout(10, &clock, Duration::from_secs(1));
+    ///
+    /// assert_eq!(clock.block_on(stream.collect::<Vec<_>>()), vec![vec![1, 2, 3], vec![4]]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn chunks_timeout<T>(self, capacity: usize, timer: T, duration: Duration) -> ChunksTimeout<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Vec<Self::Item>, _>(ChunksTimeout::new(self, capacity, timer, duration))
+    }
+
+    /// Requires each item of this stream to arrive within `duration` of the
+    /// stream starting to wait for it, as measured by `timer`.
+    ///
+    /// Each time the deadline passes without an item, `Err(TimedOut)` is
+    /// yielded and a new deadline starts; the underlying stream is not
+    /// dropped, so the caller decides whether to keep waiting.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures::time::TimedOut;
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let slow = stream::once(async {
+    ///     clock.sleep(Duration::from_secs(3)).await;
+    ///     1
+    /// });
+    /// let stream = slow.timeout(&clock, Duration::from_secs(2));
+    ///
+    /// assert_eq!(clock.block_on(stream.collect::<Vec<_>>()), vec![Err(TimedOut), Ok(1)]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn timeout<T>(self, timer: T, duration: Duration) -> Timeout<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Result<Self::Item, TimedOut>, _>(Timeout::new(self, timer, duration))
+    }
+
+    /// Limits this stream to at most one item per `duration`, as measured by
+    /// `timer`.
+    ///
+    /// After an item is yielded, the next one is held back until `duration`
+    /// has elapsed. No items are dropped: at most one item is buffered while
+    /// waiting, so a fast stream is slowed down instead.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let start = clock.now();
+    /// let stream = stream::iter(1..=3).throttle(&clock, Duration::from_secs(1));
+    ///
+    /// assert_eq!(clock.block_on(stream.collect::<Vec<_>>()), vec![1, 2, 3]);
+    /// assert_eq!(clock.now() - start, Duration::from_secs(2));
+    /// ```
+    #[cfg(feature = "std")]
+    fn throttle<T>(self, timer: T, duration: Duration) -> Throttle<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Throttle::new(self, timer, duration))
+    }
+
+    /// Yields an item only once `duration`, as measured by `timer`, has passed
+    /// without the stream producing a newer one.
+    ///
+    /// Items that are superseded within `duration` are dropped. When the
+    /// underlying stream ends, the pending item, if any, is yielded
+    /// immediately.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// let keystrokes = stream::iter(vec![(0, 'a'), (1, 'b'), (5, 'c'), (1, 'd')])
+    ///     .then(|(wait, key)| {
+    ///         let sleep = clock.sleep(Duration::from_secs(wait));
+    ///         async move {
+    ///             sleep.await;
+    ///             key
+    ///         }
+    ///     });
+    /// let stream = keystrokes.debounce(&clock, Duration::from_secs(2));
+    ///
+    /// assert_eq!(clock.block_on(stream.collect::<Vec<_>>()), vec!['b', 'd']);
+    /// ```
+    #[cfg(feature = "std")]
+    fn debounce<T>(self, timer: T, duration: Duration) -> Debounce<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Debounce::new(self, timer, duration))
+    }
+
+    /// Yields the most recent item of this stream once every `period`, as
+    /// measured by `timer`.
+    ///
+    /// Periods in which the stream produced no item are skipped, and all but
+    /// the last item produced within a period are dropped. When the
+    /// underlying stream ends, the item received since the last sample, if
+    /// any, is yielded immediately.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `period` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream::{self, StreamExt};
+    /// use futures_test::time::Clock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Clock::new();
+    /// clock.set_auto_advance(true);
+    ///
+    /// // One reading per second, sampled every 2.2 seconds.
+    /// let readings = stream::iter(1..=7).throttle(&clock, Duration::from_secs(1));
+    /// let stream = readings.sample(&clock, Duration::from_millis(2200));
+    ///
+    /// assert_eq!(clock.block_on(stream.collect::<Vec<_>>()), vec![3, 5, 7]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn sample<T>(self, timer: T, period: Duration) -> Sample<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Sample::new(self, timer, period))
+    }
+
+    /// Waits for `duration`, as measured by `timer`, before polling this
+    /// stream for the first time.
+    ///
+    /// This method is only available when the `std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "std")]
+    fn delay<T>(self, timer: T, duration: Duration) -> Delay<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        assert_stream::<Self::Item, _>(Delay::new(self, timer.sleep(duration)))
+    }
+
     /// A future that completes after the given stream has been fully processed
     /// into the sink and the sink has been flushed and closed.
     ///
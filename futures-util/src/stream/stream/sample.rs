@@ -0,0 +1,75 @@
+use crate::stream::{Fuse, StreamExt};
+use crate::time::Timer;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::{Duration, Instant};
+
+pin_project! {
+    /// Stream for the [`sample`](super::StreamExt::sample) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Sample<St: Stream, T: Timer> {
+        #[pin]
+        stream: Fuse<St>,
+        timer: T,
+        period: Duration,
+        deadline: Instant,
+        latest: Option<St::Item>,
+        #[pin]
+        sleep: T::Sleep,
+    }
+}
+
+impl<St: Stream, T: Timer> Sample<St, T> {
+    pub(super) fn new(stream: St, timer: T, period: Duration) -> Self {
+        assert!(period > Duration::from_secs(0), "`period` must be non-zero");
+        let deadline = timer.now() + period;
+        let sleep = timer.sleep_until(deadline);
+        Self { stream: stream.fuse(), timer, period, deadline, latest: None, sleep }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, T: Timer> Stream for Sample<St, T> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+
+        loop {
+            // Keep only the most recent item seen since the last sample.
+            loop {
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => *this.latest = Some(item),
+                    Poll::Ready(None) => return Poll::Ready(this.latest.take()),
+                    Poll::Pending => break,
+                }
+            }
+
+            ready!(this.sleep.as_mut().poll(cx));
+            *this.deadline += *this.period;
+            this.sleep.set(this.timer.sleep_until(*this.deadline));
+
+            if let Some(item) = this.latest.take() {
+                return Poll::Ready(Some(item));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let latest = if self.latest.is_some() { 1 } else { 0 };
+        let (_, upper) = self.stream.size_hint();
+        (0, upper.and_then(|upper| upper.checked_add(latest)))
+    }
+}
+
+impl<St: Stream, T: Timer> FusedStream for Sample<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.latest.is_none()
+    }
+}
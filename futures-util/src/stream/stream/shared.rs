@@ -0,0 +1,85 @@
+use super::broadcast::Branch;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::fmt;
+use std::pin::Pin;
+
+/// Which items of a [`SharedStream`] a new clone of it yields, see the
+/// [`shared`](super::StreamExt::shared) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Replay {
+    /// New clones only yield the items pulled from the stream after they were
+    /// created.
+    None,
+    /// New clones first yield up to this many of the items most recently
+    /// pulled from the stream.
+    Last(usize),
+    /// New clones yield every item of the stream, from the first one on.
+    All,
+}
+
+impl Replay {
+    fn len(self) -> usize {
+        match self {
+            Replay::None => 0,
+            Replay::Last(n) => n,
+            Replay::All => usize::max_value(),
+        }
+    }
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Replay::None
+    }
+}
+
+/// Stream for the [`shared`](super::StreamExt::shared) method.
+#[must_use = "streams do nothing unless polled"]
+pub struct SharedStream<St: Stream> {
+    branch: Branch<St>,
+}
+
+// The stream is polled behind an `Arc`, so it won't be moved when
+// `SharedStream` is moved.
+impl<St: Stream> Unpin for SharedStream<St> {}
+
+impl<St: Stream> fmt::Debug for SharedStream<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedStream").field("branch", &self.branch).finish()
+    }
+}
+
+impl<St: Stream> SharedStream<St> {
+    pub(super) fn new(stream: St, replay: Replay) -> Self {
+        Self { branch: Branch::new(stream, None, replay.len()) }
+    }
+}
+
+impl<St> FusedStream for SharedStream<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.branch.is_terminated()
+    }
+}
+
+impl<St> Stream for SharedStream<St>
+where
+    St: Stream,
+    St::Item: Clone,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        self.branch.poll_next(cx)
+    }
+}
+
+impl<St: Stream> Clone for SharedStream<St> {
+    fn clone(&self) -> Self {
+        Self { branch: self.branch.subscribe() }
+    }
+}
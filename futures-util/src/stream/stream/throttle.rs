@@ -0,0 +1,84 @@
+use crate::stream::{Fuse, StreamExt};
+use crate::time::Timer;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`throttle`](super::StreamExt::throttle) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Throttle<St: Stream, T: Timer> {
+        #[pin]
+        stream: Fuse<St>,
+        timer: T,
+        duration: Duration,
+        // An item received while cooling down, held until `sleep` completes.
+        pending: Option<St::Item>,
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<St: Stream, T: Timer> Throttle<St, T> {
+    pub(super) fn new(stream: St, timer: T, duration: Duration) -> Self {
+        Self { stream: stream.fuse(), timer, duration, pending: None, sleep: None }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, T: Timer> Stream for Throttle<St, T> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let mut this = self.project();
+
+        if this.sleep.is_some() {
+            // Keep polling while cooling down so that the end of the stream
+            // is noticed without waiting out the full duration.
+            if this.pending.is_none() {
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => *this.pending = Some(item),
+                    Poll::Ready(None) => {
+                        this.sleep.set(None);
+                        return Poll::Ready(None);
+                    }
+                    Poll::Pending => {}
+                }
+            }
+            ready!(this.sleep.as_mut().as_pin_mut().unwrap().poll(cx));
+            this.sleep.set(None);
+        }
+
+        let item = match this.pending.take() {
+            Some(item) => Some(item),
+            None => ready!(this.stream.poll_next(cx)),
+        };
+        if item.is_some() {
+            this.sleep.set(Some(this.timer.sleep(*this.duration)));
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = if self.pending.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+        let lower = lower.saturating_add(pending);
+        let upper = match upper {
+            Some(x) => x.checked_add(pending),
+            None => None,
+        };
+        (lower, upper)
+    }
+}
+
+impl<St: Stream, T: Timer> FusedStream for Throttle<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.pending.is_none()
+    }
+}
@@ -0,0 +1,64 @@
+use crate::stream::{Fuse, StreamExt};
+use crate::time::{TimedOut, Timer};
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+pin_project! {
+    /// Stream for the [`timeout`](super::StreamExt::timeout) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Timeout<St, T: Timer> {
+        #[pin]
+        stream: Fuse<St>,
+        timer: T,
+        duration: Duration,
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<St: Stream, T: Timer> Timeout<St, T> {
+    pub(super) fn new(stream: St, timer: T, duration: Duration) -> Self {
+        Self { stream: stream.fuse(), timer, duration, sleep: None }
+    }
+
+    delegate_access_inner!(stream, St, (.));
+}
+
+impl<St: Stream, T: Timer> Stream for Timeout<St, T> {
+    type Item = Result<St::Item, TimedOut>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Poll::Ready(item) = this.stream.poll_next(cx) {
+            this.sleep.set(None);
+            return Poll::Ready(item.map(Ok));
+        }
+
+        // The deadline for the next item starts when we first have to wait.
+        if this.sleep.is_none() {
+            this.sleep.set(Some(this.timer.sleep(*this.duration)));
+        }
+        ready!(this.sleep.as_mut().as_pin_mut().unwrap().poll(cx));
+        this.sleep.set(None);
+        Poll::Ready(Some(Err(TimedOut)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, _) = self.stream.size_hint();
+        // Any number of timeouts may be interleaved with the items.
+        (lower, None)
+    }
+}
+
+impl<St: Stream, T: Timer> FusedStream for Timeout<St, T> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
@@ -0,0 +1,283 @@
+//! A keyed set of streams.
+//!
+//! This module is only available when the `std` feature of this library is
+//! activated, and it is activated by default.
+
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+
+use crate::stream::futures_unordered::{FuturesMap, MapIter, MapIterMut};
+use crate::stream::{StreamExt, StreamFuture};
+
+/// An event produced by [`StreamMap::poll_next_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<K, T> {
+    /// The stream stored under the key yielded an item.
+    Item(K, T),
+    /// The stream stored under the key ended, and was removed from the map.
+    Ended(K),
+}
+
+/// A set of streams, each stored under a key, whose items are yielded as
+/// they become ready.
+///
+/// This is a [`SelectAll`](super::SelectAll) which remembers the key each
+/// stream was [`insert`](StreamMap::insert)ed with. It yields the key of
+/// each stream together with its items, and allows streams to be removed
+/// again by key. Streams which end are removed from the map, and their key
+/// is yielded by [`poll_next_event`](StreamMap::poll_next_event) as an
+/// [`Event::Ended`].
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::stream::{self, StreamMap};
+/// use futures::stream::stream_map::Event;
+///
+/// let mut peers = StreamMap::new();
+/// peers.insert("alice", stream::iter(vec![1, 2]));
+/// peers.insert("bob", stream::iter(vec![3]));
+///
+/// let mut events = Vec::new();
+/// while let Some(event) = peers.next_event().await {
+///     events.push(event);
+/// }
+/// assert!(events.contains(&Event::Item("bob", 3)));
+/// assert!(events.contains(&Event::Ended("bob")));
+/// assert_eq!(events.len(), 5);
+/// # });
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct StreamMap<K, St> {
+    inner: FuturesMap<K, StreamFuture<St>>,
+}
+
+impl<K, St> Debug for StreamMap<K, St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StreamMap {{ ... }}")
+    }
+}
+
+impl<K, St> StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    /// Constructs a new, empty `StreamMap`.
+    ///
+    /// The returned `StreamMap` does not contain any streams and, in this
+    /// state, `StreamMap::poll_next` will return `Poll::Ready(None)`.
+    pub fn new() -> Self {
+        Self { inner: FuturesMap::new() }
+    }
+
+    /// Returns the number of streams contained in the map.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the map contains no streams.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Inserts a stream into the map under the given key, and returns the
+    /// stream previously stored under it, if any.
+    ///
+    /// This function will not call `poll` on the submitted stream. The caller
+    /// must ensure that `StreamMap::poll_next` is called in order to receive
+    /// task notifications.
+    pub fn insert(&mut self, key: K, stream: St) -> Option<St> {
+        let prev = self.remove(&key);
+        self.inner.insert(key, stream.into_future());
+        prev
+    }
+
+    /// Removes the stream stored under the given key from the map, and
+    /// returns it.
+    pub fn remove(&mut self, key: &K) -> Option<St> {
+        self.inner.remove(key).and_then(StreamFuture::into_inner)
+    }
+
+    /// Returns `true` if the map contains a stream under the given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Returns an iterator over the keys and streams in the map, in
+    /// arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, St> {
+        Iter(self.inner.iter())
+    }
+
+    /// Returns an iterator that allows modifying each stream in the map, in
+    /// arbitrary order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, St> {
+        IterMut(self.inner.iter_mut())
+    }
+
+    /// Polls for the next item of any of the streams, or for the end of one
+    /// of them.
+    ///
+    /// Unlike [`poll_next`](Stream::poll_next), which removes streams that
+    /// end silently, this yields an [`Event::Ended`] with the key of each
+    /// stream that ends.
+    pub fn poll_next_event(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Event<K, St::Item>>> {
+        match ready!(self.inner.poll_next_unpin(cx)) {
+            Some((key, (Some(item), remaining))) => {
+                self.inner.insert(key.clone(), remaining.into_future());
+                Poll::Ready(Some(Event::Item(key, item)))
+            }
+            Some((key, (None, _))) => Poll::Ready(Some(Event::Ended(key))),
+            None => Poll::Ready(None),
+        }
+    }
+
+    /// Creates a future that resolves to the next [`Event`] of the map.
+    ///
+    /// This is the async counterpart of
+    /// [`poll_next_event`](StreamMap::poll_next_event).
+    pub fn next_event(&mut self) -> NextEvent<'_, K, St> {
+        NextEvent { map: self }
+    }
+}
+
+impl<K, St> Default for StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, St> Stream for StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    type Item = (K, St::Item);
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            match ready!(self.poll_next_event(cx)) {
+                Some(Event::Item(key, item)) => return Poll::Ready(Some((key, item))),
+                Some(Event::Ended(_)) => {}
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<K, St> FusedStream for StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<K, St> FromIterator<(K, St)> for StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    fn from_iter<T: IntoIterator<Item = (K, St)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, St> Extend<(K, St)> for StreamMap<K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    fn extend<T: IntoIterator<Item = (K, St)>>(&mut self, iter: T) {
+        for (key, stream) in iter {
+            self.insert(key, stream);
+        }
+    }
+}
+
+/// Immutable iterator over all keys and streams in a [`StreamMap`].
+#[derive(Debug)]
+pub struct Iter<'a, K, St>(MapIter<'a, K, StreamFuture<St>>);
+
+impl<'a, K, St: Stream + Unpin> Iterator for Iter<'a, K, St> {
+    type Item = (&'a K, &'a St);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Streams are only taken out of their `StreamFuture` while they are
+        // polled, which cannot happen while the map is borrowed.
+        self.0.next().map(|(key, future)| (key, future.get_ref().unwrap()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Mutable iterator over all keys and streams in a [`StreamMap`].
+#[derive(Debug)]
+pub struct IterMut<'a, K, St: Unpin>(MapIterMut<'a, K, StreamFuture<St>>);
+
+impl<'a, K, St: Stream + Unpin> Iterator for IterMut<'a, K, St> {
+    type Item = (&'a K, &'a mut St);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, future)| (key, future.get_mut().unwrap()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Future for the [`next_event`](StreamMap::next_event) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextEvent<'a, K, St> {
+    map: &'a mut StreamMap<K, St>,
+}
+
+impl<K, St> Unpin for NextEvent<'_, K, St> {}
+
+impl<K, St> FusedFuture for NextEvent<'_, K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.map.is_terminated()
+    }
+}
+
+impl<K, St> Future for NextEvent<'_, K, St>
+where
+    K: Clone + Eq + Hash,
+    St: Stream + Unpin,
+{
+    type Output = Option<Event<K, St::Item>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.map.poll_next_event(cx)
+    }
+}
@@ -122,6 +122,12 @@ cfg_target_has_atomic! {
     #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
     pub use self::try_buffered::TryBuffered;
 
+    #[cfg(feature = "alloc")]
+    mod try_flatten_unordered;
+    #[cfg(feature = "alloc")]
+    #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
+    pub use self::try_flatten_unordered::TryFlattenUnordered;
+
     #[cfg(feature = "alloc")]
     mod try_for_each_concurrent;
     #[cfg(feature = "alloc")]
@@ -700,6 +706,57 @@ pub trait TryStreamExt: TryStream {
         )
     }
 
+    /// Flattens a stream of streams into just one stream, polling the inner
+    /// streams concurrently.
+    ///
+    /// This is the fallible counterpart of
+    /// [`StreamExt::flatten_unordered`](crate::stream::StreamExt::flatten_unordered):
+    /// up to `limit` inner streams are polled at a time, and their items are
+    /// yielded in the order in which they become ready. Errors from the
+    /// underlying stream itself are yielded as soon as they are seen, and
+    /// errors from the inner streams are passed through without looking at
+    /// them. A `limit` of `None` or zero means no limit.
+    ///
+    /// This method is only available when the `std` or `alloc` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::{StreamExt, TryStreamExt};
+    ///
+    /// let (tx1, rx1) = mpsc::unbounded();
+    /// let (tx2, rx2) = mpsc::unbounded();
+    /// let (tx3, rx3) = mpsc::unbounded();
+    ///
+    /// let mut stream = rx3.try_flatten_unordered(None);
+    /// tx3.unbounded_send(Ok(rx1)).unwrap();
+    /// tx3.unbounded_send(Ok(rx2)).unwrap();
+    ///
+    /// tx2.unbounded_send(Ok(2)).unwrap();
+    /// assert_eq!(stream.next().await, Some(Ok(2)));
+    /// tx1.unbounded_send(Err(1)).unwrap();
+    /// assert_eq!(stream.next().await, Some(Err(1)));
+    ///
+    /// tx3.unbounded_send(Err(3)).unwrap();
+    /// assert_eq!(stream.next().await, Some(Err(3)));
+    /// # });
+    /// ```
+    #[cfg_attr(feature = "cfg-target-has-atomic", cfg(target_has_atomic = "ptr"))]
+    #[cfg(feature = "alloc")]
+    fn try_flatten_unordered(self, limit: impl Into<Option<usize>>) -> TryFlattenUnordered<Self>
+    where
+        Self::Ok: TryStream + Unpin,
+        <Self::Ok as TryStream>::Error: From<Self::Error>,
+        Self: Sized,
+    {
+        assert_stream::<Result<<Self::Ok as TryStream>::Ok, <Self::Ok as TryStream>::Error>, _>(
+            TryFlattenUnordered::new(self, limit.into()),
+        )
+    }
+
     /// Attempt to execute an accumulating asynchronous computation over a
     /// stream, collecting all the values into one final result.
     ///
@@ -0,0 +1,106 @@
+use crate::stream::{Fuse, FuturesUnordered, IntoStream, StreamExt, StreamFuture};
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+#[cfg(feature = "sink")]
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the
+    /// [`try_flatten_unordered`](super::TryStreamExt::try_flatten_unordered) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TryFlattenUnordered<St>
+        where St: TryStream
+    {
+        #[pin]
+        stream: Fuse<IntoStream<St>>,
+        inner_streams: FuturesUnordered<StreamFuture<IntoStream<St::Ok>>>,
+        limit: Option<NonZeroUsize>,
+    }
+}
+
+impl<St> TryFlattenUnordered<St>
+    where St: TryStream,
+          St::Ok: TryStream + Unpin,
+{
+    pub(super) fn new(stream: St, limit: Option<usize>) -> Self {
+        Self {
+            stream: IntoStream::new(stream).fuse(),
+            inner_streams: FuturesUnordered::new(),
+            // Note: `limit` = 0 gets ignored.
+            limit: limit.and_then(NonZeroUsize::new),
+        }
+    }
+
+    delegate_access_inner!(stream, St, (. .));
+}
+
+impl<St> Stream for TryFlattenUnordered<St>
+    where St: TryStream,
+          St::Ok: TryStream + Unpin,
+          <St::Ok as TryStream>::Error: From<St::Error>,
+{
+    type Item = Result<<St::Ok as TryStream>::Ok, <St::Ok as TryStream>::Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            // First up, try to pull in as many inner streams as the limit
+            // allows. Propagate errors from the stream immediately.
+            while this.limit.map(|limit| limit.get() > this.inner_streams.len()).unwrap_or(true) {
+                match this.stream.as_mut().poll_next(cx)? {
+                    Poll::Ready(Some(stream)) => {
+                        this.inner_streams.push(IntoStream::new(stream).into_future())
+                    }
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+
+            match this.inner_streams.poll_next_unpin(cx) {
+                Poll::Ready(Some((Some(item), stream))) => {
+                    this.inner_streams.push(stream.into_future());
+                    return Poll::Ready(Some(item));
+                }
+                // An inner stream ended, so there may be room for another
+                // one now.
+                Poll::Ready(Some((None, _))) => {}
+                Poll::Ready(None) => {
+                    // If more streams are still coming, we're not done yet
+                    return if this.stream.is_done() {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Pending
+                    };
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<St> FusedStream for TryFlattenUnordered<St>
+    where St: TryStream,
+          St::Ok: TryStream + Unpin,
+          <St::Ok as TryStream>::Error: From<St::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated() && self.inner_streams.is_empty()
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+#[cfg(feature = "sink")]
+impl<S, Item, E> Sink<Item> for TryFlattenUnordered<S>
+    where S: TryStream + Sink<Item, Error = E>,
+{
+    type Error = E;
+
+    delegate_sink!(stream, Item);
+}
@@ -0,0 +1,113 @@
+//! Abstractions over a source of time.
+//!
+//! futures-rs does not own a clock. Instead, the time-based combinators on
+//! [`FutureExt`](crate::future::FutureExt) and
+//! [`StreamExt`](crate::stream::StreamExt), such as `timeout`, `throttle` and
+//! `debounce`, take a [`Timer`] instance. A runtime can provide an
+//! implementation backed by its own timer, and tests can use a manually
+//! advanced one to run time-dependent code instantly and deterministically.
+
+use core::fmt;
+use futures_core::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A source of time that can create futures completing at a given instant.
+///
+/// # Examples
+///
+/// A timer that sleeps by blocking a helper thread:
+///
+/// ```
+/// use futures::channel::oneshot;
+/// use futures::future::{FutureExt, Map};
+/// use futures::time::Timer;
+/// use std::thread;
+/// use std::time::Instant;
+///
+/// struct ThreadTimer;
+///
+/// impl Timer for ThreadTimer {
+///     type Sleep = Map<oneshot::Receiver<()>, fn(Result<(), oneshot::Canceled>)>;
+///
+///     fn now(&self) -> Instant {
+///         Instant::now()
+///     }
+///
+///     fn sleep_until(&self, deadline: Instant) -> Self::Sleep {
+///         let (tx, rx) = oneshot::channel();
+///         thread::spawn(move || {
+///             let now = Instant::now();
+///             if deadline > now {
+///                 thread::sleep(deadline - now);
+///             }
+///             let _ = tx.send(());
+///         });
+///         rx.map(drop)
+///     }
+/// }
+/// ```
+pub trait Timer {
+    /// The future returned by [`sleep_until`](Timer::sleep_until).
+    type Sleep: Future<Output = ()>;
+
+    /// Returns the current instant according to this timer.
+    fn now(&self) -> Instant;
+
+    /// Returns a future that completes once [`now`](Timer::now) reaches
+    /// `deadline`.
+    ///
+    /// A `deadline` that is not in the future should complete the returned
+    /// future on its first poll.
+    fn sleep_until(&self, deadline: Instant) -> Self::Sleep;
+
+    /// Returns a future that completes once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        self.sleep_until(self.now() + duration)
+    }
+}
+
+impl<T: ?Sized + Timer> Timer for &T {
+    type Sleep = T::Sleep;
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Self::Sleep {
+        (**self).sleep_until(deadline)
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        (**self).sleep(duration)
+    }
+}
+
+impl<T: ?Sized + Timer> Timer for Arc<T> {
+    type Sleep = T::Sleep;
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Self::Sleep {
+        (**self).sleep_until(deadline)
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        (**self).sleep(duration)
+    }
+}
+
+/// Error returned by the `timeout` combinators when the deadline elapses
+/// before the future or stream produced a value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for TimedOut {}
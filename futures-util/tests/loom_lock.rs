@@ -0,0 +1,71 @@
+//! Model checks the races between the users of `Mutex` and `BiLock`.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=2 cargo test --release --features bilock,unstable --test loom_lock`.
+
+#![cfg(loom)]
+
+use futures_util::future::{poll_fn, FutureExt};
+use futures_util::lock::Mutex;
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+use std::task::Poll;
+
+#[test]
+fn mutex_contention() {
+    loom::model(|| {
+        let mutex = Arc::new(Mutex::new(0));
+        let mutex2 = mutex.clone();
+
+        let t = thread::spawn(move || *block_on(mutex2.lock()) += 1);
+        *block_on(mutex.lock()) += 1;
+
+        t.join().unwrap();
+        assert_eq!(*mutex.try_lock().unwrap(), 2);
+    });
+}
+
+#[test]
+fn mutex_waiter_dropped() {
+    loom::model(|| {
+        let mutex = Arc::new(Mutex::new(0));
+        let guard = mutex.try_lock().unwrap();
+
+        // Starts waiting for the lock, then gives up. The wakeup it may have
+        // been sent has to be passed on to the other waiter.
+        let mutex2 = mutex.clone();
+        let t1 = thread::spawn(move || {
+            let mut lock = mutex2.lock();
+            block_on(poll_fn(|cx| {
+                let _ = lock.poll_unpin(cx);
+                Poll::Ready(())
+            }));
+        });
+        let mutex3 = mutex.clone();
+        let t2 = thread::spawn(move || *block_on(mutex3.lock()) += 1);
+
+        drop(guard);
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(*mutex.try_lock().unwrap(), 1);
+    });
+}
+
+#[cfg(feature = "bilock")]
+#[test]
+fn bilock_contention() {
+    use futures_util::lock::BiLock;
+
+    loom::model(|| {
+        let (a, b) = BiLock::new(0);
+
+        let t = thread::spawn(move || {
+            *block_on(b.lock()) += 1;
+            b
+        });
+        *block_on(a.lock()) += 1;
+
+        let b = t.join().unwrap();
+        assert_eq!(a.reunite(b).unwrap(), 2);
+    });
+}
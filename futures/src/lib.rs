@@ -127,9 +127,12 @@ pub use futures_core::ready; // Readiness propagation
 pub use futures_util::pin_mut;
 #[cfg(feature = "std")]
 #[cfg(feature = "async-await")]
-pub use futures_util::select;
+pub use futures_util::{select, select_loop};
 #[cfg(feature = "async-await")]
 pub use futures_util::{join, pending, poll, select_biased, try_join}; // Async-await
+#[cfg(feature = "alloc")]
+#[cfg(feature = "async-await")]
+pub use futures_util::try_stream; // `stream!` comes with the `stream` module
 
 // Module reexports
 #[doc(inline)]
@@ -146,6 +149,10 @@ pub use futures_util::lock;
 #[doc(inline)]
 pub use futures_util::io;
 
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use futures_util::time;
+
 #[cfg(feature = "executor")]
 #[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
 #[doc(inline)]
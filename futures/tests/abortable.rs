@@ -43,3 +43,99 @@ fn abortable_resolves() {
 
     assert_eq!(Ok(Ok(())), block_on(abortable_rx));
 }
+
+#[test]
+fn abortable_stream_ends() {
+    use futures::executor::block_on;
+    use futures::stream::{self, Stream, StreamExt};
+
+    let (mut s, abort_handle) = stream::abortable(stream::iter(vec![1, 2, 3]));
+    assert_eq!(block_on(s.next()), Some(1));
+    assert!(!s.is_aborted());
+
+    abort_handle.abort();
+    assert!(s.is_aborted());
+    assert!(abort_handle.is_aborted());
+    assert_eq!(block_on(s.next()), None);
+    assert_eq!(s.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn abortable_stream_awakens() {
+    use futures::channel::mpsc;
+    use futures::stream::{self, FusedStream, StreamExt};
+    use futures::task::{Context, Poll};
+    use futures_test::task::new_count_waker;
+
+    let (_tx, rx) = mpsc::unbounded::<()>();
+    let (mut s, abort_handle) = stream::abortable(rx);
+
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(Poll::Pending, s.poll_next_unpin(&mut cx));
+    assert!(!s.is_terminated());
+    abort_handle.abort();
+    assert_eq!(counter, 1);
+    assert!(s.is_terminated());
+    assert_eq!(Poll::Ready(None), s.poll_next_unpin(&mut cx));
+}
+
+#[test]
+fn abortable_stream_stops_pipeline() {
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use futures::future::{self, FutureExt};
+    use futures::stream::{self, StreamExt};
+
+    let (tx, rx) = mpsc::unbounded();
+    let (rx, abort_handle) = stream::abortable(rx);
+    let (out_tx, out_rx) = mpsc::unbounded();
+
+    for i in 0..5 {
+        tx.unbounded_send(i).unwrap();
+    }
+    let pipeline = rx
+        .map(|i: i32| {
+            if i == 2 {
+                abort_handle.abort();
+            }
+            Ok(i * 10)
+        })
+        .forward(out_tx)
+        .map(|res| res.unwrap());
+    block_on(future::join(pipeline, async { drop(tx) }));
+
+    // The sink was closed when the stream ended.
+    assert_eq!(block_on(out_rx.collect::<Vec<_>>()), vec![0, 10, 20]);
+}
+
+#[test]
+fn abortable_sink_rejects_items() {
+    use futures::executor::block_on;
+    use futures::future::{AbortHandle, Abortable, Aborted};
+    use futures::sink::{self, SinkExt};
+
+    #[derive(Debug, PartialEq)]
+    struct Error;
+
+    impl From<Aborted> for Error {
+        fn from(_: Aborted) -> Self {
+            Error
+        }
+    }
+
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    let mut items = Vec::new();
+    let sink = sink::unfold(&mut items, |items, item: i32| async move {
+        items.push(item);
+        Ok::<_, Error>(items)
+    });
+    let sink = Abortable::new(sink, abort_registration);
+    futures::pin_mut!(sink);
+
+    block_on(sink.send(1)).unwrap();
+    abort_handle.abort();
+    assert_eq!(block_on(sink.send(2)), Err(Error));
+    block_on(sink.close()).unwrap();
+    assert_eq!(items, vec![1]);
+}
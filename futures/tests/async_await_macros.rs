@@ -430,3 +430,441 @@ fn try_join_doesnt_require_unpin() {
         )
     };
 }
+
+#[test]
+fn stream_macro() {
+    use futures::{pin_mut, stream};
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+
+    let s = stream! {
+        for i in 0..3 {
+            yield i;
+        }
+        let last = async { 10 }.await;
+        yield last;
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<_>>()), vec![0, 1, 2, 10]);
+}
+
+#[test]
+fn stream_macro_is_lazy() {
+    use futures::{pin_mut, poll, stream};
+    use futures::executor::block_on;
+    use futures::stream::{FusedStream, StreamExt};
+    use futures::task::Poll;
+    use std::cell::Cell;
+
+    let progress = &Cell::new(0);
+    let s = stream! {
+        progress.set(1);
+        yield ();
+        progress.set(2);
+    };
+    pin_mut!(s);
+    assert_eq!(progress.get(), 0);
+
+    block_on(async {
+        assert_eq!(poll!(s.next()), Poll::Ready(Some(())));
+        assert_eq!(progress.get(), 1);
+        assert!(!s.is_terminated());
+        assert_eq!(poll!(s.next()), Poll::Ready(None));
+        assert_eq!(progress.get(), 2);
+        assert!(s.is_terminated());
+        assert_eq!(poll!(s.next()), Poll::Ready(None));
+    });
+}
+
+#[test]
+fn stream_macro_awaits() {
+    use futures::{pin_mut, poll, stream};
+    use futures::channel::oneshot;
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+    use futures::task::Poll;
+
+    let (tx, rx) = oneshot::channel::<i32>();
+    let s = stream! {
+        yield 1;
+        yield rx.await.unwrap();
+    };
+    pin_mut!(s);
+
+    block_on(async {
+        assert_eq!(s.next().await, Some(1));
+        assert_eq!(poll!(s.next()), Poll::Pending);
+        tx.send(2).unwrap();
+        assert_eq!(s.next().await, Some(2));
+        assert_eq!(s.next().await, None);
+    });
+}
+
+#[test]
+fn stream_macro_nested() {
+    use futures::{pin_mut, stream};
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+
+    let s = stream! {
+        for i in 0..3 {
+            let inner = stream! {
+                yield i;
+                yield i * 10;
+            };
+            pin_mut!(inner);
+            while let Some(x) = inner.next().await {
+                yield x;
+            }
+        }
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<_>>()), vec![0, 0, 1, 10, 2, 20]);
+}
+
+#[test]
+fn stream_macro_with_other_macros() {
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+
+    let s = futures::stream! {
+        for x in vec![1, 2] {
+            assert!(x > 0);
+            yield x;
+        }
+        // A stream macro's own `yield`s are fine in other macros.
+        let inners = vec![futures::stream! { yield 3; }.boxed()];
+        let mut inners = stream::iter(inners).flatten();
+        while let Some(x) = inners.next().await {
+            yield x;
+        }
+    };
+    assert_eq!(block_on(Box::pin(s).collect::<Vec<_>>()), vec![1, 2, 3]);
+}
+
+#[test]
+fn stream_macro_is_send() {
+    use futures::executor::block_on;
+    use futures::stream::{Stream, StreamExt};
+
+    fn assert_send<S: Stream + Send>(s: S) -> S {
+        s
+    }
+
+    let s = assert_send(futures::stream! {
+        yield String::from("a");
+        yield String::from("b");
+    });
+    assert_eq!(block_on(Box::pin(s).collect::<Vec<_>>()), vec!["a", "b"]);
+}
+
+#[test]
+fn try_stream_macro() {
+    use futures::{pin_mut, try_stream};
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+
+    #[derive(Debug, PartialEq)]
+    struct Error(String);
+
+    impl From<std::num::ParseIntError> for Error {
+        fn from(e: std::num::ParseIntError) -> Self {
+            Self(e.to_string())
+        }
+    }
+
+    let s = try_stream! {
+        for s in &["1", "x", "3"] {
+            yield s.parse::<i32>()?;
+        }
+    };
+    pin_mut!(s);
+    let items: Vec<Result<i32, Error>> = block_on(s.collect());
+    assert_eq!(items, vec![Ok(1), Err(Error("invalid digit found in string".into()))]);
+
+    let s = try_stream! {
+        yield 1;
+        if true {
+            return Err("failed");
+        }
+        yield 2;
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<_>>()), vec![Ok(1), Err("failed")]);
+}
+
+#[test]
+fn try_stream_macro_tail_expression() {
+    use futures::{pin_mut, try_stream};
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::stream::StreamExt;
+
+    fn check(x: Result<u32, &'static str>) -> Result<(), &'static str> {
+        x.map(drop)
+    }
+
+    let s = try_stream! {
+        yield 1u32;
+        check(Ok(1))?
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<Result<u32, &str>>>()), vec![Ok(1)]);
+
+    let s = try_stream! {
+        yield 1u32;
+        check(Err("failed"))?
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<Result<u32, &str>>>()), vec![Ok(1), Err("failed")]);
+
+    let s = try_stream! {
+        yield 1u32;
+        check(Ok(2))?;
+        future::ready(()).await
+    };
+    pin_mut!(s);
+    assert_eq!(block_on(s.collect::<Vec<Result<u32, &str>>>()), vec![Ok(1)]);
+}
+
+#[test]
+fn select_guard_disables_branch() {
+    use futures::executor::block_on;
+    use futures::future::{self, FutureExt};
+    use futures::select;
+
+    block_on(async {
+        let mut never_polled = future::poll_fn(|_| -> futures::task::Poll<i32> {
+            panic!("polled a future disabled by its guard")
+        }).fuse();
+        let enabled = false;
+        let res = select! {
+            x = never_polled, if enabled => x,
+            y = future::ready(2).fuse() => y,
+        };
+        assert_eq!(res, 2);
+
+        let res = select! {
+            x = future::ready(1).fuse(), if !enabled => x,
+            y = future::pending::<i32>().fuse(), if enabled => y,
+        };
+        assert_eq!(res, 1);
+    });
+}
+
+#[test]
+fn select_guards_with_complete_and_default() {
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::{select, select_biased};
+
+    block_on(async {
+        let mut a = future::ready(1);
+        let mut b = future::pending::<i32>();
+
+        let res = select! {
+            x = a, if false => x,
+            y = b, if false => y,
+            complete => 0,
+        };
+        assert_eq!(res, 0);
+
+        // A disabled branch counts as exhausted, so `complete` runs before
+        // `default`.
+        let res = select_biased! {
+            x = a, if false => x,
+            y = b, if false => y,
+            complete => 0,
+            default => 1,
+        };
+        assert_eq!(res, 0);
+
+        let res = select_biased! {
+            x = a, if false => x,
+            y = b => y,
+            default => 1,
+        };
+        assert_eq!(res, 1);
+
+        // Without `complete`, `default` runs when nothing can make progress.
+        let res = select! {
+            x = a, if false => x,
+            default => 2,
+        };
+        assert_eq!(res, 2);
+
+        assert_eq!(select! { x = a => x, complete => 0 }, 1);
+    });
+}
+
+#[test]
+fn select_guard_in_loop() {
+    use futures::executor::block_on;
+    use futures::select_biased;
+    use futures::stream::{self, StreamExt};
+
+    block_on(async {
+        let mut evens = stream::iter(vec![0, 2, 4]).fuse();
+        let mut odds = stream::iter(vec![1, 3, 5]).fuse();
+        let mut out = Vec::new();
+        let mut want_even = true;
+
+        loop {
+            select_biased! {
+                x = evens.next(), if want_even => match x {
+                    Some(x) => out.push(x),
+                    None => break,
+                },
+                x = odds.next(), if !want_even => match x {
+                    Some(x) => out.push(x),
+                    None => break,
+                },
+            }
+            want_even = !want_even;
+        }
+        assert_eq!(out, vec![0, 1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "all futures in select! were completed or disabled by their guards")]
+fn select_default_all_terminated_panics() {
+    use futures::executor::block_on;
+    use futures::future::{self, FutureExt};
+    use futures::select;
+
+    block_on(async {
+        let mut a = future::ready(1).fuse();
+        let mut b = future::ready(2).fuse();
+        assert_eq!((&mut a).await, 1);
+        assert_eq!((&mut b).await, 2);
+
+        // Every branch is enabled but terminated, so `default` doesn't run
+        // even though one branch has a guard.
+        select! {
+            x = a, if true => x,
+            y = b => y,
+            default => 0,
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "all futures in select! were completed or disabled by their guards")]
+fn select_all_disabled_without_complete_panics() {
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::select;
+
+    block_on(async {
+        let mut a = future::ready(1);
+        select! {
+            x = a, if false => x,
+        }
+    });
+}
+
+#[test]
+fn select_loop() {
+    use futures::channel::{mpsc, oneshot};
+    use futures::executor::block_on;
+    use futures::future::join;
+    use futures::select_loop;
+    use futures::sink::SinkExt;
+
+    let (mut tx1, rx1) = mpsc::channel::<i32>(1);
+    let (mut tx2, rx2) = mpsc::channel::<i32>(1);
+    let (done_tx, done_rx) = oneshot::channel::<i32>();
+
+    let producer = async move {
+        for i in 0..10 {
+            tx1.send(i).await.unwrap();
+            tx2.send(i * 100).await.unwrap();
+        }
+        done_tx.send(7).unwrap();
+    };
+    let consumer = async move {
+        let mut items = Vec::new();
+        let mut done = None;
+        let completed = select_loop! {
+            x in rx1 => items.push(x),
+            x in rx2 => items.push(x),
+            res = done_rx => done = res.ok(),
+            on_all_complete => true,
+        };
+        assert!(completed);
+        (items, done)
+    };
+
+    let ((), (mut items, done)) = block_on(join(producer, consumer));
+    items.sort();
+    let mut expected: Vec<_> = (0..10).chain((0..10).map(|i| i * 100)).collect();
+    expected.sort();
+    assert_eq!(items, expected);
+    assert_eq!(done, Some(7));
+}
+
+#[test]
+fn select_loop_break_and_continue() {
+    use futures::executor::block_on;
+    use futures::select_loop;
+    use futures::stream;
+
+    block_on(async {
+        let mut seen = Vec::new();
+        let found = select_loop! {
+            x in stream::iter(1..) => {
+                if x % 3 != 0 {
+                    continue;
+                }
+                seen.push(x);
+                if seen.len() == 3 {
+                    break x;
+                }
+            }
+            on_all_complete => unreachable!(),
+        };
+        assert_eq!(found, 9);
+        assert_eq!(seen, vec![3, 6, 9]);
+
+        // Without `on_all_complete`, the loop evaluates to `()`.
+        let mut count = 0;
+        select_loop! {
+            _ in stream::iter(0..5) => count += 1,
+        }
+        assert_eq!(count, 5);
+    });
+}
+
+#[test]
+fn select_loop_guards_and_borrowed_streams() {
+    use futures::executor::block_on;
+    use futures::select_loop;
+    use futures::stream::{self, StreamExt};
+
+    block_on(async {
+        let mut numbers = stream::iter(0..10);
+        let mut taken = Vec::new();
+        select_loop! {
+            x in &mut numbers, if taken.len() < 4 => taken.push(x),
+        }
+        assert_eq!(taken, vec![0, 1, 2, 3]);
+        // The stream was only borrowed, and the rest is still there.
+        assert_eq!(numbers.collect::<Vec<_>>().await, vec![4, 5, 6, 7, 8, 9]);
+    });
+}
+
+#[test]
+fn select_loop_non_unpin() {
+    use futures::executor::block_on;
+    use futures::{select_loop, stream};
+
+    block_on(async {
+        let mut total = 0;
+        select_loop! {
+            x in stream! { yield 1; yield 2; } => total += x,
+            y = async { 10 } => total += y,
+        }
+        assert_eq!(total, 13);
+    });
+}
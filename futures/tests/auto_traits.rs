@@ -248,6 +248,14 @@ pub mod future {
     assert_not_impl!(AndThen<PinnedFuture, UnpinFuture, PhantomPinned>: Unpin);
     assert_not_impl!(AndThen<UnpinFuture, PinnedFuture, PhantomPinned>: Unpin);
 
+    assert_impl!(CancellationToken: Send);
+    assert_impl!(CancellationToken: Sync);
+    assert_impl!(CancellationToken: Unpin);
+
+    assert_impl!(Cancelled<'_>: Send);
+    assert_impl!(Cancelled<'_>: Sync);
+    assert_impl!(Cancelled<'_>: Unpin);
+
     assert_impl!(CatchUnwind<SendFuture>: Send);
     assert_not_impl!(CatchUnwind<LocalFuture>: Send);
     assert_impl!(CatchUnwind<SyncFuture>: Sync);
@@ -255,6 +263,10 @@ pub mod future {
     assert_impl!(CatchUnwind<UnpinFuture>: Unpin);
     assert_not_impl!(CatchUnwind<PinnedFuture>: Unpin);
 
+    assert_impl!(DropGuard: Send);
+    assert_impl!(DropGuard: Sync);
+    assert_impl!(DropGuard: Unpin);
+
     assert_impl!(ErrInto<SendTryFuture, *const ()>: Send);
     assert_not_impl!(ErrInto<LocalTryFuture, ()>: Send);
     assert_impl!(ErrInto<SyncTryFuture, *const ()>: Sync);
@@ -1219,6 +1231,15 @@ pub mod stream {
     assert_not_impl!(FlatMap<PhantomPinned, (), ()>: Unpin);
     assert_not_impl!(FlatMap<(), PhantomPinned, ()>: Unpin);
 
+    assert_impl!(FlatMapUnordered<SendStream<()>, SendStream<()>, fn(()) -> SendStream<()>>: Send);
+    assert_not_impl!(FlatMapUnordered<LocalStream<()>, SendStream<()>, fn(()) -> SendStream<()>>: Send);
+    assert_not_impl!(FlatMapUnordered<SendStream<()>, LocalStream<()>, fn(()) -> LocalStream<()>>: Send);
+    assert_impl!(FlatMapUnordered<SyncStream<()>, SyncStream<()>, fn(()) -> SyncStream<()>>: Sync);
+    assert_not_impl!(FlatMapUnordered<LocalStream<()>, SyncStream<()>, fn(()) -> SyncStream<()>>: Sync);
+    assert_not_impl!(FlatMapUnordered<SyncStream<()>, LocalStream<()>, fn(()) -> LocalStream<()>>: Sync);
+    assert_impl!(FlatMapUnordered<UnpinStream<()>, UnpinStream<()>, fn(()) -> UnpinStream<()>>: Unpin);
+    assert_not_impl!(FlatMapUnordered<PinnedStream<()>, UnpinStream<()>, fn(()) -> UnpinStream<()>>: Unpin);
+
     assert_impl!(Flatten<SendStream<()>>: Send);
     assert_not_impl!(Flatten<SendStream>: Send);
     assert_not_impl!(Flatten<SendStream>: Send);
@@ -1229,6 +1250,15 @@ pub mod stream {
     assert_not_impl!(Flatten<UnpinStream>: Unpin);
     assert_not_impl!(Flatten<PinnedStream>: Unpin);
 
+    assert_impl!(FlattenUnordered<SendStream<SendStream<()>>>: Send);
+    assert_not_impl!(FlattenUnordered<SendStream<LocalStream<()>>>: Send);
+    assert_not_impl!(FlattenUnordered<LocalStream<SendStream<()>>>: Send);
+    assert_impl!(FlattenUnordered<SyncStream<SyncStream<()>>>: Sync);
+    assert_not_impl!(FlattenUnordered<SyncStream<LocalStream<()>>>: Sync);
+    assert_not_impl!(FlattenUnordered<LocalStream<SyncStream<()>>>: Sync);
+    assert_impl!(FlattenUnordered<UnpinStream<UnpinStream<()>>>: Unpin);
+    assert_not_impl!(FlattenUnordered<PinnedStream<UnpinStream<()>>>: Unpin);
+
     assert_impl!(Fold<(), (), (), ()>: Send);
     assert_not_impl!(Fold<*const (), (), (), ()>: Send);
     assert_not_impl!(Fold<(), *const (), (), ()>: Send);
@@ -1266,6 +1296,14 @@ pub mod stream {
     assert_impl!(ForEachConcurrent<(), PhantomPinned, PhantomPinned>: Unpin);
     assert_not_impl!(ForEachConcurrent<PhantomPinned, (), ()>: Unpin);
 
+    assert_impl!(Fork<SendStream<()>>: Send);
+    assert_not_impl!(Fork<SendStream>: Send);
+    assert_not_impl!(Fork<LocalStream<()>>: Send);
+    assert_impl!(Fork<SendStream<()>>: Sync);
+    assert_not_impl!(Fork<SendStream>: Sync);
+    assert_not_impl!(Fork<SyncStream<()>>: Sync);
+    assert_impl!(Fork<PinnedStream>: Unpin);
+
     assert_impl!(Forward<SendTryStream<()>, ()>: Send);
     assert_not_impl!(Forward<SendTryStream, ()>: Send);
     assert_not_impl!(Forward<SendTryStream<()>, *const ()>: Send);
@@ -1293,6 +1331,14 @@ pub mod stream {
     assert_not_impl!(FuturesOrdered<LocalFuture<()>>: Sync);
     assert_impl!(FuturesOrdered<PinnedFuture>: Unpin);
 
+    assert_impl!(FuturesMap<(), ()>: Send);
+    assert_not_impl!(FuturesMap<*const (), ()>: Send);
+    assert_not_impl!(FuturesMap<(), *const ()>: Send);
+    assert_impl!(FuturesMap<(), ()>: Sync);
+    assert_not_impl!(FuturesMap<*const (), ()>: Sync);
+    assert_not_impl!(FuturesMap<(), *const ()>: Sync);
+    assert_impl!(FuturesMap<PhantomPinned, PhantomPinned>: Unpin);
+
     assert_impl!(FuturesUnordered<()>: Send);
     assert_not_impl!(FuturesUnordered<*const ()>: Send);
     assert_impl!(FuturesUnordered<()>: Sync);
@@ -1484,6 +1530,18 @@ pub mod stream {
     assert_not_impl!(Select<PhantomPinned, ()>: Unpin);
     assert_not_impl!(Select<(), PhantomPinned>: Unpin);
 
+    assert_impl!(SelectWithStrategy<(), (), (), ()>: Send);
+    assert_not_impl!(SelectWithStrategy<*const (), (), (), ()>: Send);
+    assert_not_impl!(SelectWithStrategy<(), *const (), (), ()>: Send);
+    assert_not_impl!(SelectWithStrategy<(), (), *const (), ()>: Send);
+    assert_not_impl!(SelectWithStrategy<(), (), (), *const ()>: Send);
+    assert_impl!(SelectWithStrategy<(), (), (), ()>: Sync);
+    assert_not_impl!(SelectWithStrategy<*const (), (), (), ()>: Sync);
+    assert_not_impl!(SelectWithStrategy<(), *const (), (), ()>: Sync);
+    assert_impl!(SelectWithStrategy<(), (), PhantomPinned, PhantomPinned>: Unpin);
+    assert_not_impl!(SelectWithStrategy<PhantomPinned, (), (), ()>: Unpin);
+    assert_not_impl!(SelectWithStrategy<(), PhantomPinned, (), ()>: Unpin);
+
     assert_impl!(SelectAll<()>: Send);
     assert_not_impl!(SelectAll<*const ()>: Send);
     assert_impl!(SelectAll<()>: Sync);
@@ -1496,6 +1554,14 @@ pub mod stream {
     assert_not_impl!(SelectNextSome<'_, *const ()>: Sync);
     assert_impl!(SelectNextSome<'_, PhantomPinned>: Unpin);
 
+    assert_impl!(SharedStream<SendStream<()>>: Send);
+    assert_not_impl!(SharedStream<SendStream>: Send);
+    assert_not_impl!(SharedStream<LocalStream<()>>: Send);
+    assert_impl!(SharedStream<SendStream<()>>: Sync);
+    assert_not_impl!(SharedStream<SendStream>: Sync);
+    assert_not_impl!(SharedStream<SyncStream<()>>: Sync);
+    assert_impl!(SharedStream<PinnedStream>: Unpin);
+
     assert_impl!(Skip<()>: Send);
     assert_not_impl!(Skip<*const ()>: Send);
     assert_impl!(Skip<()>: Sync);
@@ -1538,6 +1604,14 @@ pub mod stream {
     assert_impl!(StreamFuture<()>: Unpin);
     assert_not_impl!(StreamFuture<PhantomPinned>: Unpin);
 
+    assert_impl!(StreamMap<(), ()>: Send);
+    assert_not_impl!(StreamMap<*const (), ()>: Send);
+    assert_not_impl!(StreamMap<(), *const ()>: Send);
+    assert_impl!(StreamMap<(), ()>: Sync);
+    assert_not_impl!(StreamMap<*const (), ()>: Sync);
+    assert_not_impl!(StreamMap<(), *const ()>: Sync);
+    assert_impl!(StreamMap<PhantomPinned, PhantomPinned>: Unpin);
+
     assert_impl!(Take<()>: Send);
     assert_not_impl!(Take<*const ()>: Send);
     assert_impl!(Take<()>: Sync);
@@ -1659,6 +1733,15 @@ pub mod stream {
     assert_not_impl!(TryFlatten<UnpinTryStream>: Unpin);
     assert_not_impl!(TryFlatten<PinnedTryStream>: Unpin);
 
+    assert_impl!(TryFlattenUnordered<SendTryStream<SendTryStream<(), ()>, ()>>: Send);
+    assert_not_impl!(TryFlattenUnordered<SendTryStream<LocalTryStream<(), ()>, ()>>: Send);
+    assert_not_impl!(TryFlattenUnordered<LocalTryStream<SendTryStream<(), ()>, ()>>: Send);
+    assert_impl!(TryFlattenUnordered<SyncTryStream<SyncTryStream<(), ()>, ()>>: Sync);
+    assert_not_impl!(TryFlattenUnordered<SyncTryStream<LocalTryStream<(), ()>, ()>>: Sync);
+    assert_not_impl!(TryFlattenUnordered<LocalTryStream<SyncTryStream<(), ()>, ()>>: Sync);
+    assert_impl!(TryFlattenUnordered<UnpinTryStream<UnpinTryStream<(), ()>, ()>>: Unpin);
+    assert_not_impl!(TryFlattenUnordered<PinnedTryStream<UnpinTryStream<(), ()>, ()>>: Unpin);
+
     assert_impl!(TryFold<(), (), (), ()>: Send);
     assert_not_impl!(TryFold<*const (), (), (), ()>: Send);
     assert_not_impl!(TryFold<(), *const (), (), ()>: Send);
@@ -0,0 +1,188 @@
+#[test]
+fn cancel_wakes_waiting_task() {
+    use futures::future::{CancellationToken, FusedFuture, FutureExt};
+    use futures::task::{Context, Poll};
+    use futures_test::task::new_count_waker;
+
+    let token = CancellationToken::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut cancelled = token.cancelled();
+    assert_eq!(cancelled.poll_unpin(&mut cx), Poll::Pending);
+    assert_eq!(cancelled.poll_unpin(&mut cx), Poll::Pending);
+    assert!(!cancelled.is_terminated());
+    assert_eq!(counter, 0);
+
+    token.clone().cancel();
+    assert_eq!(counter, 1);
+    assert!(token.is_cancelled());
+    assert!(!cancelled.is_terminated());
+    assert_eq!(cancelled.poll_unpin(&mut cx), Poll::Ready(()));
+    assert!(cancelled.is_terminated());
+
+    // Cancelling again doesn't wake anything.
+    token.cancel();
+    assert_eq!(counter, 1);
+}
+
+#[test]
+fn select_after_cancel() {
+    use futures::executor::block_on;
+    use futures::future::CancellationToken;
+    use futures::select;
+
+    let token = CancellationToken::new();
+    token.cancel();
+
+    // The branch still runs once, even though the token was already cancelled.
+    let mut cancelled = token.cancelled();
+    let res = block_on(async {
+        let mut res = Vec::new();
+        loop {
+            select! {
+                _ = cancelled => res.push("cancelled"),
+                complete => break,
+            }
+        }
+        res
+    });
+    assert_eq!(res, vec!["cancelled"]);
+}
+
+#[test]
+fn cancel_propagates_to_children_only() {
+    use futures::future::CancellationToken;
+
+    let parent = CancellationToken::new();
+    let child = parent.child_token();
+    let grandchild = child.child_token();
+    let sibling = parent.child_token();
+
+    child.cancel();
+    assert!(child.is_cancelled());
+    assert!(grandchild.is_cancelled());
+    assert!(!parent.is_cancelled());
+    assert!(!sibling.is_cancelled());
+
+    parent.cancel();
+    assert!(sibling.is_cancelled());
+    assert!(parent.child_token().is_cancelled());
+}
+
+#[test]
+fn dropped_parent_still_propagates() {
+    use futures::future::CancellationToken;
+
+    let root = CancellationToken::new();
+    let middle = root.child_token();
+    let leaf = middle.child_token();
+    drop(middle);
+
+    root.cancel();
+    assert!(leaf.is_cancelled());
+}
+
+#[test]
+fn wide_tree_wakes_each_task_once() {
+    use futures::future::{CancellationToken, FutureExt};
+    use futures::task::{Context, Poll};
+    use futures_test::task::new_count_waker;
+
+    let root = CancellationToken::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let children = (0..10_000).map(|_| root.child_token()).collect::<Vec<_>>();
+    let mut futures = children.iter().map(|child| child.cancelled()).collect::<Vec<_>>();
+    for fut in &mut futures {
+        assert_eq!(fut.poll_unpin(&mut cx), Poll::Pending);
+    }
+    // Children dropped before the cancellation are not woken. The unit tests
+    // of `CancellationToken` check that they leave the tree right away.
+    for _ in 0..10_000 {
+        let child = root.child_token();
+        assert_eq!(child.cancelled().poll_unpin(&mut cx), Poll::Pending);
+    }
+
+    root.cancel();
+    assert_eq!(counter, 10_000);
+    for fut in &mut futures {
+        assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready(()));
+    }
+}
+
+#[test]
+fn deep_tree() {
+    use futures::future::CancellationToken;
+
+    let root = CancellationToken::new();
+    let mut leaf = root.child_token();
+    for _ in 0..100_000 {
+        leaf = leaf.child_token();
+    }
+    root.cancel();
+    assert!(leaf.is_cancelled());
+
+    // Dropping the chain from the leaf doesn't overflow the stack either.
+    let mut leaf = CancellationToken::new();
+    for _ in 0..100_000 {
+        leaf = leaf.child_token();
+    }
+    drop(leaf);
+}
+
+#[test]
+fn drop_guard() {
+    use futures::future::CancellationToken;
+
+    let token = CancellationToken::new();
+    let guard = token.clone().drop_guard();
+    let token2 = guard.disarm();
+    assert!(!token.is_cancelled());
+
+    drop(token2.drop_guard());
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn abortable_tasks() {
+    use futures::executor::block_on;
+    use futures::future::{self, Aborted, CancellationToken, FutureExt};
+    use futures::stream::{self, StreamExt};
+    use futures::task::{Context, Poll};
+    use futures_test::task::new_count_waker;
+
+    let token = CancellationToken::new();
+    let (waker, counter) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut fut = token.child_token().abortable(future::pending::<()>());
+    let mut stream = token.abortable(stream::pending::<()>());
+    assert_eq!(fut.poll_unpin(&mut cx), Poll::Pending);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    for _ in 0..100 {
+        drop(token.abortable(future::ready(())));
+    }
+
+    token.cancel();
+    assert_eq!(counter, 2);
+    assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready(Err(Aborted)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+
+    // Tasks created after cancellation are aborted right away.
+    assert_eq!(block_on(token.abortable(future::ready(1))), Err(Aborted));
+}
+
+#[test]
+fn cancel_from_another_thread() {
+    use futures::executor::block_on;
+    use futures::future::CancellationToken;
+    use std::thread;
+
+    let token = CancellationToken::new();
+    let child = token.child_token();
+    let handle = thread::spawn(move || block_on(child.cancelled()));
+    token.cancel();
+    handle.join().unwrap();
+}
@@ -0,0 +1,155 @@
+#[test]
+fn is_terminated() {
+    use futures::future;
+    use futures::stream::{FusedStream, FuturesMap, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let mut tasks = FuturesMap::new();
+
+    assert!(!tasks.is_terminated());
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(tasks.is_terminated());
+
+    tasks.insert("a", future::ready(1));
+
+    assert!(!tasks.is_empty());
+    assert_eq!(tasks.len(), 1);
+
+    assert!(!tasks.is_terminated());
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(Some(("a", 1))));
+    assert!(!tasks.contains_key(&"a"));
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(tasks.is_terminated());
+}
+
+#[test]
+fn yields_keys() {
+    use futures::channel::oneshot;
+    use futures::executor::block_on_stream;
+    use futures::stream::FuturesMap;
+
+    let (a_tx, a_rx) = oneshot::channel::<i32>();
+    let (b_tx, b_rx) = oneshot::channel::<i32>();
+    let (c_tx, c_rx) = oneshot::channel::<i32>();
+
+    let tasks = vec![(1, a_rx), (2, b_rx), (3, c_rx)].into_iter().collect::<FuturesMap<_, _>>();
+    let mut iter = block_on_stream(tasks);
+
+    b_tx.send(99).unwrap();
+    assert_eq!(Some((2, Ok(99))), iter.next());
+
+    a_tx.send(33).unwrap();
+    c_tx.send(33).unwrap();
+    let mut rest = vec![iter.next().unwrap(), iter.next().unwrap()];
+    rest.sort_by_key(|(key, _)| *key);
+    assert_eq!(rest, vec![(1, Ok(33)), (3, Ok(33))]);
+    assert_eq!(None, iter.next());
+}
+
+#[test]
+fn remove_returns_future() {
+    use futures::channel::oneshot;
+    use futures::executor::block_on;
+    use futures::stream::{FuturesMap, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (a_tx, a_rx) = oneshot::channel::<i32>();
+    let (b_tx, b_rx) = oneshot::channel::<i32>();
+
+    let mut tasks = FuturesMap::new();
+    tasks.insert("a", a_rx);
+    tasks.insert("b", b_rx);
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Pending);
+
+    // Removing a future that has already been woken must not leave it behind
+    // in the ready to run queue.
+    a_tx.send(1).unwrap();
+    let a_rx = tasks.remove(&"a").unwrap();
+    assert_eq!(block_on(a_rx), Ok(1));
+    assert!(tasks.remove(&"a").is_none());
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Pending);
+
+    b_tx.send(2).unwrap();
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(Some(("b", Ok(2)))));
+    assert!(tasks.remove(&"b").is_none());
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn cancel_drops_future() {
+    use futures::future::{self, FutureExt};
+    use futures::stream::{FuturesMap, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use std::sync::Arc;
+
+    let mut cx = noop_context();
+    let token = Arc::new(());
+
+    let mut tasks = FuturesMap::new();
+    let held = token.clone();
+    tasks.insert(1, async move {
+        future::pending::<()>().await;
+        drop(held);
+    }.boxed());
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(Arc::strong_count(&token), 2);
+
+    assert!(tasks.cancel(&1));
+    assert_eq!(Arc::strong_count(&token), 1);
+    assert!(!tasks.cancel(&1));
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn insert_replaces_future() {
+    use futures::executor::block_on_stream;
+    use futures::future;
+    use futures::stream::FuturesMap;
+
+    let mut tasks = FuturesMap::new();
+    assert!(!tasks.insert("a", future::ready(1)));
+    assert!(tasks.insert("a", future::ready(2)));
+    assert_eq!(tasks.len(), 1);
+
+    assert_eq!(block_on_stream(tasks).collect::<Vec<_>>(), vec![("a", 2)]);
+}
+
+#[test]
+fn panicking_future_can_be_removed() {
+    use futures::future::{self, FutureExt};
+    use futures::stream::{FuturesMap, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use std::panic::{self, AssertUnwindSafe};
+
+    let mut cx = noop_context();
+    let mut tasks = FuturesMap::new();
+    tasks.insert(1, future::lazy(|_| panic!("boom")).boxed());
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| tasks.poll_next_unpin(&mut cx)));
+    assert!(res.is_err());
+    assert!(!tasks.contains_key(&1));
+    assert!(!tasks.cancel(&1));
+    assert_eq!(tasks.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn iter() {
+    use futures::future;
+    use futures::stream::FuturesMap;
+
+    let mut tasks = FuturesMap::new();
+    tasks.insert(1, future::ready(1));
+    tasks.insert(2, future::ready(2));
+
+    let mut keys = tasks.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec![1, 2]);
+    assert_eq!(tasks.iter_mut().count(), 2);
+}
@@ -0,0 +1,93 @@
+use futures::executor::{block_on, LocalPool};
+use futures::io::{self, AsyncBufReadExt, AsyncWriteExt};
+use futures::stream::TryStreamExt;
+use futures::task::LocalSpawnExt;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const CHILD: &str = "FUTURES_STDIO_TEST_CHILD";
+
+// Runs this test again in a child process, with `input` as its standard
+// input, and returns what it wrote to its standard output.
+fn run_child(test: &str, input: &[u8]) -> String {
+    let mut child = Command::new(env::current_exe().unwrap())
+        .args(&[test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn stdin_lines_shared_between_tasks() {
+    if env::var_os(CHILD).is_some() {
+        let mut pool = LocalPool::new();
+        for task in 0..2 {
+            let mut lines = io::stdin().lines();
+            let mut stdout = io::stdout();
+            pool.spawner()
+                .spawn_local(async move {
+                    while let Some(line) = lines.try_next().await.unwrap() {
+                        let echo = format!("echo {}: {}\n", task, line);
+                        stdout.write_all(echo.as_bytes()).await.unwrap();
+                    }
+                    stdout.flush().await.unwrap();
+                })
+                .unwrap();
+        }
+        pool.run();
+        return;
+    }
+
+    let input: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+    let output = run_child("stdin_lines_shared_between_tasks", input.as_bytes());
+
+    let mut echoed: Vec<usize> = output
+        .lines()
+        // The test harness may have printed something before the first echo
+        // on the same line.
+        .filter_map(|line| line.find("echo ").map(|i| &line[i..]))
+        .map(|line| {
+            let line = line.splitn(2, ": ").nth(1).unwrap();
+            assert!(line.starts_with("line "), "split line: {:?}", line);
+            line["line ".len()..].parse().unwrap()
+        })
+        .collect();
+    echoed.sort();
+    assert_eq!(echoed, (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn stdout_is_line_buffered() {
+    if env::var_os(CHILD).is_some() {
+        block_on(async {
+            let mut stdout = io::stdout();
+            stdout.write_all(b"first\n").await.unwrap();
+            // Without a flush, the completed line above is still written
+            // before the process exits.
+            stdout.write_all(b"second").await.unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            std::process::exit(0);
+        });
+    }
+
+    let output = run_child("stdout_is_line_buffered", b"");
+    assert!(output.contains("first\n"));
+}
+
+#[test]
+fn stderr_and_clones() {
+    block_on(async {
+        let mut stderr = io::stderr();
+        let mut clone = stderr.clone();
+        stderr.write_all(b"").await.unwrap();
+        clone.flush().await.unwrap();
+        stderr.close().await.unwrap();
+    });
+}
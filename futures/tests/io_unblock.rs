@@ -0,0 +1,210 @@
+use futures::executor::block_on;
+use futures::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom, Unblock};
+use std::io::{self, Cursor, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A writer whose output can be inspected from the test, and which records
+/// when it was dropped.
+#[derive(Clone, Default)]
+struct Shared {
+    data: Arc<Mutex<Vec<u8>>>,
+    flushes: Arc<AtomicUsize>,
+    dropped: Arc<AtomicBool>,
+}
+
+struct SharedWriter(Shared);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.data.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flushes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl Drop for SharedWriter {
+    fn drop(&mut self) {
+        self.0.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+fn wait_for(mut cond: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !cond() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn read_lines() {
+    let input: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+    let mut lines = Unblock::with_capacity(64, Cursor::new(input)).lines();
+
+    block_on(async {
+        use futures::stream::TryStreamExt;
+
+        let mut count = 0;
+        while let Some(line) = lines.try_next().await.unwrap() {
+            assert_eq!(line, format!("line {}", count));
+            count += 1;
+        }
+        assert_eq!(count, 1000);
+    });
+}
+
+#[test]
+fn write_and_flush() {
+    let shared = Shared::default();
+    let mut writer = Unblock::with_capacity(16, SharedWriter(shared.clone()));
+
+    block_on(async {
+        for _ in 0..100 {
+            writer.write_all(b"0123456789").await.unwrap();
+        }
+        writer.flush().await.unwrap();
+    });
+
+    assert_eq!(shared.data.lock().unwrap().len(), 1000);
+    assert_eq!(shared.flushes.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn drop_flushes_and_stops_thread() {
+    let shared = Shared::default();
+    let mut writer = Unblock::new(SharedWriter(shared.clone()));
+
+    block_on(writer.write_all(b"unflushed")).unwrap();
+    drop(writer);
+
+    wait_for(|| shared.dropped.load(Ordering::SeqCst));
+    assert_eq!(&*shared.data.lock().unwrap(), b"unflushed");
+    assert_eq!(shared.flushes.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn unused_does_not_start_thread() {
+    let shared = Shared::default();
+    drop(Unblock::new(SharedWriter(shared.clone())));
+    assert!(shared.dropped.load(Ordering::SeqCst));
+}
+
+#[test]
+fn read_ahead_is_bounded() {
+    struct Endless(Arc<AtomicUsize>);
+
+    impl Read for Endless {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.fetch_add(buf.len(), Ordering::SeqCst);
+            Ok(buf.len())
+        }
+    }
+
+    let total = Arc::new(AtomicUsize::new(0));
+    let mut reader = Unblock::with_capacity(32, Endless(total.clone()));
+
+    let mut byte = [0];
+    block_on(reader.read_exact(&mut byte)).unwrap();
+    wait_for(|| total.load(Ordering::SeqCst) >= 32);
+    thread::sleep(Duration::from_millis(20));
+    // What was handed to the reader, plus a full read-ahead buffer.
+    assert!(total.load(Ordering::SeqCst) <= 64);
+}
+
+#[test]
+fn errors_are_reported() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "read failed"))
+        }
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "write failed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut io = Unblock::new(Failing);
+    block_on(async {
+        let mut buf = [0; 4];
+        assert_eq!(io.read(&mut buf).await.unwrap_err().to_string(), "read failed");
+
+        // The write is buffered, so the error shows up when flushing.
+        io.write_all(b"data").await.unwrap();
+        assert_eq!(io.flush().await.unwrap_err().to_string(), "write failed");
+    });
+}
+
+#[test]
+fn seek_accounts_for_read_ahead() {
+    let mut io = Unblock::with_capacity(8, Cursor::new(Vec::new()));
+
+    block_on(async {
+        io.write_all(b"hello world, hello futures").await.unwrap();
+        assert_eq!(io.seek(SeekFrom::Start(0)).await.unwrap(), 0);
+
+        let mut hello = [0; 5];
+        io.read_exact(&mut hello).await.unwrap();
+        assert_eq!(&hello, b"hello");
+        // More than that has been read ahead on the helper thread.
+        assert_eq!(io.seek(SeekFrom::Current(0)).await.unwrap(), 5);
+
+        assert_eq!(io.seek(SeekFrom::Current(8)).await.unwrap(), 13);
+        let mut rest = String::new();
+        io.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "hello futures");
+
+        assert_eq!(io.seek(SeekFrom::End(-7)).await.unwrap(), 19);
+        io.write_all(b"streams").await.unwrap();
+        io.seek(SeekFrom::Start(0)).await.unwrap();
+        let mut all = String::new();
+        io.read_to_string(&mut all).await.unwrap();
+        assert_eq!(all, "hello world, hello streams");
+    });
+}
+
+#[test]
+fn write_after_seeking_over_read_ahead() {
+    let mut io = Unblock::with_capacity(8, Cursor::new(b"hello world".to_vec()));
+
+    block_on(async {
+        let mut hello = [0; 6];
+        io.read_exact(&mut hello).await.unwrap();
+        assert_eq!(io.seek(SeekFrom::Current(0)).await.unwrap(), 6);
+        io.write_all(b"there").await.unwrap();
+
+        io.seek(SeekFrom::Start(0)).await.unwrap();
+        let mut all = String::new();
+        io.read_to_string(&mut all).await.unwrap();
+        assert_eq!(all, "hello there");
+    });
+}
+
+#[test]
+fn abandoned_seek_result_is_not_reused() {
+    use futures::future::FutureExt;
+    use futures_test::task::noop_context;
+
+    let mut io = Unblock::new(Cursor::new(b"hello world".to_vec()));
+    let mut cx = noop_context();
+    assert!(io.seek(SeekFrom::Start(3)).poll_unpin(&mut cx).is_pending());
+
+    assert_eq!(block_on(io.seek(SeekFrom::Start(6))).unwrap(), 6);
+    let mut world = String::new();
+    block_on(io.read_to_string(&mut world)).unwrap();
+    assert_eq!(world, "world");
+}
@@ -1,8 +1,9 @@
 // normal reexport
-pub use futures03::{join, try_join, select, select_biased};
+pub use futures03::{join, try_join, select, select_biased, select_loop, stream, try_stream};
 
 // reexport + rename
 pub use futures03::{
     join as join2, try_join as try_join2,
     select as select2, select_biased as select_biased2,
+    select_loop as select_loop2, stream as stream2, try_stream as try_stream2,
 };
@@ -66,4 +66,30 @@ fn main() {
         };
     });
 
+    // select_loop! macro
+    block_on(async {
+        futures03::select_loop! {
+            _ = future::ready(()) => {},
+        }
+        macro_reexport::select_loop! {
+            _ = future::ready(()) => {},
+        }
+        macro_reexport::select_loop2! {
+            _ = future::ready(()) => {},
+        }
+    });
+
+    // stream! macro
+    block_on(async {
+        let _ = futures03::stream! { yield 1; };
+        let _ = macro_reexport::stream! { yield 1; };
+        let _ = macro_reexport::stream2! { yield 1; };
+    });
+
+    // try_stream! macro
+    block_on(async {
+        let _ = futures03::try_stream! { yield 1; return Err(()); };
+        let _ = macro_reexport::try_stream! { yield 1; return Err(()); };
+        let _ = macro_reexport::try_stream2! { yield 1; return Err(()); };
+    });
 }
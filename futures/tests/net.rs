@@ -0,0 +1,204 @@
+#![cfg(target_os = "linux")]
+
+use std::net::SocketAddr;
+
+fn localhost() -> SocketAddr {
+    "127.0.0.1:0".parse().unwrap()
+}
+
+#[test]
+fn tcp_echo() {
+    use futures::executor::{block_on, TcpListener, TcpStream};
+    use futures::future::try_join;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+    block_on(async {
+        let listener = TcpListener::bind(localhost()).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = async {
+            let (stream, peer) = listener.accept().await?;
+            assert_eq!(peer.ip(), addr.ip());
+            let (mut reader, mut writer) = (&stream, &stream);
+            futures::io::copy(&mut reader, &mut writer).await
+        };
+        let client = async {
+            let mut stream = TcpStream::connect(addr).await?;
+            assert_eq!(stream.peer_addr()?, addr);
+            stream.write_all(b"hello world").await?;
+            stream.close().await?;
+            let mut echoed = Vec::new();
+            stream.read_to_end(&mut echoed).await?;
+            Ok(echoed)
+        };
+
+        let (copied, echoed) = try_join(server, client).await.unwrap();
+        assert_eq!(copied, 11);
+        assert_eq!(echoed, b"hello world");
+    });
+}
+
+#[test]
+fn tcp_large_transfer_on_thread_pool() {
+    use futures::channel::oneshot;
+    use futures::executor::{block_on, TcpListener, TcpStream, ThreadPool};
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Much larger than the socket buffers, so that both sides have to wait
+    // for readiness many times.
+    const LEN: usize = 8 * 1024 * 1024;
+
+    let pool = ThreadPool::new().unwrap();
+    let listener = TcpListener::bind(localhost()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, rx) = oneshot::channel();
+    pool.spawn_ok(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        tx.send(received).unwrap();
+    });
+
+    let data: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+    let sent = data.clone();
+    pool.spawn_ok(async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(&sent).await.unwrap();
+        stream.close().await.unwrap();
+    });
+
+    assert!(block_on(rx).unwrap() == data);
+}
+
+#[test]
+fn tcp_incoming() {
+    use futures::executor::{block_on, TcpListener, TcpStream};
+    use futures::io::AsyncWriteExt;
+    use futures::stream::StreamExt;
+    use std::thread;
+
+    let listener = TcpListener::bind(localhost()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let clients = thread::spawn(move || {
+        for i in 0..3u8 {
+            block_on(async {
+                let mut stream = TcpStream::connect(addr).await.unwrap();
+                stream.write_all(&[i]).await.unwrap();
+            });
+        }
+    });
+
+    let accepted = block_on(listener.incoming().take(3).collect::<Vec<_>>());
+    assert_eq!(accepted.len(), 3);
+    assert!(accepted.iter().all(|stream| stream.is_ok()));
+    clients.join().unwrap();
+}
+
+#[test]
+fn tcp_connect_refused() {
+    use futures::executor::{block_on, TcpListener, TcpStream};
+    use std::io;
+
+    // Find a port nobody listens on.
+    let addr = TcpListener::bind(localhost()).unwrap().local_addr().unwrap();
+
+    let err = block_on(TcpStream::connect(addr)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+fn udp_send_recv() {
+    use futures::executor::{block_on, UdpSocket};
+
+    block_on(async {
+        let a = UdpSocket::bind(localhost()).unwrap();
+        let b = UdpSocket::bind(localhost()).unwrap();
+        let (a_addr, b_addr) = (a.local_addr().unwrap(), b.local_addr().unwrap());
+
+        let mut buf = [0; 32];
+        assert_eq!(a.send_to(b"ping", b_addr).await.unwrap(), 4);
+        assert_eq!(b.recv_from(&mut buf).await.unwrap(), (4, a_addr));
+        assert_eq!(&buf[..4], b"ping");
+
+        a.connect(b_addr).unwrap();
+        b.connect(a_addr).unwrap();
+        assert_eq!(b.send(b"pong").await.unwrap(), 4);
+        assert_eq!(a.recv(&mut buf).await.unwrap(), 4);
+        assert_eq!(&buf[..4], b"pong");
+    });
+}
+
+#[test]
+fn udp_recv_waits_for_datagram() {
+    use futures::executor::{block_on, UdpSocket};
+    use std::net;
+    use std::thread;
+    use std::time::Duration;
+
+    let socket = UdpSocket::bind(localhost()).unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        net::UdpSocket::bind(localhost()).unwrap().send_to(b"late", addr).unwrap();
+    });
+
+    let mut buf = [0; 8];
+    assert_eq!(block_on(socket.recv_from(&mut buf)).unwrap().0, 4);
+    assert_eq!(&buf[..4], b"late");
+    sender.join().unwrap();
+}
+
+#[test]
+fn unix_stream_pair() {
+    use futures::executor::{block_on, UnixStream};
+    use futures::future::join;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    let data = vec![7u8; 1024 * 1024];
+
+    let ((), read) = block_on(join(
+        async {
+            a.write_all(&data).await.unwrap();
+            a.close().await.unwrap();
+        },
+        async {
+            let mut read = Vec::new();
+            b.read_to_end(&mut read).await.unwrap();
+            read
+        },
+    ));
+    assert!(read == data);
+}
+
+#[test]
+fn unix_stream_connect() {
+    use futures::executor::{block_on, UnixStream};
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use std::io::Write;
+    use std::os::unix::net::UnixListener;
+    use std::{env, fs, process, thread};
+
+    let path = env::temp_dir().join(format!("futures-net-test-{}.sock", process::id()));
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.write_all(b"welcome").unwrap();
+    });
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    let mut greeting = String::new();
+    block_on(async {
+        stream.read_to_string(&mut greeting).await.unwrap();
+        stream.close().await.unwrap();
+    });
+    assert_eq!(greeting, "welcome");
+
+    server.join().unwrap();
+    fs::remove_file(&path).unwrap();
+}
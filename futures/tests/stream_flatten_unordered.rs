@@ -0,0 +1,143 @@
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// A stream that counts how many times it is polled.
+struct CountPolls<St> {
+    stream: St,
+    polls: Arc<AtomicUsize>,
+}
+
+impl<St: Stream + Unpin> Stream for CountPolls<St> {
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+#[test]
+fn interleaves_ready_streams() {
+    use futures::channel::mpsc;
+    use futures::stream::{self, StreamExt};
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (tx1, rx1) = mpsc::unbounded::<i32>();
+    let (tx2, rx2) = mpsc::unbounded::<i32>();
+    let mut s = stream::iter(vec![rx1, rx2]).flatten_unordered(None);
+
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+    tx2.unbounded_send(2).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    tx1.unbounded_send(1).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    tx2.unbounded_send(3).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+
+    drop(tx1);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+    drop(tx2);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn respects_limit() {
+    use futures::channel::mpsc;
+    use futures::stream::{FusedStream, StreamExt};
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (outer_tx, outer_rx) = mpsc::unbounded();
+    let (tx1, rx1) = mpsc::unbounded::<i32>();
+    let (tx2, rx2) = mpsc::unbounded::<i32>();
+    let (tx3, rx3) = mpsc::unbounded::<i32>();
+    outer_tx.unbounded_send(rx1).unwrap();
+    outer_tx.unbounded_send(rx2).unwrap();
+    outer_tx.unbounded_send(rx3).unwrap();
+    drop(outer_tx);
+
+    let mut s = outer_rx.flatten_unordered(2);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+
+    // The third stream has not been pulled in yet.
+    tx3.unbounded_send(3).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+
+    // Once one of the first two ends, the third takes its place.
+    drop(tx1);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+
+    drop((tx2, tx3));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(s.is_terminated());
+}
+
+#[test]
+fn polls_only_woken_streams() {
+    use futures::channel::mpsc;
+    use futures::stream::{self, StreamExt};
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let polls = Arc::new(AtomicUsize::new(0));
+    let (busy_tx, busy_rx) = mpsc::unbounded::<i32>();
+    let mut idle_txs = Vec::new();
+    let mut streams = vec![CountPolls { stream: busy_rx, polls: Arc::new(AtomicUsize::new(0)) }];
+    for _ in 0..10 {
+        let (tx, rx) = mpsc::unbounded::<i32>();
+        idle_txs.push(tx);
+        streams.push(CountPolls { stream: rx, polls: polls.clone() });
+    }
+
+    let mut s = stream::iter(streams).flatten_unordered(None);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(polls.load(Ordering::SeqCst), 10);
+
+    for i in 0..100 {
+        busy_tx.unbounded_send(i).unwrap();
+        assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(i)));
+    }
+    assert_eq!(polls.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn flat_map_unordered() {
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+
+    let s = stream::iter(1..=4).flat_map_unordered(2, |x| stream::iter(vec![x; x]));
+    let mut items = block_on(s.collect::<Vec<_>>());
+    items.sort();
+    assert_eq!(items, vec![1, 2, 2, 3, 3, 3, 4, 4, 4, 4]);
+}
+
+#[test]
+fn try_flatten_unordered() {
+    use futures::channel::mpsc;
+    use futures::stream::{StreamExt, TryStreamExt};
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (outer_tx, outer_rx) = mpsc::unbounded();
+    let (tx1, rx1) = mpsc::unbounded::<Result<i32, i32>>();
+    let (tx2, rx2) = mpsc::unbounded::<Result<i32, i32>>();
+    let mut s = outer_rx.try_flatten_unordered(None);
+
+    outer_tx.unbounded_send(Ok(rx1)).unwrap();
+    outer_tx.unbounded_send(Err(-1)).unwrap();
+    outer_tx.unbounded_send(Ok(rx2)).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(Err(-1))));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+
+    tx2.unbounded_send(Ok(2)).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(Ok(2))));
+    tx1.unbounded_send(Err(1)).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(Err(1))));
+
+    drop((outer_tx, tx1, tx2));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
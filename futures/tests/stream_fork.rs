@@ -0,0 +1,128 @@
+#[test]
+fn every_branch_sees_every_item() {
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::stream::{self, StreamExt};
+
+    let a = stream::iter(0..10).fork(3);
+    let b = a.clone();
+    let c = b.clone();
+
+    let (a, b, c) = block_on(future::join3(
+        a.collect::<Vec<_>>(),
+        b.collect::<Vec<_>>(),
+        c.collect::<Vec<_>>(),
+    ));
+    let expected = (0..10).collect::<Vec<_>>();
+    assert_eq!(a, expected);
+    assert_eq!(b, expected);
+    assert_eq!(c, expected);
+}
+
+#[test]
+fn clone_starts_at_current_position() {
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+
+    let mut a = stream::iter(1..=4).fork(4);
+    let mut b = a.clone();
+    assert_eq!(block_on(a.next()), Some(1));
+    assert_eq!(block_on(a.next()), Some(2));
+
+    // `c` has not seen anything `b` has not seen either.
+    let c = b.clone();
+    assert_eq!(block_on(b.next()), Some(1));
+    assert_eq!(block_on(c.collect::<Vec<_>>()), vec![1, 2, 3, 4]);
+    let d = a.clone();
+    assert_eq!(block_on(d.collect::<Vec<_>>()), vec![3, 4]);
+}
+
+#[test]
+fn slowest_branch_applies_backpressure() {
+    use futures::stream::{self, FusedStream, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::new_count_waker;
+
+    let (waker, count) = new_count_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    let mut fast = stream::iter(1..=3).fork(2);
+    let mut slow = fast.clone();
+
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    // Two items are waiting for `slow`, so `fast` has to wait for it.
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Pending);
+    let woken = count.get();
+
+    assert_eq!(slow.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert!(count.get() > woken);
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    // The end of the stream is not known before there is room for an item.
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Pending);
+
+    assert_eq!(slow.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(fast.is_terminated());
+    assert_eq!(slow.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    assert_eq!(slow.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn dropping_a_branch_releases_backpressure() {
+    use futures::stream::{self, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::new_count_waker;
+
+    let (waker, count) = new_count_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    let mut fast = stream::iter(1..=3).fork(1);
+    let slow = fast.clone();
+
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Pending);
+    let woken = count.get();
+
+    drop(slow);
+    assert!(count.get() > woken);
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    assert_eq!(fast.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn branches_on_different_threads() {
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use futures::stream::StreamExt;
+    use std::thread;
+
+    let (tx, rx) = mpsc::unbounded();
+    let a = rx.fork(4);
+    let handles = (0..3)
+        .map(|_| {
+            let branch = a.clone();
+            thread::spawn(move || block_on(branch.collect::<Vec<i32>>()))
+        })
+        .collect::<Vec<_>>();
+    drop(a);
+
+    for i in 0..100 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+
+    let expected = (0..100).collect::<Vec<_>>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
+
+#[test]
+#[should_panic]
+fn zero_capacity_panics() {
+    use futures::stream::{self, StreamExt};
+
+    let _ = stream::iter(vec![1]).fork(0);
+}
@@ -0,0 +1,110 @@
+#[test]
+fn is_terminated() {
+    use futures::stream::{self, FusedStream, StreamExt, StreamMap};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let mut map = StreamMap::new();
+
+    assert!(!map.is_terminated());
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(map.is_terminated());
+
+    map.insert("a", stream::iter(vec![1]));
+
+    assert!(!map.is_empty());
+    assert_eq!(map.len(), 1);
+
+    assert!(!map.is_terminated());
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Ready(Some(("a", 1))));
+    assert!(!map.is_terminated());
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(map.is_terminated());
+}
+
+#[test]
+fn yields_keys_and_end_events() {
+    use futures::channel::mpsc;
+    use futures::stream::stream_map::Event;
+    use futures::stream::StreamMap;
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (a_tx, a_rx) = mpsc::unbounded::<i32>();
+    let (b_tx, b_rx) = mpsc::unbounded::<i32>();
+
+    let mut map = vec![("a", a_rx), ("b", b_rx)].into_iter().collect::<StreamMap<_, _>>();
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Pending);
+
+    b_tx.unbounded_send(1).unwrap();
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Ready(Some(Event::Item("b", 1))));
+    a_tx.unbounded_send(2).unwrap();
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Ready(Some(Event::Item("a", 2))));
+
+    drop(a_tx);
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Ready(Some(Event::Ended("a"))));
+    assert!(!map.contains_key(&"a"));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Pending);
+
+    drop(b_tx);
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Ready(Some(Event::Ended("b"))));
+    assert_eq!(map.poll_next_event(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn remove_stops_stream() {
+    use futures::channel::mpsc;
+    use futures::stream::{StreamExt, StreamMap};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (a_tx, a_rx) = mpsc::unbounded::<i32>();
+    let (b_tx, b_rx) = mpsc::unbounded::<i32>();
+
+    let mut map = StreamMap::new();
+    assert!(map.insert(1, a_rx).is_none());
+    assert!(map.insert(2, b_rx).is_none());
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Pending);
+
+    a_tx.unbounded_send(1).unwrap();
+    let mut a_rx = map.remove(&1).unwrap();
+    assert!(map.remove(&1).is_none());
+
+    // The item was not consumed by the map.
+    assert_eq!(a_rx.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Pending);
+
+    b_tx.unbounded_send(2).unwrap();
+    assert_eq!(map.poll_next_unpin(&mut cx), Poll::Ready(Some((2, 2))));
+
+    // Replacing a stream returns the previous one.
+    let (_c_tx, c_rx) = mpsc::unbounded::<i32>();
+    assert!(map.insert(2, c_rx).is_some());
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn iter() {
+    use futures::executor::block_on_stream;
+    use futures::stream::{self, StreamExt, StreamMap};
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let mut map = StreamMap::new();
+    map.insert(1, stream::iter(vec![1, 2]));
+    map.insert(2, stream::iter(vec![3]));
+
+    let mut keys = map.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec![1, 2]);
+
+    // Skip the first item of each stream.
+    for (_, stream) in map.iter_mut() {
+        assert!(stream.poll_next_unpin(&mut cx).is_ready());
+    }
+    assert_eq!(block_on_stream(map).collect::<Vec<_>>(), vec![(1, 2)]);
+}
@@ -81,3 +81,66 @@ fn works_1() {
     drop((a_tx, b_tx, c_tx));
     assert_eq!(None, stream.next());
 }
+
+#[test]
+fn weighted_distribution() {
+    use futures::executor::block_on_stream;
+    use futures::stream::{repeat, SelectAll, StreamExt};
+
+    let set = SelectAll::with_weights(vec![(repeat(0), 1), (repeat(1), 2), (repeat(2), 5)]);
+    let items = block_on_stream(set.take(8 * 100)).collect::<Vec<_>>();
+
+    let mut counts = [0; 3];
+    for item in &items {
+        counts[*item] += 1;
+    }
+    assert_eq!(counts, [100, 200, 500]);
+
+    // Each round yields every stream's weight in items in a row.
+    assert_eq!(&items[..8], &[0, 1, 1, 2, 2, 2, 2, 2]);
+}
+
+#[test]
+fn weighted_stream_pending_or_ending() {
+    use futures::channel::mpsc;
+    use futures::stream::{FusedStream, SelectAll, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (a_tx, a_rx) = mpsc::unbounded::<i32>();
+    let (b_tx, b_rx) = mpsc::unbounded::<i32>();
+
+    let mut set = SelectAll::new();
+    set.push_weighted(a_rx, 3);
+    set.push(b_rx);
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Pending);
+
+    // `a` runs out of items before it has used up its weight, and has to
+    // wait for wakeups again.
+    a_tx.unbounded_send(1).unwrap();
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Pending);
+    b_tx.unbounded_send(2).unwrap();
+    a_tx.unbounded_send(3).unwrap();
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+
+    // `a` ends while it still has weight left.
+    drop(a_tx);
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Pending);
+    assert_eq!(set.len(), 1);
+    drop(b_tx);
+    assert_eq!(set.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(set.is_terminated());
+}
+
+#[test]
+#[should_panic(expected = "stream weight must be non-zero")]
+fn zero_weight() {
+    use futures::stream::{self, SelectAll};
+
+    let mut set = SelectAll::new();
+    set.push_weighted(stream::empty::<()>(), 0);
+}
@@ -0,0 +1,59 @@
+#[test]
+fn toggle_alternates() {
+    use futures::executor::block_on_stream;
+    use futures::stream::{self, select_with_strategy, PollNext};
+
+    let a = stream::iter(vec![1, 3, 5]);
+    let b = stream::iter(vec![2, 4, 6, 8, 10]);
+    let s = select_with_strategy(a, b, PollNext::toggle);
+
+    assert_eq!(block_on_stream(s).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 8, 10]);
+}
+
+#[test]
+fn left_priority_falls_back_to_right() {
+    use futures::channel::mpsc;
+    use futures::stream::{select_with_strategy, FusedStream, PollNext, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+
+    let mut cx = noop_context();
+    let (left_tx, left_rx) = mpsc::unbounded::<i32>();
+    let (right_tx, right_rx) = mpsc::unbounded::<i32>();
+    let mut s = select_with_strategy(left_rx, right_rx, |_: &mut ()| PollNext::Left);
+
+    left_tx.unbounded_send(1).unwrap();
+    left_tx.unbounded_send(2).unwrap();
+    right_tx.unbounded_send(10).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(10)));
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Pending);
+
+    drop(left_tx);
+    right_tx.unbounded_send(11).unwrap();
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(Some(11)));
+    drop(right_tx);
+    assert_eq!(s.poll_next_unpin(&mut cx), Poll::Ready(None));
+    assert!(s.is_terminated());
+}
+
+#[test]
+fn weighted_distribution() {
+    use futures::executor::block_on_stream;
+    use futures::stream::{repeat, select_with_strategy, PollNext, StreamExt};
+
+    let weighted = |count: &mut u32| {
+        *count = (*count + 1) % 4;
+        if *count == 0 {
+            PollNext::Right
+        } else {
+            PollNext::Left
+        }
+    };
+    let s = select_with_strategy(repeat(0), repeat(1), weighted);
+    let items = block_on_stream(s.take(400)).collect::<Vec<_>>();
+
+    assert_eq!(items.iter().filter(|&&item| item == 0).count(), 300);
+    assert_eq!(&items[..8], &[0, 0, 0, 1, 0, 0, 0, 1]);
+}
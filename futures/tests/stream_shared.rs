@@ -0,0 +1,129 @@
+#[test]
+fn clones_see_every_item() {
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::stream::{self, Replay, StreamExt};
+
+    let a = stream::iter(0..10).shared(Replay::None);
+    let b = a.clone();
+    let c = a.clone();
+
+    let (a, b, c) = block_on(future::join3(
+        a.collect::<Vec<_>>(),
+        b.collect::<Vec<_>>(),
+        c.collect::<Vec<_>>(),
+    ));
+    let expected = (0..10).collect::<Vec<_>>();
+    assert_eq!(a, expected);
+    assert_eq!(b, expected);
+    assert_eq!(c, expected);
+}
+
+#[test]
+fn replay_none_starts_live() {
+    use futures::executor::block_on;
+    use futures::stream::{self, Replay, StreamExt};
+
+    let mut a = stream::iter(1..=5).shared(Replay::None);
+    let mut b = a.clone();
+    assert_eq!(block_on(a.next()), Some(1));
+    assert_eq!(block_on(a.next()), Some(2));
+
+    // `b` has not seen the items yet, but a new clone starts after them.
+    let c = b.clone();
+    assert_eq!(block_on(b.next()), Some(1));
+    assert_eq!(block_on(c.collect::<Vec<_>>()), vec![3, 4, 5]);
+    assert_eq!(block_on(b.collect::<Vec<_>>()), vec![2, 3, 4, 5]);
+    assert_eq!(block_on(a.collect::<Vec<_>>()), vec![3, 4, 5]);
+}
+
+#[test]
+fn replay_last_n() {
+    use futures::executor::block_on;
+    use futures::stream::{self, Replay, StreamExt};
+
+    let mut a = stream::iter(1..=5).shared(Replay::Last(2));
+    assert_eq!(block_on(a.clone().collect::<Vec<_>>()), vec![1, 2, 3, 4, 5]);
+
+    // Only the two most recent items are left to replay.
+    assert_eq!(block_on(a.next()), Some(1));
+    assert_eq!(block_on(a.clone().collect::<Vec<_>>()), vec![4, 5]);
+    assert_eq!(block_on(a.collect::<Vec<_>>()), vec![2, 3, 4, 5]);
+}
+
+#[test]
+fn replay_last_n_before_n_items() {
+    use futures::executor::block_on;
+    use futures::stream::{self, Replay, StreamExt};
+
+    let mut a = stream::iter(1..=3).shared(Replay::Last(5));
+    assert_eq!(block_on(a.next()), Some(1));
+    assert_eq!(block_on(a.next()), Some(2));
+    assert_eq!(block_on(a.clone().collect::<Vec<_>>()), vec![1, 2, 3]);
+}
+
+#[test]
+fn replay_all() {
+    use futures::executor::block_on;
+    use futures::stream::{self, Replay, StreamExt};
+
+    let a = stream::iter(1..=4).shared(Replay::All);
+    let b = a.clone();
+    assert_eq!(block_on(a.collect::<Vec<_>>()), vec![1, 2, 3, 4]);
+    assert_eq!(block_on(b.clone().collect::<Vec<_>>()), vec![1, 2, 3, 4]);
+    assert_eq!(block_on(b.collect::<Vec<_>>()), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn wakes_waiting_clones() {
+    use futures::channel::mpsc;
+    use futures::stream::{Replay, StreamExt};
+    use futures::task::Poll;
+    use futures_test::task::new_count_waker;
+
+    let (waker_a, count_a) = new_count_waker();
+    let (waker_b, count_b) = new_count_waker();
+    let (tx, rx) = mpsc::unbounded();
+    let mut a = rx.shared(Replay::None);
+    let mut b = a.clone();
+
+    assert_eq!(a.poll_next_unpin(&mut std::task::Context::from_waker(&waker_a)), Poll::Pending);
+    assert_eq!(b.poll_next_unpin(&mut std::task::Context::from_waker(&waker_b)), Poll::Pending);
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(count_a, 1);
+    assert_eq!(count_b, 1);
+
+    assert_eq!(b.poll_next_unpin(&mut std::task::Context::from_waker(&waker_b)), Poll::Ready(Some(1)));
+    assert_eq!(a.poll_next_unpin(&mut std::task::Context::from_waker(&waker_a)), Poll::Ready(Some(1)));
+    drop(tx);
+    assert_eq!(a.poll_next_unpin(&mut std::task::Context::from_waker(&waker_a)), Poll::Ready(None));
+    assert_eq!(b.poll_next_unpin(&mut std::task::Context::from_waker(&waker_b)), Poll::Ready(None));
+}
+
+#[test]
+fn clones_on_different_threads() {
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use futures::stream::{Replay, StreamExt};
+    use std::thread;
+
+    let (tx, rx) = mpsc::unbounded();
+    let a = rx.shared(Replay::All);
+    let handles = (0..3)
+        .map(|_| {
+            let clone = a.clone();
+            thread::spawn(move || block_on(clone.collect::<Vec<i32>>()))
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..100 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+
+    let expected = (0..100).collect::<Vec<_>>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+    assert_eq!(block_on(a.collect::<Vec<_>>()), expected);
+}
@@ -0,0 +1,127 @@
+use futures::future::Future;
+use futures::task::{Context, Poll};
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+type Queue = Rc<RefCell<Vec<u32>>>;
+
+// Takes a message out of the queue before it waits, so the message is lost
+// if the future is dropped while waiting.
+struct LoseMessage {
+    queue: Queue,
+    handled: Queue,
+    message: Option<u32>,
+}
+
+impl Future for LoseMessage {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match self.message.take() {
+            None => {
+                let message = self.queue.borrow_mut().pop();
+                self.message = message;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Some(message) => {
+                self.handled.borrow_mut().push(message);
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+// Handles a message which was taken out of the queue when it was created.
+struct Handle {
+    handled: Queue,
+    message: Option<u32>,
+}
+
+impl Future for Handle {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        let message = self.message.take().unwrap();
+        self.handled.borrow_mut().push(message);
+        Poll::Ready(())
+    }
+}
+
+// Throws a message away when it completes.
+struct Discard {
+    queue: Queue,
+}
+
+impl Future for Discard {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        self.queue.borrow_mut().pop();
+        Poll::Ready(())
+    }
+}
+
+// Returns `Poll::Pending` a given number of times before completing.
+struct PendingTimes(usize);
+
+impl Future for PendingTimes {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 == 0 {
+            return Poll::Ready(());
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+fn queue() -> Queue {
+    Rc::new(RefCell::new((0..10).collect()))
+}
+
+#[test]
+fn counts_drop_points() {
+    use futures_test::cancel::check_cancel_safety;
+
+    assert_eq!(check_cancel_safety(|| PendingTimes(0), || true), 1);
+    assert_eq!(check_cancel_safety(|| PendingTimes(3), || true), 4);
+}
+
+#[test]
+#[should_panic(expected = "invariant broken after dropping `test_cancel::Handle` before it was polled")]
+fn reports_drop_before_poll() {
+    use futures_test::cancel::check_cancel_safety;
+
+    let queue = queue();
+    let handled = Queue::default();
+    check_cancel_safety(
+        || Handle { handled: handled.clone(), message: queue.borrow_mut().pop() },
+        || queue.borrow().len() + handled.borrow().len() == 10,
+    );
+}
+
+#[test]
+#[should_panic(expected = "invariant broken after dropping `test_cancel::LoseMessage` at `Poll::Pending` number 1")]
+fn reports_drop_while_pending() {
+    use futures_test::cancel::check_cancel_safety;
+
+    let queue = queue();
+    let handled = Queue::default();
+    check_cancel_safety(
+        || LoseMessage { queue: queue.clone(), handled: handled.clone(), message: None },
+        || queue.borrow().len() + handled.borrow().len() == 10,
+    );
+}
+
+#[test]
+#[should_panic(expected = "invariant broken after running `test_cancel::Discard` to completion")]
+fn reports_completion() {
+    use futures_test::cancel::check_cancel_safety;
+
+    let queue = queue();
+    check_cancel_safety(|| Discard { queue: queue.clone() }, || queue.borrow().len() == 10);
+}
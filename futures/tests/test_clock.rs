@@ -0,0 +1,174 @@
+#[test]
+fn advance_fires_due_timers_in_order() {
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+    use futures_test::time::Clock;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let fired = Rc::new(RefCell::new(Vec::new()));
+
+    for &secs in &[3, 1, 2] {
+        let sleep = clock.sleep(Duration::from_secs(secs));
+        let fired = fired.clone();
+        spawner
+            .spawn_local(async move {
+                sleep.await;
+                fired.borrow_mut().push(secs);
+            })
+            .unwrap();
+    }
+
+    pool.run_until_stalled();
+    assert_eq!(clock.pending_timers(), 3);
+    assert!(fired.borrow().is_empty());
+
+    clock.advance(Duration::from_secs(2));
+    pool.run_until_stalled();
+    assert_eq!(*fired.borrow(), vec![1, 2]);
+
+    clock.advance(Duration::from_secs(1));
+    pool.run_until_stalled();
+    assert_eq!(*fired.borrow(), vec![1, 2, 3]);
+    assert_eq!(clock.pending_timers(), 0);
+}
+
+#[test]
+fn dropped_sleep_is_deregistered() {
+    use futures::future::FutureExt;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    let mut sleep = clock.sleep(Duration::from_secs(1));
+    assert!(sleep.poll_unpin(&mut noop_context()).is_pending());
+    assert_eq!(clock.pending_timers(), 1);
+
+    drop(sleep);
+    assert_eq!(clock.pending_timers(), 0);
+    assert_eq!(clock.next_deadline(), None);
+}
+
+#[test]
+fn reset_moves_deadline() {
+    use futures::future::FutureExt;
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    let mut cx = noop_context();
+    let mut sleep = clock.sleep(Duration::from_secs(1));
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+
+    sleep.reset(clock.now() + Duration::from_secs(5));
+    assert_eq!(clock.pending_timers(), 0);
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+    assert_eq!(clock.next_deadline(), Some(sleep.deadline()));
+
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Pending);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(sleep.poll_unpin(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn advance_wakes_task() {
+    use futures::future::FutureExt;
+    use futures_test::task::new_count_waker;
+    use futures_test::time::Clock;
+    use std::task::Context;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    let (waker, count) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut sleep = clock.sleep(Duration::from_millis(10));
+
+    assert!(sleep.poll_unpin(&mut cx).is_pending());
+    clock.advance(Duration::from_millis(5));
+    assert_eq!(count, 0);
+    clock.advance(Duration::from_millis(5));
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn auto_advance_runs_nested_timers_instantly() {
+    use futures_test::time::Clock;
+    use std::time::{Duration, Instant};
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+
+    let real_start = Instant::now();
+    let start = clock.now();
+    let attempts = clock.block_on(async {
+        let mut attempts = 0;
+        let mut backoff = Duration::from_secs(1);
+        while attempts < 10 {
+            clock.sleep(backoff).await;
+            backoff *= 2;
+            attempts += 1;
+        }
+        attempts
+    });
+
+    assert_eq!(attempts, 10);
+    assert_eq!(clock.now() - start, Duration::from_secs(1023));
+    assert!(real_start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn auto_advance_drives_spawned_tasks() {
+    use futures::channel::oneshot;
+    use futures::executor::LocalPool;
+    use futures::task::LocalSpawnExt;
+    use futures_test::time::Clock;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+    let mut pool = LocalPool::new();
+
+    let (tx, rx) = oneshot::channel();
+    let sleep = clock.sleep(Duration::from_secs(30));
+    pool.spawner()
+        .spawn_local(async move {
+            sleep.await;
+            tx.send(42).unwrap();
+        })
+        .unwrap();
+
+    let start = clock.now();
+    assert_eq!(clock.run_until(&mut pool, rx), Ok(42));
+    assert_eq!(clock.now() - start, Duration::from_secs(30));
+}
+
+#[test]
+fn manual_mode_waits_for_advance_from_another_thread() {
+    use futures_test::time::Clock;
+    use std::thread;
+    use std::time::Duration;
+
+    let clock = Clock::new();
+    let sleep = clock.sleep(Duration::from_secs(1));
+
+    let handle = {
+        let clock = clock.clone();
+        thread::spawn(move || {
+            while clock.pending_timers() == 0 {
+                thread::yield_now();
+            }
+            clock.advance(Duration::from_secs(1));
+        })
+    };
+
+    clock.block_on(sleep);
+    handle.join().unwrap();
+}
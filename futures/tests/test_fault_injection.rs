@@ -0,0 +1,303 @@
+use futures::executor::block_on;
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Cursor};
+use std::io;
+
+// Reads `reader` to the end, returning the length of each read.
+fn read_lens<R: AsyncRead + Unpin>(mut reader: R, buf_len: usize) -> Vec<usize> {
+    block_on(async {
+        let mut buf = vec![0; buf_len];
+        let mut lens = Vec::new();
+        loop {
+            let n = reader.read(&mut buf).await.unwrap();
+            if n == 0 {
+                return lens;
+            }
+            assert!(n <= buf_len);
+            lens.push(n);
+        }
+    })
+}
+
+// Writes `data` to `writer`, returning the length of each write.
+fn write_lens<W: AsyncWrite + Unpin>(mut writer: W, data: &[u8]) -> Vec<usize> {
+    block_on(async {
+        let mut lens = Vec::new();
+        let mut written = 0;
+        while written < data.len() {
+            let n = writer.write(&data[written..]).await.unwrap();
+            assert!(n >= 1 && n <= data.len() - written);
+            written += n;
+            lens.push(n);
+        }
+        lens
+    })
+}
+
+#[test]
+fn fail_after_stream() {
+    use futures::stream::{self, StreamExt};
+    use futures_test::stream::StreamTestExt;
+
+    let mut stream = stream::iter(vec![Ok(1), Ok(2), Ok(3)]).fail_after(2, || Err("reset"));
+    assert_eq!(block_on(stream.next()), Some(Ok(1)));
+    assert_eq!(stream.remaining(), 1);
+    assert_eq!(block_on(stream.next()), Some(Ok(2)));
+    assert_eq!(block_on(stream.next()), Some(Err("reset")));
+    assert_eq!(block_on(stream.next()), None);
+
+    // A stream that ends first never fails.
+    let stream = stream::iter(vec![Ok(1)]).fail_after(2, || Err("reset"));
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), vec![Ok(1)]);
+}
+
+#[test]
+fn fail_after_sink() {
+    use futures::sink::{self, SinkExt};
+    use futures_test::sink::SinkTestExt;
+
+    let mut sink = sink::drain().sink_map_err(|e| match e {}).fail_after_sink(0, || "disconnected");
+    assert_eq!(block_on(sink.send(1)), Err("disconnected"));
+
+    let mut sink = Vec::new().sink_map_err(|e| match e {}).fail_after_sink(3, || "disconnected");
+    for i in 0..3 {
+        block_on(sink.send(i)).unwrap();
+    }
+    assert_eq!(block_on(sink.send(3)), Err("disconnected"));
+    assert_eq!(block_on(sink.send(4)), Err("disconnected"));
+    assert_eq!(sink.get_ref().get_ref(), &[0, 1, 2]);
+}
+
+#[test]
+fn fail_after_read() {
+    use futures_test::io::AsyncReadTestExt;
+
+    let mut reader = Cursor::new(vec![1, 2, 3, 4, 5]).fail_after(3, io::ErrorKind::ConnectionReset);
+    let mut buf = [0; 2];
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 2);
+    assert_eq!(reader.remaining(), 1);
+    // The read is cut short at the failure point.
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 1);
+    assert_eq!(buf[0], 3);
+    let err = block_on(reader.read(&mut buf)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+
+    // Empty reads still succeed.
+    assert_eq!(block_on(reader.read(&mut [])).unwrap(), 0);
+}
+
+#[test]
+fn fail_after_write() {
+    use futures_test::io::AsyncWriteTestExt;
+
+    let mut writer = Cursor::new(Vec::new()).fail_after_write(3, io::ErrorKind::BrokenPipe);
+    assert_eq!(block_on(writer.write(&[1, 2])).unwrap(), 2);
+    assert_eq!(block_on(writer.write(&[3, 4])).unwrap(), 1);
+    let err = block_on(writer.write(&[4])).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(writer.get_ref().get_ref(), &[1, 2, 3]);
+}
+
+#[test]
+fn fail_flush_sink() {
+    use futures::sink::SinkExt;
+    use futures_test::sink::SinkTestExt;
+
+    let mut sink = Vec::new().sink_map_err(|e| match e {}).fail_flush(3, || "flush failed");
+    let results = (0..5).map(|i| block_on(sink.send(i))).collect::<Vec<_>>();
+    assert_eq!(results, vec![Ok(()), Ok(()), Err("flush failed"), Ok(()), Ok(())]);
+    // Only the flush failed, the item was still sent.
+    assert_eq!(sink.get_ref().get_ref(), &[0, 1, 2, 3, 4]);
+}
+
+#[test]
+#[should_panic(expected = "calls to `poll_flush` are counted from 1")]
+fn fail_flush_sink_zero() {
+    use futures::sink::SinkExt;
+    use futures_test::sink::SinkTestExt;
+
+    let _ = Vec::<i32>::new().sink_map_err(|e| match e {}).fail_flush(0, || "flush failed");
+}
+
+#[test]
+fn fail_flush_write() {
+    use futures_test::io::AsyncWriteTestExt;
+
+    let mut writer = Cursor::new(Vec::new()).fail_flush_write(2, io::ErrorKind::Other);
+    let results =
+        (0..4).map(|_| block_on(writer.flush()).map_err(|e| e.kind())).collect::<Vec<_>>();
+    assert_eq!(results, vec![Ok(()), Err(io::ErrorKind::Other), Ok(()), Ok(())]);
+}
+
+#[test]
+fn inject_errors_stream_is_reproducible() {
+    use futures::stream::{self, StreamExt};
+    use futures_test::stream::StreamTestExt;
+
+    let run = |seed, probability| {
+        let stream = stream::iter((0..100).map(Ok::<_, io::Error>))
+            .inject_errors(seed, probability)
+            .map(|res| res.map_err(|e| e.kind()));
+        let results = block_on(stream.collect::<Vec<_>>());
+        let values = results.iter().filter_map(|res| res.as_ref().ok()).copied();
+        assert_eq!(values.collect::<Vec<_>>(), (0..100).collect::<Vec<_>>());
+        for res in &results {
+            if let Err(kind) = res {
+                assert!(*kind == io::ErrorKind::Interrupted || *kind == io::ErrorKind::WouldBlock);
+            }
+        }
+        results
+    };
+
+    assert_eq!(run(7, 0.3), run(7, 0.3));
+    assert_ne!(run(7, 0.3), run(8, 0.3));
+    assert_eq!(run(7, 0.0).len(), 100);
+}
+
+#[test]
+fn inject_errors_counts_errors() {
+    use futures::stream::{self, StreamExt};
+    use futures_test::stream::StreamTestExt;
+
+    let mut stream = stream::iter((0..100).map(Ok::<_, io::Error>)).inject_errors(3, 0.5);
+    let mut errors = 0;
+    while let Some(res) = block_on(stream.next()) {
+        if res.is_err() {
+            errors += 1;
+        }
+    }
+    assert!(errors > 0);
+    assert_eq!(stream.injected(), errors);
+}
+
+#[test]
+fn inject_errors_io_is_reproducible() {
+    use futures::task::Poll;
+    use futures_test::io::{AsyncReadTestExt, AsyncWriteTestExt};
+    use futures_test::task::noop_context;
+    use std::pin::Pin;
+
+    let read_errors = |seed| {
+        let mut cx = noop_context();
+        let mut reader = Cursor::new(vec![0; 50]).inject_errors(seed, 0.5);
+        (0..100)
+            .map(|_| match Pin::new(&mut reader).poll_read(&mut cx, &mut [0; 1]) {
+                Poll::Ready(Ok(_)) => None,
+                Poll::Ready(Err(e)) => Some(e.kind()),
+                Poll::Pending => panic!("`Cursor` is always ready"),
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(read_errors(5), read_errors(5));
+    assert_ne!(read_errors(5), read_errors(6));
+
+    let write_errors = |seed| {
+        let mut cx = noop_context();
+        let mut writer = Cursor::new(Vec::new()).inject_errors_write(seed, 0.5);
+        let errors = (0..100)
+            .map(|i| match Pin::new(&mut writer).poll_write(&mut cx, &[i]) {
+                Poll::Ready(Ok(_)) => None,
+                Poll::Ready(Err(e)) => Some(e.kind()),
+                Poll::Pending => panic!("`Cursor` is always ready"),
+            })
+            .collect::<Vec<_>>();
+        // Calls that get an error are not passed on.
+        let written = writer.get_ref().get_ref().len();
+        assert_eq!(written + writer.injected(), 100);
+        errors
+    };
+    assert_eq!(write_errors(5), write_errors(5));
+}
+
+#[test]
+fn inject_errors_sink_is_reproducible() {
+    use futures::sink::{Sink, SinkExt};
+    use futures::task::Poll;
+    use futures_test::sink::SinkTestExt;
+    use futures_test::task::noop_context;
+    use std::pin::Pin;
+
+    let run = |seed| {
+        let mut cx = noop_context();
+        let mut sink = Vec::new()
+            .sink_map_err(|e| -> io::Error { match e {} })
+            .inject_errors_sink(seed, 0.5);
+        let mut sent = 0;
+        let mut results = Vec::new();
+        while sent < 20 {
+            match Pin::new(&mut sink).poll_ready(&mut cx) {
+                Poll::Ready(Ok(())) => {
+                    Pin::new(&mut sink).start_send(sent).unwrap();
+                    sent += 1;
+                    results.push(None);
+                }
+                Poll::Ready(Err(e)) => results.push(Some(e.kind())),
+                Poll::Pending => panic!("`Vec` is always ready"),
+            }
+        }
+        assert_eq!(sink.get_ref().get_ref(), &(0..20).collect::<Vec<_>>());
+        results
+    };
+    assert_eq!(run(9), run(9));
+}
+
+#[test]
+#[should_panic(expected = "the probability of injecting an error must be between 0 and 1")]
+fn inject_errors_bad_probability() {
+    use futures_test::io::AsyncReadTestExt;
+
+    let _ = Cursor::new(Vec::new()).inject_errors(1, 1.5);
+}
+
+#[test]
+fn short_reads_bounds() {
+    use futures_test::io::AsyncReadTestExt;
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    for &buf_len in &[1, 2, 7, 64, 1000] {
+        let lens = read_lens(Cursor::new(data.clone()).short_reads(42), buf_len);
+        assert_eq!(lens.iter().sum::<usize>(), data.len());
+        assert!(lens.iter().all(|&len| len >= 1 && len <= buf_len));
+    }
+
+    // Reads of every length are picked eventually.
+    let lens = read_lens(Cursor::new(vec![0; 10_000]).short_reads(42), 4);
+    for len in 1..=4 {
+        assert!(lens.contains(&len));
+    }
+
+    let mut reader = Cursor::new(data).short_reads(42);
+    assert_eq!(block_on(reader.read(&mut [])).unwrap(), 0);
+}
+
+#[test]
+fn short_writes_bounds() {
+    use futures_test::io::AsyncWriteTestExt;
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    let mut writer = Cursor::new(Vec::new()).short_writes(42);
+    let lens = write_lens(&mut writer, &data);
+    assert!(lens.len() > 1);
+    assert_eq!(writer.get_ref().get_ref(), &data);
+
+    assert_eq!(block_on(writer.write(&[])).unwrap(), 0);
+}
+
+#[test]
+fn short_io_is_reproducible() {
+    use futures_test::io::{AsyncReadTestExt, AsyncWriteTestExt};
+
+    let data = vec![0; 1000];
+    let reads = read_lens(Cursor::new(data.clone()).short_reads(1), 16);
+    assert_eq!(read_lens(Cursor::new(data.clone()).short_reads(1), 16), reads);
+    assert_ne!(read_lens(Cursor::new(data.clone()).short_reads(2), 16), reads);
+    // The lengths don't depend on how often the wrapped reader is not ready.
+    assert_eq!(read_lens(Cursor::new(data.clone()).interleave_pending().short_reads(1), 16), reads);
+
+    let writes = write_lens(Cursor::new(Vec::new()).short_writes(1), &data);
+    assert_eq!(write_lens(Cursor::new(Vec::new()).short_writes(1), &data), writes);
+    assert_ne!(write_lens(Cursor::new(Vec::new()).short_writes(2), &data), writes);
+    assert_eq!(
+        write_lens(Cursor::new(Vec::new()).interleave_pending_write().short_writes(1), &data),
+        writes,
+    );
+}
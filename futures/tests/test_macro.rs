@@ -0,0 +1,96 @@
+use futures::channel::oneshot;
+use futures::executor::{LocalSpawner, ThreadPool};
+use futures::future::{self, FutureExt};
+use futures::select;
+use futures::task::{LocalSpawnExt, SpawnExt};
+use futures_test::task::TrackingSpawner;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+#[futures_test::test]
+async fn runs_async_fn() {
+    assert_eq!(future::ready(1).await, 1);
+}
+
+#[futures_test::test]
+async fn returns_result() -> Result<(), oneshot::Canceled> {
+    let (tx, rx) = oneshot::channel();
+    tx.send(()).unwrap();
+    rx.await
+}
+
+#[futures_test::test]
+async fn spawns_on_local_pool(spawner: TrackingSpawner<LocalSpawner>) {
+    let test_thread = thread::current().id();
+    let (tx, rx) = oneshot::channel();
+    spawner
+        .spawn_local(async move { tx.send(thread::current().id()).unwrap() })
+        .unwrap();
+    assert_eq!(rx.await.unwrap(), test_thread);
+}
+
+#[futures_test::test(thread_pool(workers = 2), timeout = "10s")]
+async fn spawns_on_thread_pool(spawner: TrackingSpawner<ThreadPool>) {
+    let handles: Vec<_> = (0..8)
+        .map(|i| spawner.spawn_with_handle(async move { i * 2 }).unwrap())
+        .collect();
+    let doubled = future::join_all(handles).await;
+    assert_eq!(doubled, vec![0, 2, 4, 6, 8, 10, 12, 14]);
+}
+
+#[futures_test::test(thread_pool)]
+async fn thread_pool_waits_for_spawned_tasks(spawner: TrackingSpawner<ThreadPool>) {
+    // Still running when the test completes, but done soon after.
+    spawner
+        .spawn(async {
+            thread::sleep(std::time::Duration::from_millis(20));
+        })
+        .unwrap();
+}
+
+#[futures_test::test(timeout = "50ms")]
+#[should_panic(expected = "test timed out after 50ms")]
+async fn times_out() {
+    future::pending::<()>().await;
+}
+
+#[futures_test::test]
+#[should_panic(expected = "1 spawned task was still running at the end of the test")]
+async fn detects_leaked_task(spawner: TrackingSpawner<LocalSpawner>) {
+    spawner.spawn_local(future::pending()).unwrap();
+}
+
+#[futures_test::test(allow_leaks)]
+async fn allows_leaked_task(spawner: TrackingSpawner<LocalSpawner>) {
+    spawner.spawn_local(future::pending()).unwrap();
+}
+
+// Both seeded tests record the branches `select!` picked, and whichever runs
+// second checks that it made the same choices.
+static SEEDED_CHOICES: AtomicU64 = AtomicU64::new(0);
+
+async fn record_choices() {
+    let mut choices = 1u64;
+    for _ in 0..40 {
+        let choice = select! {
+            a = future::ready(0).fuse() => a,
+            b = future::ready(1).fuse() => b,
+            c = future::ready(2).fuse() => c,
+        };
+        choices = choices * 3 + choice;
+    }
+
+    if let Err(other) = SEEDED_CHOICES.compare_exchange(0, choices, Ordering::SeqCst, Ordering::SeqCst) {
+        assert_eq!(other, choices);
+    }
+}
+
+#[futures_test::test(seed = 42)]
+async fn seeded_select_is_reproducible() {
+    record_choices().await;
+}
+
+#[futures_test::test(seed = 42)]
+async fn seeded_select_is_reproducible_again() {
+    record_choices().await;
+}
@@ -0,0 +1,167 @@
+#[test]
+fn reads_and_writes_in_order() {
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().read(b"hello").write(b"ok").read(b" world").build();
+
+    block_on(async {
+        let mut buf = [0; 5];
+        io.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+        io.write_all(b"ok").await.unwrap();
+        let mut rest = Vec::new();
+        io.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b" world");
+    });
+    assert!(io.is_done());
+}
+
+#[test]
+fn partial_reads_and_writes() {
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().read(b"abcdef").write(b"123456").build();
+
+    block_on(async {
+        let mut buf = [0; 4];
+        assert_eq!(io.read(&mut buf).await.unwrap(), 4);
+        assert_eq!(&buf, b"abcd");
+        assert_eq!(io.read(&mut buf).await.unwrap(), 2);
+        assert_eq!(&buf[..2], b"ef");
+
+        io.write_all(b"12").await.unwrap();
+        assert_eq!(io.write(b"34567").await.unwrap(), 4);
+        assert_eq!(io.read(&mut buf).await.unwrap(), 0);
+    });
+}
+
+#[test]
+fn wait_pending_returns_pending_once() {
+    use futures::io::{AsyncRead, AsyncWrite};
+    use futures::task::Poll;
+    use futures_test::io::MockIo;
+    use futures_test::task::new_count_waker;
+    use std::pin::Pin;
+    use std::task::Context;
+
+    let mut io = MockIo::builder().wait_pending().read(b"a").wait_pending().write(b"b").build();
+    let (waker, count) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut buf = [0; 1];
+
+    assert!(Pin::new(&mut io).poll_read(&mut cx, &mut buf).is_pending());
+    assert_eq!(count, 1);
+    assert!(matches_ok(Pin::new(&mut io).poll_read(&mut cx, &mut buf), 1));
+
+    assert!(Pin::new(&mut io).poll_write(&mut cx, b"b").is_pending());
+    assert_eq!(count, 2);
+    assert!(matches_ok(Pin::new(&mut io).poll_write(&mut cx, b"b"), 1));
+
+    fn matches_ok(poll: Poll<std::io::Result<usize>>, n: usize) -> bool {
+        match poll {
+            Poll::Ready(Ok(m)) => m == n,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn read_error() {
+    use futures::executor::block_on;
+    use futures::io::AsyncReadExt;
+    use futures_test::io::MockIo;
+    use std::io::ErrorKind;
+
+    let mut io = MockIo::builder().read_error(ErrorKind::ConnectionReset).read(b"x").build();
+
+    block_on(async {
+        let mut buf = [0; 1];
+        let err = io.read(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+        assert_eq!(io.read(&mut buf).await.unwrap(), 1);
+    });
+}
+
+#[test]
+fn buf_read() {
+    use futures::executor::block_on;
+    use futures::io::AsyncBufReadExt;
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().read(b"first line\nsec").read(b"ond line\n").build();
+
+    block_on(async {
+        let mut line = String::new();
+        io.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "first line\n");
+        line.clear();
+        io.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "second line\n");
+    });
+}
+
+#[test]
+fn reader_waits_for_writer() {
+    use futures::executor::LocalPool;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use futures::task::LocalSpawnExt;
+    use futures_test::io::MockIo;
+
+    let io = MockIo::builder().write(b"request").read(b"response").build();
+    let (mut reader, mut writer) = io.split();
+    let mut pool = LocalPool::new();
+
+    let response = pool
+        .spawner()
+        .spawn_local_with_handle(async move {
+            let mut buf = [0; 8];
+            reader.read_exact(&mut buf).await.unwrap();
+            buf
+        })
+        .unwrap();
+    pool.run_until_stalled();
+
+    pool.run_until(writer.write_all(b"request")).unwrap();
+    assert_eq!(&pool.run_until(response), b"response");
+}
+
+#[test]
+#[should_panic(expected = "MockIo: unexpected write, first difference at byte 1\n    \
+                           expected: b\"ok\\n\"\n     written: b\"on\\n\"")]
+fn unexpected_write_panics() {
+    use futures::executor::block_on;
+    use futures::io::AsyncWriteExt;
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().write(b"hook\n").build();
+    block_on(io.write_all(b"ho")).unwrap();
+    block_on(io.write_all(b"on\n")).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "MockIo: unexpected write after the end of the script")]
+fn write_after_end_panics() {
+    use futures::executor::block_on;
+    use futures::io::AsyncWriteExt;
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().build();
+    block_on(io.write_all(b"extra")).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "MockIo: dropped before the end of the script, 2 steps left: \
+                           [read(b\"lo\"), write(b\"bye\")]")]
+fn unconsumed_script_panics_on_drop() {
+    use futures::executor::block_on;
+    use futures::io::AsyncReadExt;
+    use futures_test::io::MockIo;
+
+    let mut io = MockIo::builder().read(b"hello").write(b"bye").build();
+    let mut buf = [0; 3];
+    block_on(io.read_exact(&mut buf)).unwrap();
+}
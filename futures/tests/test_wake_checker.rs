@@ -0,0 +1,78 @@
+use futures::future::Future;
+use futures::task::{Context, Poll};
+use std::pin::Pin;
+
+// A future that returns `Poll::Pending` once without keeping the waker.
+struct LoseWakeup;
+
+impl Future for LoseWakeup {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+}
+
+// A future that stores a clone of the waker and never drops it.
+struct LeakWaker(bool);
+
+impl Future for LeakWaker {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        let waker = cx.waker().clone();
+        waker.wake_by_ref();
+        std::mem::forget(waker);
+        Poll::Pending
+    }
+}
+
+#[test]
+#[should_panic(expected = "`test_wake_checker::LoseWakeup` returned `Poll::Pending`")]
+fn poll_reports_type_name() {
+    use futures_test::task::WakeChecker;
+
+    let mut fut = LoseWakeup;
+    let _ = WakeChecker::new().poll(Pin::new(&mut fut));
+}
+
+#[test]
+#[should_panic(expected = "`test_wake_checker::LoseWakeup` returned `Poll::Pending`")]
+fn block_on_reports_type_name() {
+    use futures_test::task::WakeChecker;
+
+    WakeChecker::new().block_on(LoseWakeup);
+}
+
+#[test]
+fn counts_leaked_waker() {
+    use futures_test::task::WakeChecker;
+
+    let checker = WakeChecker::new();
+    checker.block_on(LeakWaker(false));
+    assert_eq!(checker.clones(), 1);
+    assert_eq!(checker.drops(), 0);
+    assert_eq!(checker.wakes(), 1);
+    assert!(checker.live_wakers() > 0);
+}
+
+#[test]
+fn counts_dropped_wakers() {
+    use futures::channel::oneshot;
+    use futures_test::task::WakeChecker;
+
+    let checker = WakeChecker::new();
+    let (tx, rx) = oneshot::channel::<i32>();
+    let mut rx = Box::pin(rx);
+    assert!(checker.poll(rx.as_mut()).is_pending());
+    assert_eq!(checker.live_wakers(), 1);
+
+    drop(rx);
+    drop(tx);
+    assert_eq!(checker.clones(), checker.drops());
+    assert_eq!(checker.live_wakers(), 0);
+}
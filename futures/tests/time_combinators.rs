@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn future_timeout_prefers_ready_value() {
+    use futures::future::{self, FutureExt};
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    let fut = future::ready(5).timeout(&clock, secs(1));
+    clock.advance(secs(10));
+    assert_eq!(clock.block_on(fut), Ok(5));
+}
+
+#[test]
+fn future_timeout_manual_clock() {
+    use futures::channel::oneshot;
+    use futures::future::FutureExt;
+    use futures::task::Poll;
+    use futures::time::TimedOut;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    let mut cx = noop_context();
+    let (_tx, rx) = oneshot::channel::<()>();
+    let mut fut = rx.timeout(&clock, secs(5));
+
+    assert_eq!(fut.poll_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(4));
+    assert_eq!(fut.poll_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready(Err(TimedOut)));
+}
+
+#[test]
+fn future_delay() {
+    use futures::future::FutureExt;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+    let start = clock.now();
+    let polled_at = clock.block_on(async { clock.now() }.delay(&clock, secs(7)));
+    assert_eq!(polled_at - start, secs(7));
+}
+
+#[test]
+fn stream_timeout_restarts_deadline() {
+    use futures::stream::{self, StreamExt};
+    use futures::time::TimedOut;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+
+    let stream = stream::iter(vec![1u64, 5, 2])
+        .then(|wait| {
+            let sleep = clock.sleep(secs(wait));
+            async move {
+                sleep.await;
+                wait
+            }
+        })
+        .timeout(&clock, secs(2));
+
+    assert_eq!(
+        clock.block_on(stream.collect::<Vec<_>>()),
+        vec![Ok(1), Err(TimedOut), Err(TimedOut), Ok(5), Ok(2)]
+    );
+}
+
+#[test]
+fn throttle_spaces_items() {
+    use futures::stream::{self, StreamExt};
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+    let start = clock.now();
+
+    let stream = stream::iter(0..4).throttle(&clock, secs(3));
+    let times = clock.block_on(stream.map(|_| clock.now() - start).collect::<Vec<_>>());
+    assert_eq!(times, vec![secs(0), secs(3), secs(6), secs(9)]);
+    assert_eq!(clock.now() - start, secs(9));
+}
+
+#[test]
+fn debounce_yields_after_quiet_period() {
+    use futures::channel::mpsc;
+    use futures::stream::StreamExt;
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    let mut cx = noop_context();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = rx.debounce(&clock, secs(2));
+
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+
+    tx.unbounded_send(3).unwrap();
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn sample_skips_empty_periods() {
+    use futures::channel::mpsc;
+    use futures::stream::StreamExt;
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    let mut cx = noop_context();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = rx.sample(&clock, secs(1));
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+
+    clock.advance(secs(3));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    tx.unbounded_send(3).unwrap();
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn chunks_timeout_flushes_full_and_late_chunks() {
+    use futures::channel::mpsc;
+    use futures::stream::StreamExt;
+    use futures::task::Poll;
+    use futures_test::task::noop_context;
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    let mut cx = noop_context();
+    let (tx, rx) = mpsc::unbounded();
+    let mut stream = rx.chunks_timeout(2, &clock, secs(5));
+
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    for i in 0..3 {
+        tx.unbounded_send(i).unwrap();
+    }
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![0, 1])));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(4));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+    clock.advance(secs(1));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![2])));
+
+    tx.unbounded_send(3).unwrap();
+    drop(tx);
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(vec![3])));
+    assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn stream_delay() {
+    use futures::stream::{self, StreamExt};
+    use futures_test::time::Clock;
+
+    let clock = Clock::new();
+    clock.set_auto_advance(true);
+    let start = clock.now();
+
+    let stream = stream::iter(1..=2).delay(&clock, secs(4));
+    let items = clock.block_on(stream.map(|x| (x, clock.now() - start)).collect::<Vec<_>>());
+    assert_eq!(items, vec![(1, secs(4)), (2, secs(4))]);
+}
+
+#[test]
+fn system_timer() {
+    use futures::executor::{block_on, SystemTimer};
+    use futures::stream::{self, StreamExt};
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let stream = stream::iter(0..3).throttle(SystemTimer, Duration::from_millis(10));
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), vec![0, 1, 2]);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
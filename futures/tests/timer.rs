@@ -0,0 +1,177 @@
+use std::time::{Duration, Instant};
+
+#[test]
+fn delay_waits_until_deadline() {
+    use futures::executor::{block_on, Delay};
+
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(20);
+    block_on(Delay::new_at(deadline));
+    assert!(Instant::now() >= deadline);
+}
+
+#[test]
+fn delay_in_the_past_is_ready() {
+    use futures::executor::Delay;
+    use futures::future::FutureExt;
+    use futures::task::Poll;
+    use futures_test::task::panic_context;
+
+    let mut delay = Delay::new_at(Instant::now() - Duration::from_secs(1));
+    assert_eq!(delay.poll_unpin(&mut panic_context()), Poll::Ready(()));
+    assert!(delay.is_elapsed());
+}
+
+#[test]
+fn delays_complete_in_deadline_order() {
+    use futures::executor::{block_on, Delay};
+    use futures::stream::{FuturesUnordered, StreamExt};
+
+    let start = Instant::now();
+    let delays: FuturesUnordered<_> = [30u64, 10, 20]
+        .iter()
+        .map(|&ms| async move {
+            Delay::new_at(start + Duration::from_millis(ms)).await;
+            ms
+        })
+        .collect();
+
+    assert_eq!(block_on(delays.collect::<Vec<_>>()), vec![10, 20, 30]);
+}
+
+#[test]
+fn delays_cascade_between_wheel_levels() {
+    use futures::executor::{block_on, Delay};
+    use futures::stream::{FuturesUnordered, StreamExt};
+
+    // Long enough to start out on the coarser levels of the wheel.
+    let start = Instant::now();
+    let delays: FuturesUnordered<_> = [700u64, 70, 130, 4200]
+        .iter()
+        .map(|&ms| async move {
+            let deadline = start + Duration::from_millis(ms);
+            Delay::new_at(deadline).await;
+            assert!(Instant::now() >= deadline);
+            ms
+        })
+        .collect();
+
+    assert_eq!(block_on(delays.collect::<Vec<_>>()), vec![70, 130, 700, 4200]);
+}
+
+#[test]
+fn delay_works_on_thread_pool() {
+    use futures::channel::oneshot;
+    use futures::executor::{block_on, Delay, ThreadPool};
+
+    let pool = ThreadPool::new().unwrap();
+    let (tx, rx) = oneshot::channel();
+    pool.spawn_ok(async move {
+        Delay::new(Duration::from_millis(10)).await;
+        tx.send(()).unwrap();
+    });
+    block_on(rx).unwrap();
+}
+
+#[test]
+fn delay_reset() {
+    use futures::executor::{block_on, Delay};
+
+    let start = Instant::now();
+    let mut delay = Delay::new(Duration::from_millis(5));
+    block_on(&mut delay);
+    assert!(delay.is_elapsed());
+
+    delay.reset(start + Duration::from_millis(40));
+    assert!(!delay.is_elapsed());
+    block_on(&mut delay);
+    assert!(start.elapsed() >= Duration::from_millis(40));
+}
+
+#[test]
+fn dropped_delay_does_not_wake() {
+    use futures::executor::{block_on, Delay};
+    use futures::future::FutureExt;
+    use futures_test::task::new_count_waker;
+    use std::task::Context;
+
+    let (waker, count) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut delay = Delay::new(Duration::from_millis(10));
+    assert!(delay.poll_unpin(&mut cx).is_pending());
+    drop(delay);
+
+    block_on(Delay::new(Duration::from_millis(50)));
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn many_pending_delays() {
+    use futures::executor::{block_on, Delay};
+    use futures::future::FutureExt;
+    use futures::stream::{FuturesUnordered, StreamExt};
+    use futures_test::task::noop_context;
+
+    // A large population of far-future timers, most of which are cancelled,
+    // must not disturb the ones that actually fire.
+    let mut cx = noop_context();
+    let mut far: Vec<_> = (0..100_000u64)
+        .map(|i| {
+            let mut delay = Delay::new(Duration::from_secs(3600 + i));
+            assert!(delay.poll_unpin(&mut cx).is_pending());
+            delay
+        })
+        .collect();
+    far.truncate(10);
+
+    let near: FuturesUnordered<_> = (0..1000u64)
+        .map(|i| Delay::new(Duration::from_millis(i % 50)))
+        .collect();
+    assert_eq!(block_on(near.collect::<Vec<()>>()).len(), 1000);
+    assert!(far.iter().all(|delay| !delay.is_elapsed()));
+}
+
+#[test]
+fn interval_burst_catches_up() {
+    use futures::executor::{block_on_stream, Interval};
+
+    let period = Duration::from_millis(10);
+    let start = Instant::now() - Duration::from_millis(35);
+    let mut ticks = block_on_stream(Interval::new_at(start, period));
+
+    let expected: Vec<_> = (0..5).map(|i| start + period * i).collect();
+    let actual: Vec<_> = ticks.by_ref().take(5).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn interval_skip_missed_ticks() {
+    use futures::executor::{block_on_stream, Interval, MissedTickBehavior};
+
+    let period = Duration::from_millis(10);
+    let start = Instant::now() - Duration::from_millis(35);
+    let mut interval = Interval::new_at(start, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut ticks = block_on_stream(interval);
+
+    assert_eq!(ticks.next(), Some(start));
+    assert_eq!(ticks.next(), Some(start + period * 4));
+}
+
+#[test]
+fn interval_delay_missed_ticks() {
+    use futures::executor::{block_on_stream, Interval, MissedTickBehavior};
+
+    let period = Duration::from_millis(10);
+    let start = Instant::now() - Duration::from_millis(35);
+    let mut interval = Interval::new_at(start, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut ticks = block_on_stream(interval);
+
+    assert_eq!(ticks.next(), Some(start));
+    let observed = Instant::now();
+    let second = ticks.next().unwrap();
+    assert!(second >= observed + period - Duration::from_millis(5));
+    assert!(second - start > period * 4);
+}